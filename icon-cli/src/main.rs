@@ -1,13 +1,41 @@
-use clap::Parser;
+mod profiles;
+
+use clap::{Args, Parser, ValueEnum};
 use icon_engine::{
-    CustomStyles, StylePreset,
+    Alignment, CustomStyles, FitMode, IconEngineError, Insets, Placement, SafeZone, ShadowColors, StylePreset,
+    animation::{Animation, AnimationKind, AnimationSyntax, AnimationTrigger},
+    contrast::{check_contrast, validate_min_contrast},
+    palette::{Palette, PaletteFormat},
+    containers::{encode_icns, encode_ico, ICNS_SIZES, ICO_SIZES},
+    encoders::{encode_avif, encode_webp, EncodeOptions},
+    atlas::pack_atlas,
+    components::{component_name, generate_component, index_file, Component, ComponentTarget},
+    font::{assign_codepoints, build_ttf, codepoint_map_to_json, font_css, parse_codepoint_map, ttf_to_woff2, FontGlyph},
+    print::{to_eps, to_pdf},
+    raster::{encode_png, render, render_png, RasterImage},
+    sprite::{build_sprite, sanitize_id, sprite_preview_html, unique_ids},
+    style_generator::shadow_colors,
     gradient_parser::parse_gradient,
+    monogram::generate_monogram,
+    optimize::{optimize, OptimizeLevel},
+    overlay::{Overlay, OverlayShape},
+    pattern::{BasePattern, PatternKind},
+    glyph_stack::GlyphLayer,
+    path_data::Transform,
+    svg_processor::{parse_svg, tight_coverage, validate_placement},
+    native::{to_vector_drawable, to_xaml, NativeIcon},
     generate_icon,
+    states::{generate_stateful_icon, state_styles, IconState},
+    theme::{generate_adaptive_icon, Theme, ThemeConfig, ThemeOverrides},
 };
 use rayon::prelude::*;
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use anyhow::{Context, Result};
+use profiles::Profile;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
@@ -29,34 +57,643 @@ enum Commands {
         #[arg(short, long, value_name = "PATH")]
         output: PathBuf,
 
-        /// The style preset to apply.
+        #[command(flatten)]
+        style_args: StyleArgs,
+
+        /// How to treat icons whose foreground is below `--min-contrast`.
+        #[arg(long, value_enum, default_value_t = ContrastCheck::Off)]
+        contrast_check: ContrastCheck,
+
+        /// The output format (repeatable), e.g. `--format svg --format png`.
+        #[arg(long = "format", value_enum, default_values_t = [OutputFormat::Svg])]
+        formats: Vec<OutputFormat>,
+
+        /// The pixel size of the longer side of raster outputs. Defaults to the canvas size.
+        #[arg(long, value_name = "PIXELS")]
+        raster_size: Option<u32>,
+
+        /// The scale factor for raster outputs, e.g. 2 for @2x assets.
+        #[arg(long, default_value_t = 1.0)]
+        scale: f32,
+
+        /// The quality (0-100) of lossy WebP and AVIF outputs.
+        #[arg(long, default_value_t = 90, value_parser = clap::value_parser!(u8).range(0..=100))]
+        quality: u8,
+
+        /// Writes WebP with the lossless codec after dropping this many low
+        /// bits (1-7) from each color channel, instead of with the lossy
+        /// codec at `--quality`. 0 keeps lossy WebP.
+        #[arg(long, value_name = "BITS", default_value_t = 0, value_parser = clap::value_parser!(u8).range(0..=7))]
+        webp_quantize: u8,
+
+        /// Writes WebP with the exact lossless codec and AVIF at quality 100.
+        /// AVIF stays near-lossless: its encoder has no true lossless mode.
         #[arg(long)]
-        style: StylePreset,
+        lossless: bool,
 
-        /// An optional CSS gradient for the base.
+        /// Optimizes generated SVGs and sprite symbols at the given level.
+        #[arg(long, value_enum, default_value_t = OptimizeLevel::Off)]
+        optimize: OptimizeLevel,
+
+        /// Combines all generated SVGs into a single `sprite.svg` of `<symbol>`s
+        /// instead of writing one SVG per icon.
         #[arg(long)]
-        gradient: Option<String>,
+        sprite: bool,
+
+        /// Also writes `sprite.html`, a page previewing every symbol.
+        #[arg(long, requires = "sprite")]
+        sprite_preview: bool,
 
-        /// The color for the foreground icon in hex format (e.g., #RRGGBB).
-        #[arg(long, default_value = "#333333")]
-        color: String,
+        #[command(flatten)]
+        theme_args: Box<ThemeArgs>,
 
-        /// The width of the final SVG canvas.
-        #[arg(long, default_value_t = 128)]
-        width: u32,
+        /// Generates a variant per interaction state (repeatable), e.g.
+        /// `--state hover --state pressed`.
+        #[arg(long = "state", value_name = "STATE", conflicts_with = "adaptive")]
+        states: Vec<IconState>,
 
-        /// The height of the final SVG canvas.
-        #[arg(long, default_value_t = 128)]
-        height: u32,
+        /// Writes the states into a single SVG that switches between them with
+        /// CSS, following the SVG's or its button's `:hover`, `:active` etc.
+        #[arg(long, requires = "states")]
+        stateful: bool,
+    },
+    /// Builds platform icon bundles (iOS, Android, favicon, PWA) from one source icon.
+    Bundle {
+        /// Path to the source SVG icon.
+        #[arg(short, long, value_name = "PATH")]
+        source: PathBuf,
+
+        /// The directory where the bundles will be saved.
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
 
-        /// The corner radius for the base shape.
-        #[arg(long, default_value_t = 25.0)]
-        corner_radius: f32,
+        /// The platform profile to build (repeatable).
+        #[arg(long = "profile", value_enum, required = true)]
+        profiles: Vec<Profile>,
 
-        /// The padding between the icon and the edge of the base.
-        #[arg(long, default_value_t = 16)]
-        padding: u32,
+        #[command(flatten)]
+        style_args: StyleArgs,
     },
+    /// Packs a directory of icons into a PNG atlas with JSON and CSS coordinate maps.
+    Atlas {
+        /// Path to a folder containing the source SVG icons.
+        #[arg(short, long, value_name = "PATH")]
+        source: PathBuf,
+
+        /// The directory where the atlas files will be saved.
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// The base file name of the atlas (`<name>.png`, `<name>.json`, `<name>.css`).
+        #[arg(long, default_value = "atlas")]
+        name: String,
+
+        /// The pixel size of each icon. Defaults to the canvas size.
+        #[arg(long, value_name = "PIXELS")]
+        raster_size: Option<u32>,
+
+        /// Transparent pixels between icons.
+        #[arg(long, default_value_t = 2)]
+        spacing: u32,
+
+        /// The maximum width of the atlas in pixels.
+        #[arg(long, default_value_t = 4096)]
+        max_width: u32,
+
+        #[command(flatten)]
+        style_args: StyleArgs,
+    },
+    /// Builds an icon font (TTF and WOFF2) with a CSS class per icon from a directory of icons.
+    Font {
+        /// Path to a folder containing the source SVG icons.
+        #[arg(short, long, value_name = "PATH")]
+        source: PathBuf,
+
+        /// The directory where the font files will be saved.
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// The font family and base file name (`<name>.ttf`, `<name>.woff2`, `<name>.css`).
+        #[arg(long, default_value = "icons")]
+        name: String,
+
+        /// The JSON file mapping icon names to codepoints. New icons are added to it so
+        /// codepoints stay stable across runs. Defaults to `<output>/<name>.json`.
+        #[arg(long, value_name = "PATH")]
+        codepoints: Option<PathBuf>,
+
+        /// The CSS class prefix, e.g. `icon` for `<i class="icon icon-home">`.
+        #[arg(long, default_value = "icon")]
+        class_prefix: String,
+    },
+    /// Wraps a directory of icons in framework components, one folder per target.
+    Components {
+        /// Path to a folder containing the source SVG icons.
+        #[arg(short, long, value_name = "PATH")]
+        source: PathBuf,
+
+        /// The directory where a folder per target will be created.
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// The target to generate (repeatable), e.g. `--target react --target swiftui`.
+        #[arg(long = "target", value_enum, required = true)]
+        targets: Vec<ComponentTarget>,
+
+        #[command(flatten)]
+        style_args: StyleArgs,
+    },
+    /// Generates an avatar or placeholder icon from one to three letters.
+    Monogram {
+        /// The letters to draw, e.g. "JD".
+        #[arg(long)]
+        text: String,
+
+        /// The TrueType or OpenType font to set the letters in.
+        #[arg(long, value_name = "PATH")]
+        font: PathBuf,
+
+        /// The file to write; a `.png` extension renders a PNG, anything else an SVG.
+        #[arg(short, long, value_name = "PATH")]
+        output: PathBuf,
+
+        /// The pixel size of the longer side of a PNG. Defaults to the canvas size.
+        #[arg(long, value_name = "PIXELS")]
+        raster_size: Option<u32>,
+
+        #[command(flatten)]
+        style_args: StyleArgs,
+    },
+}
+
+/// The theme variants of `mass-export` and their per-theme colors.
+#[derive(Args, Debug)]
+struct ThemeArgs {
+    /// Generates a variant per theme (repeatable), e.g. `--theme light --theme dark`.
+    #[arg(long = "theme", value_name = "THEME")]
+    themes: Vec<Theme>,
+
+    /// Writes a single SVG that switches to the dark theme via `prefers-color-scheme`.
+    #[arg(long, conflicts_with = "themes")]
+    adaptive: bool,
+
+    /// Overrides the base color of the light theme.
+    #[arg(long, value_name = "COLOR")]
+    light_base: Option<String>,
+
+    /// Overrides the foreground color of the light theme.
+    #[arg(long, value_name = "COLOR")]
+    light_color: Option<String>,
+
+    /// Overrides the base color of the dark theme.
+    #[arg(long, value_name = "COLOR")]
+    dark_base: Option<String>,
+
+    /// Overrides the foreground color of the dark theme.
+    #[arg(long, value_name = "COLOR")]
+    dark_color: Option<String>,
+
+    /// Overrides the neumorphism shadow cast towards the bottom-right in the light theme.
+    #[arg(long, value_name = "COLOR")]
+    light_shadow_dark: Option<String>,
+
+    /// Overrides the neumorphism highlight cast towards the top-left in the light theme.
+    #[arg(long, value_name = "COLOR")]
+    light_shadow_light: Option<String>,
+
+    /// Overrides the neumorphism shadow cast towards the bottom-right in the dark theme.
+    #[arg(long, value_name = "COLOR")]
+    dark_shadow_dark: Option<String>,
+
+    /// Overrides the neumorphism highlight cast towards the top-left in the dark theme.
+    #[arg(long, value_name = "COLOR")]
+    dark_shadow_light: Option<String>,
+}
+
+impl ThemeArgs {
+    /// Builds the per-theme overrides; a single shadow override keeps the
+    /// theme's derived color for the other one.
+    fn config(&self, styles: &CustomStyles, style: StylePreset, palette: Option<&Palette>) -> Result<ThemeConfig> {
+        let mut config = ThemeConfig {
+            light: ThemeOverrides {
+                base_color: self.light_base.clone(),
+                icon_color: self.light_color.clone(),
+                ..ThemeOverrides::default()
+            },
+            dark: ThemeOverrides {
+                base_color: self.dark_base.clone(),
+                icon_color: self.dark_color.clone(),
+                ..ThemeOverrides::default()
+            },
+        };
+
+        for (theme, dark, light) in [
+            (Theme::Light, &self.light_shadow_dark, &self.light_shadow_light),
+            (Theme::Dark, &self.dark_shadow_dark, &self.dark_shadow_light),
+        ] {
+            if dark.is_none() && light.is_none() {
+                continue;
+            }
+            let defaults = shadow_colors(&config.styles_for(styles, style, theme));
+            let overrides = match theme {
+                Theme::Light => &mut config.light,
+                Theme::Dark => &mut config.dark,
+            };
+            overrides.shadow_colors = Some(ShadowColors {
+                dark: dark.clone().unwrap_or(defaults.dark),
+                light: light.clone().unwrap_or(defaults.light),
+            });
+        }
+
+        let empty = Palette::new();
+        let resolve = |color: &mut String| -> Result<()> {
+            *color = match palette {
+                Some(palette) => palette.resolve(color)?,
+                None => empty.resolve(color).context(NO_PALETTE)?,
+            };
+            Ok(())
+        };
+        for overrides in [&mut config.light, &mut config.dark] {
+            for color in [&mut overrides.base_color, &mut overrides.icon_color].into_iter().flatten() {
+                resolve(color)?;
+            }
+            if let Some(shadows) = &mut overrides.shadow_colors {
+                for color in [&mut shadows.dark, &mut shadows.light] {
+                    resolve(color)?;
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// The styling options shared by every command that generates icons.
+#[derive(Args, Debug)]
+struct StyleArgs {
+    /// The style preset to apply.
+    #[arg(long)]
+    style: StylePreset,
+
+    /// An optional CSS gradient for the base.
+    #[arg(long)]
+    gradient: Option<String>,
+
+    /// Textures the base. The spec is a kind (`noise`, `dots`, `stripes`,
+    /// `checkerboard` or `image:PATH` to a PNG) followed by comma-separated
+    /// options: `color`, `scale` (the tile size in pixels) and `opacity`,
+    /// e.g. "dots,color=#ffffff,scale=10,opacity=0.2".
+    #[arg(long, value_name = "SPEC")]
+    pattern: Option<String>,
+
+    /// The color for the foreground icon in hex format (e.g., #RRGGBB), or
+    /// `auto` to pick a color that contrasts with the base.
+    #[arg(long, default_value = "#333333")]
+    color: String,
+
+    /// The minimum WCAG contrast ratio between the foreground and the base.
+    #[arg(long, default_value_t = 3.0)]
+    min_contrast: f32,
+
+    /// Overrides the preset's base color.
+    #[arg(long, value_name = "COLOR")]
+    base_color: Option<String>,
+
+    /// Overrides the color of the neumorphism drop shadow.
+    #[arg(long, value_name = "COLOR")]
+    shadow_dark: Option<String>,
+
+    /// Overrides the color of the neumorphism highlight.
+    #[arg(long, value_name = "COLOR")]
+    shadow_light: Option<String>,
+
+    /// A palette (JSON, CSS, GPL or ASE) whose entries colors can reference as `@name`.
+    #[arg(long, value_name = "PATH")]
+    palette: Option<PathBuf>,
+
+    /// The width of the final SVG canvas.
+    #[arg(long, default_value_t = 128)]
+    width: u32,
+
+    /// The height of the final SVG canvas.
+    #[arg(long, default_value_t = 128)]
+    height: u32,
+
+    /// The corner radius for the base shape.
+    #[arg(long, default_value_t = 25.0)]
+    corner_radius: f32,
+
+    /// The padding between the icon and the edge of the base.
+    #[arg(long, default_value_t = 16)]
+    padding: u32,
+
+    /// Per-side padding as CSS shorthand (top, right, bottom, left), in pixels
+    /// or percent of the canvas, e.g. "10%" or "8 12 16 12". Overrides `--padding`.
+    #[arg(long, value_name = "LENGTHS")]
+    insets: Option<String>,
+
+    /// Fits the glyph into a platform's safe zone. Overrides `--padding` and `--insets`.
+    #[arg(long, value_enum)]
+    safe_zone: Option<SafeZone>,
+
+    /// Applies the glyph's fit to its path coordinates instead of wrapping it
+    /// in a transformed group (SVG and VectorDrawable outputs).
+    #[arg(long)]
+    bake_transform: bool,
+
+    /// How the glyph is fitted into the padded area.
+    #[arg(long, value_enum, default_value_t = FitMode::ViewBox)]
+    fit: FitMode,
+
+    /// The share of the padded area the glyph should cover with `--fit visual-area`.
+    /// `mass-export` defaults to the median of the icon set.
+    #[arg(long, value_name = "FRACTION")]
+    target_coverage: Option<f32>,
+
+    /// Where the glyph sits within the padded area.
+    #[arg(long, value_enum, default_value_t = Alignment::Center)]
+    align: Alignment,
+
+    /// Moves the glyph right by this many pixels after alignment.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    offset_x: f32,
+
+    /// Moves the glyph down by this many pixels after alignment.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    offset_y: f32,
+
+    /// Multiplies the fitted size of the glyph.
+    #[arg(long, default_value_t = 1.0)]
+    glyph_scale: f32,
+
+    /// Sizes the glyph's longer side to this many pixels instead of fitting it to the padding.
+    #[arg(long, value_name = "PIXELS")]
+    glyph_size: Option<f32>,
+
+    /// Rotates the glyph clockwise by this many degrees.
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    rotation: f32,
+
+    /// Mirrors the glyph horizontally, e.g. for right-to-left variants.
+    #[arg(long)]
+    flip_horizontal: bool,
+
+    /// Mirrors the glyph vertically.
+    #[arg(long)]
+    flip_vertical: bool,
+
+    /// Draws a badge over the icon; repeat for several. The spec is a kind
+    /// (`dot`, `badge:TEXT`, `ribbon:TEXT` or `icon:PATH`) followed by
+    /// comma-separated options: `color`, `text-color`, `anchor`, `size` and
+    /// `cutout`, e.g. "badge:3,anchor=top-right,cutout=4".
+    #[arg(long = "overlay", value_name = "SPEC")]
+    overlays: Vec<String>,
+
+    /// Stacks another source SVG above the glyph; repeat for several, bottom
+    /// to top. The spec is a path followed by comma-separated options:
+    /// `transform` (an SVG transform list in the glyph's viewBox), `color`,
+    /// `opacity` and `knockout` (a gap in pixels cut out of the layers below),
+    /// e.g. "lock.svg,transform=translate(12, 12) scale(.5),knockout=3".
+    #[arg(long = "layer", value_name = "SPEC")]
+    glyph_layers: Vec<String>,
+
+    /// Animates SVG outputs; the icon without the animation stays valid.
+    #[arg(long, value_enum)]
+    animation: Option<AnimationKind>,
+
+    /// Writes the animation as CSS keyframes or SMIL elements.
+    #[arg(long, value_enum, default_value_t = AnimationSyntax::Css)]
+    animation_syntax: AnimationSyntax,
+
+    /// Plays the animation on load or only on hover.
+    #[arg(long, value_enum, default_value_t = AnimationTrigger::Always)]
+    animate_on: AnimationTrigger,
+
+    /// The length of one animation cycle in seconds. Defaults to the animation's usual pace.
+    #[arg(long, value_name = "SECONDS")]
+    animation_duration: Option<f32>,
+}
+
+impl StyleArgs {
+    /// Builds the engine styles, returning the loaded palette (if any) so
+    /// other color options can be resolved against it.
+    fn build(&self) -> Result<(CustomStyles, Option<Palette>)> {
+        validate_min_contrast(self.min_contrast)?;
+        let gradient = self.gradient.as_deref().map(parse_gradient).transpose()?;
+        let insets = self.insets.as_deref().map(Insets::parse).transpose()?;
+        let pattern = self
+            .pattern
+            .as_deref()
+            .map(|spec| parse_pattern(spec, self.width.max(self.height)))
+            .transpose()?;
+        let overlays = self
+            .overlays
+            .iter()
+            .map(|spec| parse_overlay(spec, self.width.min(self.height)))
+            .collect::<Result<Vec<_>>>()?;
+        let glyph_layers = self
+            .glyph_layers
+            .iter()
+            .map(|spec| parse_glyph_layer(spec))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut styles = CustomStyles {
+            width: self.width,
+            height: self.height,
+            corner_radius: self.corner_radius,
+            padding: self.padding,
+            insets,
+            safe_zone: self.safe_zone,
+            icon_color: self.color.clone(),
+            gradient,
+            pattern,
+            min_contrast: self.min_contrast,
+            base_color: self.base_color.clone(),
+            bake_transform: self.bake_transform,
+            fit: self.fit,
+            placement: Placement {
+                align: self.align,
+                offset: (self.offset_x, self.offset_y),
+                scale: self.glyph_scale,
+                size: self.glyph_size,
+                rotation: self.rotation,
+                flip_horizontal: self.flip_horizontal,
+                flip_vertical: self.flip_vertical,
+            },
+            overlays,
+            glyph_layers,
+            animation: self.animation.map(|kind| {
+                let animation = Animation::new(kind);
+                Animation {
+                    syntax: self.animation_syntax,
+                    trigger: self.animate_on,
+                    duration: self.animation_duration.unwrap_or(animation.duration),
+                    ..animation
+                }
+            }),
+            ..CustomStyles::default()
+        };
+        if let Some(target_coverage) = self.target_coverage {
+            styles.target_coverage = target_coverage;
+        }
+        validate_placement(&styles)?;
+        if self.shadow_dark.is_some() || self.shadow_light.is_some() {
+            let defaults = shadow_colors(&styles);
+            styles.shadow_colors = Some(ShadowColors {
+                dark: self.shadow_dark.clone().unwrap_or(defaults.dark),
+                light: self.shadow_light.clone().unwrap_or(defaults.light),
+            });
+        }
+
+        let palette = self.palette.as_deref().map(load_palette).transpose()?;
+        styles = match (&palette, &self.palette) {
+            (Some(palette), Some(path)) => {
+                log::info!("Loaded {} colors from palette '{}'", palette.len(), path.display());
+                palette.apply(&styles)?
+            }
+            // Resolving against an empty palette rejects any `@name` left over.
+            _ => Palette::new().apply(&styles).context(NO_PALETTE)?,
+        };
+
+        Ok((styles, palette))
+    }
+}
+
+/// The file formats `mass-export` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    Svg,
+    Png,
+    /// A Windows icon holding every size in `ICO_SIZES`.
+    Ico,
+    /// A macOS icon holding every size in `ICNS_SIZES`.
+    Icns,
+    Webp,
+    Avif,
+    /// An Android `<vector>` drawable.
+    VectorDrawable,
+    /// A WPF/WinUI XAML resource dictionary with the glyph geometry and a `DrawingImage`.
+    Xaml,
+    /// A single-page vector PDF; shadows are embedded as soft-masked images.
+    Pdf,
+    /// Encapsulated PostScript, composited over white without shadows.
+    Eps,
+}
+
+impl OutputFormat {
+    /// Whether the format holds rendered pixels rather than vector data.
+    fn is_raster(self) -> bool {
+        matches!(
+            self,
+            OutputFormat::Png | OutputFormat::Ico | OutputFormat::Icns | OutputFormat::Webp | OutputFormat::Avif
+        )
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Svg => "svg",
+            OutputFormat::Png => "png",
+            OutputFormat::Ico => "ico",
+            OutputFormat::Icns => "icns",
+            OutputFormat::Webp => "webp",
+            OutputFormat::Avif => "avif",
+            OutputFormat::VectorDrawable => "xml",
+            OutputFormat::Xaml => "xaml",
+            OutputFormat::Pdf => "pdf",
+            OutputFormat::Eps => "eps",
+        }
+    }
+}
+
+/// Running per-format totals of the bytes written by `mass-export`.
+#[derive(Default)]
+struct SizeReport {
+    totals: Mutex<BTreeMap<&'static str, (usize, usize)>>,
+    /// SVG bytes before and after optimization.
+    optimized: Mutex<(usize, usize)>,
+    /// Whether any raster or encoded file was written.
+    encoded: AtomicBool,
+}
+
+impl SizeReport {
+    fn record(&self, format: OutputFormat, bytes: usize) {
+        if format.is_raster() {
+            self.encoded.store(true, Ordering::Relaxed);
+        }
+        let mut totals = self.totals.lock().unwrap();
+        let entry = totals.entry(format.extension()).or_default();
+        entry.0 += 1;
+        entry.1 += bytes;
+    }
+
+    fn record_savings(&self, before: usize, after: usize) {
+        let mut optimized = self.optimized.lock().unwrap();
+        optimized.0 += before;
+        optimized.1 += after;
+    }
+
+    /// Prints the totals to stdout. Exports of only SVGs print nothing
+    /// unless the optimizer ran, since their sizes are rarely of interest.
+    fn print(&self) {
+        let totals = self.totals.lock().unwrap();
+        let (before, after) = *self.optimized.lock().unwrap();
+        if !self.encoded.load(Ordering::Relaxed) && before == 0 {
+            return;
+        }
+        for (extension, (files, bytes)) in totals.iter() {
+            println!("{:>5}: {} file(s), {} bytes", extension, files, bytes);
+        }
+        if before > 0 {
+            let saved = before.saturating_sub(after);
+            println!(
+                "optimized SVGs: {} -> {} bytes, saved {} bytes ({:.1}%)",
+                before,
+                after,
+                saved,
+                saved as f64 * 100.0 / before as f64
+            );
+        }
+    }
+}
+
+/// What `mass-export` does with icons that fail the contrast check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ContrastCheck {
+    /// Skips the check.
+    Off,
+    /// Logs a warning and writes the icon anyway.
+    Warn,
+    /// Skips the icon and makes the command fail.
+    Error,
+}
+
+/// Everything `process_file` needs to turn one source icon into output files.
+struct ExportSettings {
+    style: StylePreset,
+    styles: CustomStyles,
+    themes: Vec<Theme>,
+    adaptive: bool,
+    theme_config: ThemeConfig,
+    states: Vec<IconState>,
+    stateful: bool,
+    contrast_check: ContrastCheck,
+    formats: Vec<OutputFormat>,
+    raster_size: Option<u32>,
+    scale: f32,
+    encode_options: EncodeOptions,
+    size_report: SizeReport,
+    optimize: OptimizeLevel,
+    sprite: bool,
+}
+
+impl ExportSettings {
+    /// Runs the optimizer on a generated SVG, recording the bytes it saved.
+    fn optimize_svg(&self, svg: &str) -> Result<String> {
+        let optimized = optimize(svg, self.optimize)?;
+        if self.optimize != OptimizeLevel::Off {
+            self.size_report.record_savings(svg.len(), optimized.len());
+        }
+        Ok(optimized)
+    }
 }
 
 fn main() -> Result<()> {
@@ -67,13 +704,20 @@ fn main() -> Result<()> {
         Commands::MassExport {
             source,
             output,
-            style,
-            gradient,
-            color,
-            width,
-            height,
-            corner_radius,
-            padding,
+            style_args,
+            contrast_check,
+            formats,
+            raster_size,
+            scale,
+            quality,
+            webp_quantize,
+            lossless,
+            optimize,
+            sprite,
+            sprite_preview,
+            theme_args,
+            states,
+            stateful,
         } => {
             // Validate source directory
             if !source.is_dir() {
@@ -85,58 +729,673 @@ fn main() -> Result<()> {
 
             log::info!("Starting mass export from '{}' to '{}'", source.display(), output.display());
 
-            let gradient = gradient.map(|s| parse_gradient(&s)).transpose()?;
+            let style = style_args.style;
+            let (mut styles, palette) = style_args.build()?;
+            let entries = svg_entries(&source)?;
 
-            let styles = CustomStyles {
-                width,
-                height,
-                corner_radius,
-                padding,
-                icon_color: color,
-                gradient,
-            };
+            if styles.fit == FitMode::VisualArea && style_args.target_coverage.is_none() {
+                if let Some(coverage) = median_coverage(&entries, &styles) {
+                    log::info!("Matching the set's median visual coverage of {:.1}%", coverage * 100.0);
+                    styles.target_coverage = coverage;
+                }
+            }
 
-            let entries: Vec<_> = fs::read_dir(&source)?
-                .filter_map(Result::ok)
-                .filter(|e| e.path().extension().map_or(false, |ext| ext == "svg"))
-                .collect();
+            let theme_config = theme_args.config(&styles, style, palette.as_ref())?;
+
+            let settings = ExportSettings {
+                style,
+                styles,
+                themes: theme_args.themes.clone(),
+                adaptive: theme_args.adaptive,
+                contrast_check,
+                theme_config,
+                states,
+                stateful,
+                formats,
+                raster_size,
+                scale,
+                encode_options: EncodeOptions { lossless, quality, webp_quantize },
+                size_report: SizeReport::default(),
+                optimize,
+                sprite,
+            };
 
             log::info!("Found {} SVG files to process.", entries.len());
 
-            entries.par_iter().for_each(|entry| {
-                if let Err(e) = process_file(entry.path(), &output, style, &styles) {
-                    log::error!("Failed to process file {}: {}", entry.path().display(), e);
+            let results: Vec<_> = entries
+                .par_iter()
+                .map(|entry| (entry, process_file(entry.path(), &output, &settings)))
+                .collect();
+
+            let mut low_contrast = 0;
+            let mut sprite_icons = Vec::new();
+            for (entry, result) in results {
+                match result {
+                    Ok(generated) => sprite_icons.extend(generated),
+                    Err(e) => {
+                        log::error!("Failed to process file {}: {:#}", entry.path().display(), e);
+                        if e.downcast_ref::<IconEngineError>()
+                            .is_some_and(|e| matches!(e, IconEngineError::InsufficientContrast { .. }))
+                        {
+                            low_contrast += 1;
+                        }
+                    }
                 }
-            });
+            }
 
+            if sprite {
+                write_sprite(&output, sprite_icons, sprite_preview, &settings.size_report)?;
+            }
+
+            settings.size_report.print();
             log::info!("Mass export complete!");
+
+            if low_contrast > 0 {
+                anyhow::bail!(
+                    "{} icon(s) are below the minimum contrast of {}:1",
+                    low_contrast,
+                    style_args.min_contrast
+                );
+            }
+        }
+        Commands::Bundle {
+            source,
+            output,
+            profiles,
+            style_args,
+        } => {
+            let icon_data = fs::read_to_string(&source)
+                .with_context(|| format!("Failed to read source SVG file: {}", source.display()))?;
+            let (styles, _) = style_args.build()?;
+
+            for profile in profiles {
+                profiles::write_bundle(profile, &icon_data, style_args.style, &styles, &output)?;
+            }
+
+            log::info!("Bundle export complete!");
+        }
+        Commands::Atlas {
+            source,
+            output,
+            name,
+            raster_size,
+            spacing,
+            max_width,
+            style_args,
+        } => {
+            if !source.is_dir() {
+                anyhow::bail!("Source path is not a valid directory: {}", source.display());
+            }
+            fs::create_dir_all(&output).context("Failed to create output directory")?;
+
+            let style = style_args.style;
+            let (styles, _) = style_args.build()?;
+
+            let mut images: Vec<(String, RasterImage)> = svg_entries(&source)?
+                .par_iter()
+                .filter_map(|entry| {
+                    let path = entry.path();
+                    let rendered = fs::read_to_string(&path)
+                        .with_context(|| format!("Failed to read source SVG file: {}", path.display()))
+                        .and_then(|icon_data| Ok(generate_icon(&icon_data, style, &styles)?))
+                        .and_then(|svg| Ok(render(&svg, raster_size, 1.0)?));
+                    match rendered {
+                        Ok(image) => {
+                            let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                            Some((stem.into_owned(), image))
+                        }
+                        Err(e) => {
+                            log::error!("Failed to process file {}: {:#}", path.display(), e);
+                            None
+                        }
+                    }
+                })
+                .collect();
+
+            // Frame names are sanitized after sorting by file stem, so stems
+            // that sanitize alike get the same suffixes on every run.
+            images.sort_by(|a, b| a.0.cmp(&b.0));
+            let (stems, images): (Vec<String>, Vec<RasterImage>) = images.into_iter().unzip();
+            let atlas = pack_atlas(unique_ids(&stems).into_iter().zip(images).collect(), spacing, max_width)?;
+            let image_file = format!("{}.png", name);
+            let files = [
+                (image_file.clone(), encode_png(&atlas.image)?),
+                (format!("{}.json", name), atlas.to_texturepacker_json(&image_file)?.into_bytes()),
+                (format!("{}.css", name), atlas.to_css(&image_file, "icon").into_bytes()),
+            ];
+            for (file_name, bytes) in files {
+                let path = output.join(file_name);
+                fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+                log::info!("Successfully generated {}", path.display());
+            }
+
+            log::info!(
+                "Packed {} icons into a {}x{} atlas",
+                atlas.frames.len(),
+                atlas.image.width,
+                atlas.image.height
+            );
+        }
+        Commands::Font {
+            source,
+            output,
+            name,
+            codepoints,
+            class_prefix,
+        } => {
+            if !source.is_dir() {
+                anyhow::bail!("Source path is not a valid directory: {}", source.display());
+            }
+            fs::create_dir_all(&output).context("Failed to create output directory")?;
+
+            let mut icons = Vec::new();
+            for entry in svg_entries(&source)? {
+                let path = entry.path();
+                let icon_data = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read source SVG file: {}", path.display()))?;
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                icons.push((sanitize_id(&stem), icon_data));
+            }
+
+            let map_path = codepoints.unwrap_or_else(|| output.join(format!("{}.json", name)));
+            let mut mapping = if map_path.exists() {
+                let json = fs::read_to_string(&map_path)
+                    .with_context(|| format!("Failed to read codepoint map: {}", map_path.display()))?;
+                parse_codepoint_map(&json)?
+            } else {
+                BTreeMap::new()
+            };
+            let names: Vec<String> = icons.iter().map(|(id, _)| id.clone()).collect();
+            assign_codepoints(&names, &mut mapping)?;
+
+            let glyphs: Vec<FontGlyph> = icons
+                .par_iter()
+                .filter_map(|(id, icon_data)| match FontGlyph::from_svg(id, mapping[id], icon_data) {
+                    Ok(glyph) => Some(glyph),
+                    Err(e) => {
+                        log::error!("Failed to convert {} to a glyph: {}", id, e);
+                        None
+                    }
+                })
+                .collect();
+
+            let ttf = build_ttf(&name, &glyphs)?;
+            let files = [
+                (output.join(format!("{}.woff2", name)), ttf_to_woff2(&ttf)?),
+                (output.join(format!("{}.ttf", name)), ttf),
+                (output.join(format!("{}.css", name)), font_css(&name, &glyphs, &class_prefix).into_bytes()),
+                (map_path, codepoint_map_to_json(&mapping).into_bytes()),
+            ];
+            for (path, bytes) in files {
+                fs::write(&path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+                log::info!("Successfully generated {}", path.display());
+            }
+
+            log::info!("Built a font with {} glyphs", glyphs.len());
+        }
+        Commands::Monogram {
+            text,
+            font,
+            output,
+            raster_size,
+            style_args,
+        } => {
+            let font_data = fs::read(&font).with_context(|| format!("Failed to read font file: {}", font.display()))?;
+            let (styles, _) = style_args.build()?;
+            let svg = generate_monogram(&text, &font_data, style_args.style, &styles)?;
+
+            let png = output.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+            let bytes = if png { render_png(&svg, raster_size, 1.0)? } else { svg.into_bytes() };
+            if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).context("Failed to create output directory")?;
+            }
+            fs::write(&output, bytes).with_context(|| format!("Failed to write {}", output.display()))?;
+            log::info!("Wrote the {:?} monogram to {}", text, output.display());
+        }
+        Commands::Components {
+            source,
+            output,
+            targets,
+            style_args,
+        } => {
+            if !source.is_dir() {
+                anyhow::bail!("Source path is not a valid directory: {}", source.display());
+            }
+
+            let style = style_args.style;
+            let (styles, _) = style_args.build()?;
+
+            let mut icons = Vec::new();
+            for entry in svg_entries(&source)? {
+                let path = entry.path();
+                let icon_data = fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read source SVG file: {}", path.display()))?;
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                icons.push((stem, icon_data));
+            }
+
+            for target in targets {
+                let dir = output.join(target.to_possible_value().unwrap().get_name());
+                fs::create_dir_all(&dir).context("Failed to create output directory")?;
+
+                let components: Vec<Component> = icons
+                    .par_iter()
+                    .filter_map(|(stem, icon_data)| {
+                        match generate_component(target, stem, icon_data, style, &styles) {
+                            Ok(component) => Some(component),
+                            Err(e) => {
+                                log::error!("Failed to generate a {:?} component for {}: {}", target, stem, e);
+                                None
+                            }
+                        }
+                    })
+                    .collect();
+
+                let index = index_file(target, &components);
+                let files = components
+                    .into_iter()
+                    .map(|c| (c.file_name, c.source))
+                    .chain(index);
+                for (file_name, source) in files {
+                    let path = dir.join(file_name);
+                    fs::write(&path, source).with_context(|| format!("Failed to write {}", path.display()))?;
+                    log::info!("Successfully generated {}", path.display());
+                }
+            }
+
+            log::info!("Component export complete!");
         }
     }
 
     Ok(())
 }
 
+/// Parses an `--overlay` spec. Overlays default to a red quarter-size
+/// badge in the top-right corner with white text and no cutout.
+fn parse_overlay(spec: &str, canvas: u32) -> Result<Overlay> {
+    let mut parts = spec_parts(spec);
+    let kind = parts.next().unwrap_or_default();
+    let mut overlay = Overlay {
+        shape: OverlayShape::Dot,
+        color: "#e53935".to_string(),
+        anchor: Alignment::TopRight,
+        size: canvas as f32 / 4.0,
+        cutout: None,
+    };
+    let mut text_color = "white".to_string();
+    for option in parts {
+        let (key, value) = spec_option(option)?;
+        let number = || value.parse::<f32>().with_context(|| format!("Invalid overlay {}: {}", key, value));
+        match key {
+            "color" => overlay.color = value.to_string(),
+            "text-color" => text_color = value.to_string(),
+            "anchor" => {
+                overlay.anchor = Alignment::from_str(value, true)
+                    .map_err(|_| anyhow::anyhow!("Invalid overlay anchor: {}", value))?
+            }
+            "size" => overlay.size = number()?,
+            "cutout" => overlay.cutout = Some(number()?),
+            _ => anyhow::bail!("Unknown overlay option: {}", key),
+        }
+    }
+
+    overlay.shape = match kind.split_once(':') {
+        None if kind == "dot" => OverlayShape::Dot,
+        Some(("badge", text)) => OverlayShape::Badge { text: text.to_string(), text_color },
+        Some(("ribbon", text)) => OverlayShape::Ribbon { text: text.to_string(), text_color },
+        Some(("icon", path)) => OverlayShape::Icon(
+            fs::read_to_string(path).with_context(|| format!("Failed to read overlay icon {}", path))?,
+        ),
+        _ => anyhow::bail!("Unknown overlay kind '{}': expected dot, badge:TEXT, ribbon:TEXT or icon:PATH", kind),
+    };
+    Ok(overlay)
+}
+
+/// Parses a `--layer` spec into a glyph layer read from its source file.
+fn parse_glyph_layer(spec: &str) -> Result<GlyphLayer> {
+    let mut parts = spec_parts(spec);
+    let path = parts.next().unwrap_or_default();
+    let icon_data = fs::read_to_string(path).with_context(|| format!("Failed to read layer icon {}", path))?;
+    let mut layer = GlyphLayer::new(&icon_data);
+    for option in parts {
+        let (key, value) = spec_option(option)?;
+        let number = || value.parse::<f32>().with_context(|| format!("Invalid layer {}: {}", key, value));
+        match key {
+            "transform" => {
+                layer.transform =
+                    Transform::parse(value).with_context(|| format!("Invalid layer transform: {}", value))?
+            }
+            "color" => layer.color = Some(value.to_string()),
+            "opacity" => layer.opacity = number()?,
+            "knockout" => layer.knockout = Some(number()?),
+            _ => anyhow::bail!("Unknown layer option: {}", key),
+        }
+    }
+    Ok(layer)
+}
+
+/// Parses a `--pattern` spec. Image patterns default to one tile covering
+/// the canvas.
+fn parse_pattern(spec: &str, canvas: u32) -> Result<BasePattern> {
+    let mut parts = spec_parts(spec);
+    let kind = parts.next().unwrap_or_default();
+    let mut pattern = match kind.split_once(':') {
+        None if kind == "noise" => BasePattern::new(PatternKind::Noise),
+        None if kind == "dots" => BasePattern::new(PatternKind::Dots),
+        None if kind == "stripes" => BasePattern::new(PatternKind::Stripes),
+        None if kind == "checkerboard" => BasePattern::new(PatternKind::Checkerboard),
+        Some(("image", path)) => {
+            let png = fs::read(path).with_context(|| format!("Failed to read pattern image {}", path))?;
+            BasePattern { scale: canvas as f32, ..BasePattern::image(png)? }
+        }
+        _ => anyhow::bail!(
+            "Unknown pattern kind '{}': expected noise, dots, stripes, checkerboard or image:PATH",
+            kind
+        ),
+    };
+    for option in parts {
+        let (key, value) = spec_option(option)?;
+        let number = || value.parse::<f32>().with_context(|| format!("Invalid pattern {}: {}", key, value));
+        match key {
+            "color" => pattern.color = value.to_string(),
+            "scale" => pattern.scale = number()?,
+            "opacity" => pattern.opacity = number()?,
+            _ => anyhow::bail!("Unknown pattern option: {}", key),
+        }
+    }
+    Ok(pattern)
+}
+
+/// Splits an `--overlay`, `--layer` or `--pattern` spec at the commas outside parentheses,
+/// so option values like `translate(4, 4)` stay whole.
+fn spec_parts(spec: &str) -> impl Iterator<Item = &str> {
+    let mut depth = 0i32;
+    spec.split(move |c: char| {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        c == ',' && depth == 0
+    })
+    .map(str::trim)
+}
+
+fn spec_option(option: &str) -> Result<(&str, &str)> {
+    option
+        .split_once('=')
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .with_context(|| format!("Option '{}' must be key=value", option))
+}
+
+/// Lists the `.svg` files directly inside `dir`.
+fn svg_entries(dir: &Path) -> Result<Vec<fs::DirEntry>> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "svg"))
+        .collect())
+}
+
+/// The context of errors about `@name` colors given without `--palette`.
+const NO_PALETTE: &str = "Palette color references need a --palette file";
+
+fn load_palette(path: &Path) -> Result<Palette> {
+    let format = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(PaletteFormat::from_extension)
+        .with_context(|| format!("Unsupported palette format: {}", path.display()))?;
+    let data = fs::read(path).with_context(|| format!("Failed to read palette: {}", path.display()))?;
+    Ok(Palette::parse(&data, format)?)
+}
+
+/// Generates every variant of one source icon and writes it in each output
+/// format, returning the `(file stem, SVG)` pairs for sprite mode.
 fn process_file(
     source_path: PathBuf,
     output_dir: &Path,
-    style: StylePreset,
-    styles: &CustomStyles,
-) -> Result<()> {
+    settings: &ExportSettings,
+) -> Result<Vec<(String, String)>> {
     let icon_data = fs::read_to_string(&source_path)
         .with_context(|| format!("Failed to read source SVG file: {}", source_path.display()))?;
 
-    let generated_svg = generate_icon(&icon_data, style, styles)
-        .with_context(|| format!("Failed to generate icon for {}", source_path.display()))?;
-
+    let style = settings.style;
     let file_name = source_path.file_stem().unwrap_or_default().to_str().unwrap_or("icon");
     let style_str = format!("{:?}", style).to_lowercase();
-    let output_filename = format!("{}-{}.svg", file_name, style_str);
-    let output_path = output_dir.join(output_filename);
 
-    fs::write(&output_path, generated_svg)
-        .with_context(|| format!("Failed to write output SVG to {}", output_path.display()))?;
+    if settings.contrast_check != ContrastCheck::Off {
+        let checked_styles: Vec<CustomStyles> = if settings.themes.is_empty() && !settings.adaptive {
+            vec![settings.styles.clone()]
+        } else {
+            [Theme::Light, Theme::Dark]
+                .into_iter()
+                .filter(|theme| settings.adaptive || settings.themes.contains(theme))
+                .map(|theme| settings.theme_config.styles_for(&settings.styles, style, theme))
+                .collect()
+        };
+        for styles in &checked_styles {
+            match check_contrast(styles, style) {
+                Err(e) if settings.contrast_check == ContrastCheck::Error => {
+                    return Err(anyhow::Error::new(e)
+                        .context(format!("Contrast check failed for {}", source_path.display())));
+                }
+                Err(e) => log::warn!("{}: {}", source_path.display(), e),
+                Ok(_) => {}
+            }
+        }
+    }
+
+    // Each output is a (file name suffix, styles, SVG) triple; the styles let
+    // multi-size formats regenerate the icon at every size.
+    let outputs = if settings.adaptive {
+        let svg = generate_adaptive_icon(&icon_data, style, &settings.styles, &settings.theme_config)
+            .with_context(|| format!("Failed to generate icon for {}", source_path.display()))?;
+        let light = settings.theme_config.styles_for(&settings.styles, style, Theme::Light);
+        vec![(String::new(), light, svg)]
+    } else if settings.themes.is_empty() {
+        let svg = generate_icon(&icon_data, style, &settings.styles)
+            .with_context(|| format!("Failed to generate icon for {}", source_path.display()))?;
+        vec![(String::new(), settings.styles.clone(), svg)]
+    } else {
+        settings
+            .themes
+            .iter()
+            .map(|&theme| {
+                let styles = settings.theme_config.styles_for(&settings.styles, style, theme);
+                let svg = generate_icon(&icon_data, style, &styles)
+                    .with_context(|| format!("Failed to generate icon for {}", source_path.display()))?;
+                Ok((format!("-{:?}", theme).to_lowercase(), styles, svg))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+    let outputs = if settings.states.is_empty() {
+        outputs
+    } else {
+        with_states(outputs, &icon_data, settings)
+            .with_context(|| format!("Failed to generate icon states for {}", source_path.display()))?
+    };
+
+    let mut generated = Vec::new();
+    for (suffix, styles, generated_svg) in outputs {
+        let stem = format!("{}-{}{}", file_name, style_str, suffix);
+        let output = GeneratedIcon {
+            icon_data: &icon_data,
+            style,
+            styles: &styles,
+            svg: &generated_svg,
+        };
+        for &format in &settings.formats {
+            // In sprite mode the SVGs are only written as part of the sprite.
+            if settings.sprite && format == OutputFormat::Svg {
+                continue;
+            }
+            write_output(output_dir, &stem, &output, format, settings)?;
+        }
+        if settings.sprite {
+            generated.push((stem, settings.optimize_svg(&generated_svg)?));
+        }
+    }
+
+    Ok(generated)
+}
+
+/// Replaces each output with one per requested state, or with a single
+/// SVG switching between them for `--stateful`; the normal styles stand in
+/// for the latter when it is rasterized.
+fn with_states(
+    outputs: Vec<(String, CustomStyles, String)>,
+    icon_data: &str,
+    settings: &ExportSettings,
+) -> Result<Vec<(String, CustomStyles, String)>> {
+    let mut expanded = Vec::new();
+    for (suffix, styles, _) in outputs {
+        if settings.stateful {
+            let svg = generate_stateful_icon(icon_data, settings.style, &styles, &settings.states)?;
+            expanded.push((suffix, styles, svg));
+            continue;
+        }
+        for &state in &settings.states {
+            let state_styles = state_styles(&styles, state);
+            let svg = generate_icon(icon_data, settings.style, &state_styles)?;
+            expanded.push((format!("{}-{}", suffix, state.name()), state_styles, svg));
+        }
+    }
+    Ok(expanded)
+}
+
+/// Returns the median share of the padded area the icons cover when fitted
+/// tightly; icons that cannot be read or parsed are left out.
+fn median_coverage(entries: &[fs::DirEntry], styles: &CustomStyles) -> Option<f32> {
+    let mut coverages: Vec<f32> = entries
+        .iter()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|data| parse_svg(&data).ok())
+        .filter_map(|icon| tight_coverage(&icon, styles))
+        .collect();
+    coverages.sort_by(f32::total_cmp);
+    // The lower median, so a two-icon set settles on the lighter one.
+    coverages.get(coverages.len().checked_sub(1)? / 2).copied()
+}
+
+/// Writes `sprite.svg` (and optionally `sprite.html`) from `(file stem, SVG)`
+/// pairs, sorted by stem so repeated runs produce identical files and
+/// stems that sanitize to the same symbol id get the same suffixes.
+fn write_sprite(
+    output_dir: &Path,
+    mut icons: Vec<(String, String)>,
+    preview: bool,
+    size_report: &SizeReport,
+) -> Result<()> {
+    icons.sort_by(|a, b| a.0.cmp(&b.0));
+    let (stems, svgs): (Vec<String>, Vec<String>) = icons.into_iter().unzip();
+    let icons: Vec<(String, String)> = unique_ids(&stems).into_iter().zip(svgs).collect();
+    let sprite = build_sprite(&icons)?;
+
+    let sprite_path = output_dir.join("sprite.svg");
+    fs::write(&sprite_path, &sprite)
+        .with_context(|| format!("Failed to write sprite to {}", sprite_path.display()))?;
+    size_report.record(OutputFormat::Svg, sprite.len());
+    log::info!("Wrote {} symbols to {}", icons.len(), sprite_path.display());
+
+    if preview {
+        let ids: Vec<String> = icons.into_iter().map(|(id, _)| id).collect();
+        let preview_path = output_dir.join("sprite.html");
+        fs::write(&preview_path, sprite_preview_html(&sprite, &ids))
+            .with_context(|| format!("Failed to write sprite preview to {}", preview_path.display()))?;
+        log::info!("Successfully generated {}", preview_path.display());
+    }
+
+    Ok(())
+}
+
+/// One generated icon variant, with what's needed to regenerate it at other sizes.
+struct GeneratedIcon<'a> {
+    icon_data: &'a str,
+    style: StylePreset,
+    styles: &'a CustomStyles,
+    svg: &'a str,
+}
+
+impl GeneratedIcon<'_> {
+    /// Rasterizes the icon at each size with size-appropriate tuning.
+    fn render_sizes(&self, sizes: &[u32]) -> Result<Vec<RasterImage>> {
+        sizes
+            .iter()
+            .map(|&size| {
+                let styles = profiles::styles_for_size(self.styles, size);
+                let svg = generate_icon(self.icon_data, self.style, &styles)?;
+                Ok(render(&svg, None, 1.0)?)
+            })
+            .collect()
+    }
+}
+
+/// Writes one generated icon to `output_dir` in the given format.
+fn write_output(
+    output_dir: &Path,
+    stem: &str,
+    icon: &GeneratedIcon,
+    format: OutputFormat,
+    settings: &ExportSettings,
+) -> Result<()> {
+    let render_scaled = || render(icon.svg, settings.raster_size, settings.scale);
+    let bytes = match format {
+        OutputFormat::Svg => settings.optimize_svg(icon.svg)?.into_bytes(),
+        OutputFormat::Png => render_png(icon.svg, settings.raster_size, settings.scale)?,
+        OutputFormat::Ico => encode_ico(&icon.render_sizes(ICO_SIZES)?)?,
+        OutputFormat::Icns => encode_icns(&icon.render_sizes(ICNS_SIZES)?)?,
+        OutputFormat::Webp => encode_webp(&render_scaled()?, settings.encode_options)?,
+        OutputFormat::Avif => encode_avif(&render_scaled()?, settings.encode_options)?,
+        OutputFormat::VectorDrawable => {
+            native_source(stem, format, to_vector_drawable(icon.icon_data, icon.style, icon.styles)?)
+        }
+        OutputFormat::Xaml => {
+            let key = component_name(stem);
+            native_source(stem, format, to_xaml(&key, icon.icon_data, icon.style, icon.styles)?)
+        }
+        OutputFormat::Pdf => to_pdf(icon.icon_data, icon.style, icon.styles)?,
+        OutputFormat::Eps => to_eps(icon.icon_data, icon.style, icon.styles)?,
+    };
+
+    let file_stem = match format {
+        // Android resource names only allow lowercase letters, digits and underscores.
+        OutputFormat::VectorDrawable => stem.to_ascii_lowercase().replace(|c: char| !c.is_ascii_alphanumeric(), "_"),
+        // Single-image raster formats carry the @2x-style scale in their name.
+        OutputFormat::Png | OutputFormat::Webp | OutputFormat::Avif => {
+            format!("{}{}", stem, scale_suffix(settings.scale))
+        }
+        _ => stem.to_string(),
+    };
+    let output_path = output_dir.join(format!("{}.{}", file_stem, format.extension()));
+    settings.size_report.record(format, bytes.len());
+
+    fs::write(&output_path, bytes)
+        .with_context(|| format!("Failed to write output to {}", output_path.display()))?;
 
     log::info!("Successfully generated {}", output_path.display());
 
     Ok(())
 }
+
+/// Warns about the effects a native vector format had to drop and returns
+/// the converted source.
+fn native_source(stem: &str, format: OutputFormat, icon: NativeIcon) -> Vec<u8> {
+    if !icon.dropped_effects.is_empty() {
+        log::warn!(
+            "{}.{}: dropped unsupported effects: {}",
+            stem,
+            format.extension(),
+            icon.dropped_effects.join(", ")
+        );
+    }
+    icon.source.into_bytes()
+}
+
+/// Returns the `@2x`-style file name suffix for a raster scale factor.
+fn scale_suffix(scale: f32) -> String {
+    if scale == 1.0 {
+        String::new()
+    } else {
+        format!("@{}x", scale)
+    }
+}
@@ -0,0 +1,273 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use icon_engine::{
+    containers::encode_ico,
+    generate_icon, generate_icon_layers,
+    glyph_stack::GlyphLayer,
+    overlay::Overlay,
+    pattern::BasePattern,
+    raster::{render, render_png},
+    CustomStyles, Insets, Length, SafeZone, StylePreset,
+};
+use serde_json::json;
+use std::fs;
+use std::path::Path;
+
+/// The platform bundles `bundle` can build.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Profile {
+    /// An Xcode `AppIcon.appiconset` with `Contents.json`.
+    Ios,
+    /// Launcher mipmaps for every density plus adaptive icon layers.
+    Android,
+    /// Browser favicons (PNG, ICO and SVG) and the Apple touch icon.
+    Favicon,
+    /// Web app manifest icons, including a maskable variant.
+    Pwa,
+}
+
+/// iOS app icon slots as (idiom, point size, scale).
+const IOS_ICONS: &[(&str, f32, u32)] = &[
+    ("iphone", 20.0, 2),
+    ("iphone", 20.0, 3),
+    ("iphone", 29.0, 2),
+    ("iphone", 29.0, 3),
+    ("iphone", 40.0, 2),
+    ("iphone", 40.0, 3),
+    ("iphone", 60.0, 2),
+    ("iphone", 60.0, 3),
+    ("ipad", 20.0, 1),
+    ("ipad", 20.0, 2),
+    ("ipad", 29.0, 1),
+    ("ipad", 29.0, 2),
+    ("ipad", 40.0, 1),
+    ("ipad", 40.0, 2),
+    ("ipad", 76.0, 1),
+    ("ipad", 76.0, 2),
+    ("ipad", 83.5, 2),
+    ("ios-marketing", 1024.0, 1),
+];
+
+/// Android density buckets as (name, scale relative to mdpi).
+const ANDROID_DENSITIES: &[(&str, f32)] = &[
+    ("mdpi", 1.0),
+    ("hdpi", 1.5),
+    ("xhdpi", 2.0),
+    ("xxhdpi", 3.0),
+    ("xxxhdpi", 4.0),
+];
+
+/// Legacy launcher icons are 48dp; adaptive icon layers are 108dp.
+const ANDROID_LAUNCHER_DP: f32 = 48.0;
+const ANDROID_ADAPTIVE_DP: f32 = 108.0;
+/// Keeps the maskable PWA glyph inside the 80% safe zone circle.
+const PWA_MASKABLE_PADDING: f32 = 0.22;
+
+const FAVICON_SIZES: &[u32] = &[16, 32, 48];
+const APPLE_TOUCH_ICON_SIZE: u32 = 180;
+const PWA_SIZES: &[u32] = &[192, 512];
+
+const ADAPTIVE_ICON_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<adaptive-icon xmlns:android="http://schemas.android.com/apk/res/android">
+    <background android:drawable="@mipmap/ic_launcher_background"/>
+    <foreground android:drawable="@mipmap/ic_launcher_foreground"/>
+</adaptive-icon>
+"#;
+
+/// Writes the bundle for `profile` into its own directory under `output_dir`.
+pub fn write_bundle(
+    profile: Profile,
+    icon_data: &str,
+    style: StylePreset,
+    styles: &CustomStyles,
+    output_dir: &Path,
+) -> Result<()> {
+    let bundle = BundleWriter { icon_data, style, styles };
+    match profile {
+        Profile::Ios => bundle.write_ios(&output_dir.join("ios").join("AppIcon.appiconset")),
+        Profile::Android => bundle.write_android(&output_dir.join("android").join("res")),
+        Profile::Favicon => bundle.write_favicon(&output_dir.join("favicon")),
+        Profile::Pwa => bundle.write_pwa(&output_dir.join("pwa")),
+    }
+}
+
+/// Icons at or below this pixel size get tighter padding and no shadows.
+const SMALL_ICON_SIZE: u32 = 32;
+
+/// Scales the styles to a square canvas of `size` pixels, keeping padding,
+/// overlays, knockouts and corner radius proportional. Favicon-sized icons
+/// get half the padding so the glyph keeps as many pixels as possible, and
+/// drop the shadows, which would only blur the edges at that size.
+pub fn styles_for_size(styles: &CustomStyles, size: u32) -> CustomStyles {
+    let ratio = size as f32 / styles.width.max(styles.height) as f32;
+    let small = size <= SMALL_ICON_SIZE;
+    let padding_ratio = if small { ratio / 2.0 } else { ratio };
+
+    // Per-side insets are resolved at the original size and scaled like `padding`.
+    let insets = styles.insets.map(|_| {
+        let content = styles.content_box();
+        let side = |inset: f32| Length::Px(inset * padding_ratio);
+        Insets {
+            top: side(content.min_y),
+            right: side(styles.width as f32 - content.max_x),
+            bottom: side(styles.height as f32 - content.max_y),
+            left: side(content.min_x),
+        }
+    });
+
+    CustomStyles {
+        width: size,
+        height: size,
+        padding: (styles.padding as f32 * padding_ratio).round() as u32,
+        insets,
+        overlays: styles
+            .overlays
+            .iter()
+            .map(|overlay| Overlay {
+                size: overlay.size * ratio,
+                cutout: overlay.cutout.map(|ring| ring * ratio),
+                ..overlay.clone()
+            })
+            .collect(),
+        glyph_layers: styles
+            .glyph_layers
+            .iter()
+            .map(|layer| GlyphLayer { knockout: layer.knockout.map(|gap| gap * ratio), ..layer.clone() })
+            .collect(),
+        pattern: styles
+            .pattern
+            .as_ref()
+            .map(|pattern| BasePattern { scale: pattern.scale * ratio, ..pattern.clone() }),
+        corner_radius: styles.corner_radius * ratio,
+        shadows: styles.shadows && !small,
+        ..styles.clone()
+    }
+}
+
+struct BundleWriter<'a> {
+    icon_data: &'a str,
+    style: StylePreset,
+    styles: &'a CustomStyles,
+}
+
+impl BundleWriter<'_> {
+    /// Renders a full icon at `size` pixels; `square` drops the corner radius
+    /// for platforms that apply their own mask.
+    fn render(&self, size: u32, square: bool) -> Result<Vec<u8>> {
+        let mut styles = styles_for_size(self.styles, size);
+        if square {
+            styles.corner_radius = 0.0;
+        }
+        let svg = generate_icon(self.icon_data, self.style, &styles)?;
+        Ok(render_png(&svg, None, 1.0)?)
+    }
+
+    fn write_ios(&self, dir: &Path) -> Result<()> {
+        let mut images = Vec::new();
+        for &(idiom, points, scale) in IOS_ICONS {
+            let pixels = (points * scale as f32).round() as u32;
+            let filename = format!("Icon-{}@{}x.png", points, scale);
+            write_file(&dir.join(&filename), &self.render(pixels, true)?)?;
+            images.push(json!({
+                "filename": filename,
+                "idiom": idiom,
+                "scale": format!("{}x", scale),
+                "size": format!("{}x{}", points, points),
+            }));
+        }
+
+        let contents = json!({
+            "images": images,
+            "info": { "author": "icon-cli", "version": 1 },
+        });
+        write_file(&dir.join("Contents.json"), serde_json::to_string_pretty(&contents)?.as_bytes())
+    }
+
+    fn write_android(&self, res_dir: &Path) -> Result<()> {
+        for &(density, scale) in ANDROID_DENSITIES {
+            let dir = res_dir.join(format!("mipmap-{}", density));
+
+            let launcher = (ANDROID_LAUNCHER_DP * scale).round() as u32;
+            write_file(&dir.join("ic_launcher.png"), &self.render(launcher, false)?)?;
+
+            let adaptive = (ANDROID_ADAPTIVE_DP * scale).round() as u32;
+            let styles = CustomStyles {
+                corner_radius: 0.0,
+                safe_zone: Some(SafeZone::AndroidAdaptive),
+                ..styles_for_size(self.styles, adaptive)
+            };
+            let layers = generate_icon_layers(self.icon_data, self.style, &styles)?;
+            write_file(&dir.join("ic_launcher_background.png"), &render_png(&layers.background, None, 1.0)?)?;
+            write_file(&dir.join("ic_launcher_foreground.png"), &render_png(&layers.foreground, None, 1.0)?)?;
+        }
+
+        write_file(
+            &res_dir.join("mipmap-anydpi-v26").join("ic_launcher.xml"),
+            ADAPTIVE_ICON_XML.as_bytes(),
+        )
+    }
+
+    fn write_favicon(&self, dir: &Path) -> Result<()> {
+        for &size in FAVICON_SIZES {
+            write_file(&dir.join(format!("favicon-{0}x{0}.png", size)), &self.render(size, false)?)?;
+        }
+        write_file(&dir.join("apple-touch-icon.png"), &self.render(APPLE_TOUCH_ICON_SIZE, true)?)?;
+
+        let images = FAVICON_SIZES
+            .iter()
+            .map(|&size| {
+                let svg = generate_icon(self.icon_data, self.style, &styles_for_size(self.styles, size))?;
+                Ok(render(&svg, None, 1.0)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        write_file(&dir.join("favicon.ico"), &encode_ico(&images)?)?;
+
+        let svg = generate_icon(self.icon_data, self.style, self.styles)?;
+        write_file(&dir.join("favicon.svg"), svg.as_bytes())
+    }
+
+    fn write_pwa(&self, dir: &Path) -> Result<()> {
+        let mut icons = Vec::new();
+        for &size in PWA_SIZES {
+            let filename = format!("icon-{}.png", size);
+            write_file(&dir.join(&filename), &self.render(size, false)?)?;
+            icons.push(json!({
+                "src": filename,
+                "sizes": format!("{0}x{0}", size),
+                "type": "image/png",
+            }));
+        }
+
+        let maskable_size = *PWA_SIZES.last().unwrap();
+        // Insets and safe zones would take precedence over the safe-area padding.
+        let styles = CustomStyles {
+            corner_radius: 0.0,
+            padding: (maskable_size as f32 * PWA_MASKABLE_PADDING).round() as u32,
+            insets: None,
+            safe_zone: None,
+            ..styles_for_size(self.styles, maskable_size)
+        };
+        let svg = generate_icon(self.icon_data, self.style, &styles)?;
+        let filename = format!("icon-maskable-{}.png", maskable_size);
+        write_file(&dir.join(&filename), &render_png(&svg, None, 1.0)?)?;
+        icons.push(json!({
+            "src": filename,
+            "sizes": format!("{0}x{0}", maskable_size),
+            "type": "image/png",
+            "purpose": "maskable",
+        }));
+
+        let manifest = json!({ "icons": icons });
+        write_file(&dir.join("manifest-icons.json"), serde_json::to_string_pretty(&manifest)?.as_bytes())
+    }
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+    }
+    fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))?;
+    log::info!("Successfully generated {}", path.display());
+    Ok(())
+}
@@ -3,6 +3,8 @@ use predicates::prelude::*;
 use std::process::Command;
 use tempfile::Builder;
 use std::fs;
+use std::path::PathBuf;
+use tempfile::TempDir;
 
 const SAMPLE_SVG: &str = "<svg width=\"24\" height=\"24\" viewBox=\"0 0 24 24\" fill=\"none\" xmlns=\"http://www.w3.org/2000/svg\"><path d=\"M12 2L2 22h20L12 2z\" fill=\"#000000\"></path></svg>";
 const SAMPLE_SVG_FILENAME: &str = "test_icon.svg";
@@ -56,3 +58,656 @@ fn test_mass_export_neumorphism() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+/// A temporary directory whose `source` directory holds the sample icon.
+struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        let dir = Builder::new().prefix("cli-test-").tempdir()?;
+        fs::create_dir_all(dir.path().join("source"))?;
+        fs::write(dir.path().join("source").join(SAMPLE_SVG_FILENAME), SAMPLE_SVG)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.path().join(name)
+    }
+
+    fn source(&self) -> PathBuf {
+        self.path("source")
+    }
+
+    /// Runs `subcommand` from the source directory into `output`.
+    fn command(&self, subcommand: &str, output: &str) -> Command {
+        let mut cmd = Command::cargo_bin("icon-cli").expect("the icon-cli binary is built");
+        cmd.arg(subcommand).arg("--source").arg(self.source()).arg("--output").arg(self.path(output));
+        cmd
+    }
+
+    /// A `mass-export` run in `style` from the source directory into `output`.
+    fn mass_export(&self, output: &str, style: &str) -> Command {
+        let mut cmd = self.command("mass-export", output);
+        cmd.arg("--style").arg(style);
+        cmd
+    }
+
+    fn read(&self, output: &str, name: &str) -> Result<String, std::io::Error> {
+        fs::read_to_string(self.path(output).join(name))
+    }
+}
+
+#[test]
+fn test_mass_export_theme_variants() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--theme")
+        .arg("light")
+        .arg("--theme")
+        .arg("dark")
+        .arg("--dark-color")
+        .arg("#abcdef")
+        .arg("--dark-shadow-dark")
+        .arg("#000000");
+
+    cmd.assert().success();
+
+    let light = fixture.read("output", "test_icon-neumorphism-light.svg")?;
+    let dark = fixture.read("output", "test_icon-neumorphism-dark.svg")?;
+    assert!(light.contains("fill=\"#333333\""));
+    assert!(light.contains("flood-color=\"rgba(0,0,0,0.12)\""));
+    assert!(dark.contains("fill=\"#abcdef\""));
+    assert!(dark.contains("fill=\"#1f1f1f\""));
+    assert!(dark.contains("flood-color=\"#000000\""));
+    assert!(dark.contains("flood-color=\"rgba(255,255,255,0.08)\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_contrast_error() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--color").arg("#d0d0d0").arg("--contrast-check").arg("error");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("below the minimum contrast"));
+    assert!(!fixture.path("output").join("test_icon-neumorphism.svg").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_with_palette() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let palette_path = fixture.path("brand.css");
+    fs::write(&palette_path, ":root { --ink: #102030; --paper: #f0f0f0; }")?;
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--palette")
+        .arg(&palette_path)
+        .arg("--color")
+        .arg("@ink")
+        .arg("--base-color")
+        .arg("@paper");
+
+    cmd.assert().success();
+
+    let output_content = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(output_content.contains("fill=\"#102030\""));
+    assert!(output_content.contains("fill=\"#f0f0f0\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_rejects_palette_references_without_palette() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--color").arg("@ink");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--palette").and(predicate::str::contains("Unknown palette color: ink")));
+    assert!(!fixture.path("output").join("test_icon-neumorphism.svg").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_png() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--format")
+        .arg("svg")
+        .arg("--format")
+        .arg("png")
+        .arg("--raster-size")
+        .arg("32")
+        .arg("--scale")
+        .arg("2");
+
+    cmd.assert().success();
+
+    let output_dir = fixture.path("output");
+    assert!(output_dir.join("test_icon-neumorphism.svg").exists());
+    let png = fs::read(output_dir.join("test_icon-neumorphism@2x.png"))?;
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    // The IHDR chunk stores the width and height right after the signature.
+    assert_eq!(&png[16..24], &[0, 0, 0, 64, 0, 0, 0, 64]);
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_profiles() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = Command::cargo_bin("icon-cli")?;
+    cmd.arg("bundle")
+        .arg("--source")
+        .arg(fixture.source().join(SAMPLE_SVG_FILENAME))
+        .arg("--output")
+        .arg(fixture.path("output"))
+        .arg("--style")
+        .arg("neumorphism")
+        .arg("--profile")
+        .arg("ios")
+        .arg("--profile")
+        .arg("android");
+
+    cmd.assert().success();
+
+    let output_dir = fixture.path("output");
+    let appiconset = output_dir.join("ios").join("AppIcon.appiconset");
+    let contents = fs::read_to_string(appiconset.join("Contents.json"))?;
+    assert!(contents.contains("\"filename\": \"Icon-60@3x.png\""));
+    assert!(appiconset.join("Icon-1024@1x.png").exists());
+
+    let res = output_dir.join("android").join("res");
+    assert!(res.join("mipmap-xxxhdpi").join("ic_launcher.png").exists());
+    assert!(res.join("mipmap-mdpi").join("ic_launcher_foreground.png").exists());
+    let adaptive = fs::read_to_string(res.join("mipmap-anydpi-v26").join("ic_launcher.xml"))?;
+    assert!(adaptive.contains("<adaptive-icon"));
+
+    Ok(())
+}
+
+#[test]
+fn test_bundle_pwa_maskable_ignores_insets() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let maskable = |output: &str, insets: Option<&str>| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut cmd = Command::cargo_bin("icon-cli")?;
+        cmd.arg("bundle")
+            .arg("--source")
+            .arg(fixture.source().join(SAMPLE_SVG_FILENAME))
+            .arg("--output")
+            .arg(fixture.path(output))
+            .arg("--style")
+            .arg("neumorphism")
+            .arg("--profile")
+            .arg("pwa");
+        if let Some(insets) = insets {
+            cmd.arg("--insets").arg(insets);
+        }
+        cmd.assert().success();
+        Ok(fs::read(fixture.path(output).join("pwa").join("icon-maskable-512.png"))?)
+    };
+
+    // The maskable icon always keeps the glyph inside the safe area.
+    assert_eq!(maskable("plain", None)?, maskable("inset", Some("0 0 96 96"))?);
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_ico() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--format").arg("ico");
+
+    cmd.assert().success();
+
+    let ico = fs::read(fixture.path("output").join("test_icon-neumorphism.ico"))?;
+    // Reserved, type 1 (icon) and seven images.
+    assert_eq!(&ico[..6], &[0, 0, 1, 0, 7, 0]);
+    assert!(!fixture.path("output").join("test_icon-neumorphism.svg").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_webp_size_report() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "glassmorphism");
+    cmd.arg("--format").arg("webp").arg("--webp-quantize").arg("2");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("webp: 1 file(s)"));
+
+    let webp = fs::read(fixture.path("output").join("test_icon-glassmorphism.webp"))?;
+    assert_eq!(&webp[8..12], b"WEBP");
+    assert_eq!(&webp[12..16], b"VP8L");
+
+    // Without quantization WebP is lossy and follows --quality.
+    let lossy = |output: &str, quality: &str| -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let mut cmd = fixture.mass_export(output, "glassmorphism");
+        cmd.arg("--format").arg("webp").arg("--quality").arg(quality);
+        cmd.assert().success();
+        Ok(fs::read(fixture.path(output).join("test_icon-glassmorphism.webp"))?)
+    };
+    let (low, high) = (lossy("low", "10")?, lossy("high", "95")?);
+    assert!(high.windows(4).any(|w| w == b"VP8 "));
+    assert!(low.len() < high.len());
+
+    // SVG-only exports keep stdout quiet.
+    fixture.mass_export("svg-only", "glassmorphism").assert().success().stdout(predicate::str::is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_sprite() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    fs::write(fixture.source().join("second icon.svg"), SAMPLE_SVG)?;
+    fs::write(fixture.source().join("second-icon.svg"), SAMPLE_SVG)?;
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--sprite").arg("--sprite-preview");
+
+    cmd.assert().success();
+
+    assert!(!fixture.path("output").join("test_icon-neumorphism.svg").exists());
+    let sprite = fixture.read("output", "sprite.svg")?;
+    assert!(sprite.contains("<symbol id=\"second-icon-neumorphism\""));
+    assert!(sprite.contains("<symbol id=\"second-icon-neumorphism-2\""));
+    assert!(sprite.contains("id=\"test_icon-neumorphism-neumorphism-shadow\""));
+    let preview = fixture.read("output", "sprite.html")?;
+    assert!(preview.contains("<use href=\"#test_icon-neumorphism\"/>"));
+
+    Ok(())
+}
+
+#[test]
+fn test_atlas_export() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    fs::write(fixture.source().join("other.svg"), SAMPLE_SVG)?;
+
+    let mut cmd = fixture.command("atlas", "output");
+    cmd.arg("--style").arg("neumorphism").arg("--raster-size").arg("32");
+
+    cmd.assert().success();
+
+    let png = fs::read(fixture.path("output").join("atlas.png"))?;
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let json = fixture.read("output", "atlas.json")?;
+    assert!(json.contains("\"test_icon\""));
+    assert!(json.contains("\"other\""));
+    let css = fixture.read("output", "atlas.css")?;
+    assert!(css.contains(".icon-other{"));
+
+    Ok(())
+}
+
+#[test]
+fn test_font_export_keeps_codepoints_stable() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    fs::rename(fixture.source().join(SAMPLE_SVG_FILENAME), fixture.source().join("lock.svg"))?;
+
+    let run = || fixture.command("font", "output").assert().success();
+
+    run();
+    let ttf = fs::read(fixture.path("output").join("icons.ttf"))?;
+    assert!(ttf.starts_with(&[0, 1, 0, 0]));
+    let woff2 = fs::read(fixture.path("output").join("icons.woff2"))?;
+    assert!(woff2.starts_with(b"wOF2"));
+    let css = fixture.read("output", "icons.css")?;
+    assert!(css.contains(".icon-lock::before{content:\"\\e000\"}"));
+
+    // An icon added later sorts first but must not take over lock's codepoint.
+    fs::write(fixture.source().join("alarm.svg"), SAMPLE_SVG)?;
+    run();
+    let css = fixture.read("output", "icons.css")?;
+    assert!(css.contains(".icon-lock::before{content:\"\\e000\"}"));
+    assert!(css.contains(".icon-alarm::before{content:\"\\e001\"}"));
+    let mapping = fixture.read("output", "icons.json")?;
+    assert!(mapping.contains("\"alarm\": \"e001\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_components_export() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.command("components", "output");
+    cmd.arg("--style").arg("neumorphism");
+    for target in ["react", "vue", "svelte", "vector-drawable", "swiftui"] {
+        cmd.arg("--target").arg(target);
+    }
+
+    cmd.assert().success();
+
+    let output_dir = fixture.path("output");
+    let tsx = fs::read_to_string(output_dir.join("react").join("TestIcon.tsx"))?;
+    assert!(tsx.contains("fill={color}"));
+    let index = fs::read_to_string(output_dir.join("react").join("index.ts"))?;
+    assert!(index.contains("export { default as TestIcon } from \"./TestIcon\";"));
+    assert!(output_dir.join("vue").join("TestIcon.vue").exists());
+    assert!(output_dir.join("svelte").join("index.ts").exists());
+    assert!(output_dir.join("vector-drawable").join("ic_test_icon.xml").exists());
+    assert!(output_dir.join("swiftui").join("TestIcon.swift").exists());
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_native_vectors() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.env("RUST_LOG", "warn");
+    cmd.arg("--format").arg("vector-drawable").arg("--format").arg("xaml");
+
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("dropped unsupported effects: neumorphism drop shadows"));
+
+    let drawable = fixture.read("output", "test_icon_neumorphism.xml")?;
+    assert!(drawable.contains("<vector"));
+    let xaml = fixture.read("output", "test_icon-neumorphism.xaml")?;
+    assert!(xaml.contains("x:Key=\"TestIconNeumorphismIconImage\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_print_formats() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--format").arg("pdf").arg("--format").arg("eps");
+
+    cmd.assert().success();
+
+    let pdf = fs::read(fixture.path("output").join("test_icon-neumorphism.pdf"))?;
+    assert!(pdf.starts_with(b"%PDF-1.4"));
+    let eps = fixture.read("output", "test_icon-neumorphism.eps")?;
+    assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0"));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_optimize() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "glassmorphism");
+    cmd.arg("--optimize").arg("safe");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("optimized SVGs:").and(predicate::str::contains("saved")));
+
+    let svg = fixture.read("output", "test_icon-glassmorphism.svg")?;
+    assert!(svg.contains("class=\"icon-fg\""));
+    assert!(!svg.contains("glass-blur"));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_bake_transform() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--bake-transform");
+
+    cmd.assert().success();
+
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(!svg.contains("transform="));
+    assert!(svg.contains("d=\"M64 24L24 104L104 104L64 24Z\""));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_fit_modes() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    fs::write(
+        fixture.source().join("small_square.svg"),
+        r#"<svg viewBox="0 0 24 24"><path d="M8 8H16V16H8Z"/></svg>"#,
+    )?;
+
+    let mut cmd = fixture.mass_export("tight", "neumorphism");
+    cmd.arg("--fit").arg("tight");
+    cmd.assert().success();
+    let svg = fixture.read("tight", "small_square-neumorphism.svg")?;
+    assert!(svg.contains("scale(12)"));
+
+    let mut cmd = fixture.mass_export("area", "neumorphism");
+    cmd.env("RUST_LOG", "info");
+    cmd.arg("--fit").arg("visual-area");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("median visual coverage of 50.0%"));
+    // The triangle covers half its bounds, so the square shrinks to match it.
+    let svg = fixture.read("area", "small_square-neumorphism.svg")?;
+    assert!(!svg.contains("scale(12)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_glyph_placement() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--align")
+        .arg("top-left")
+        .arg("--glyph-size")
+        .arg("48")
+        .arg("--offset-x")
+        .arg("-4")
+        .arg("--flip-horizontal");
+    cmd.assert().success();
+
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains("translate(60, 16) rotate(0) scale(-2, 2)"));
+
+    let mut cmd = fixture.mass_export("zero", "neumorphism");
+    cmd.arg("--glyph-scale").arg("0");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Glyph scale must be above 0"));
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_insets() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--insets").arg("16 40 40 16");
+    cmd.assert().success();
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains("translate(16, 16) scale(3)"));
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--insets").arg("1 2 3 4 5");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("one to four lengths"));
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_overlays() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let badge_path = fixture.path("badge.svg");
+    fs::write(&badge_path, SAMPLE_SVG)?;
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--format")
+        .arg("svg")
+        .arg("--format")
+        .arg("png")
+        .arg("--overlay")
+        .arg("badge:3,anchor=top-right,cutout=4")
+        .arg("--overlay")
+        .arg(format!("icon:{},anchor=bottom-left,color=#1e88e5", badge_path.display()));
+    cmd.assert().success();
+
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains(r#"<g mask="url(#overlay-cutout)">"#));
+    assert!(svg.contains(">3</text>"));
+    assert!(svg.contains(r##"fill="#1e88e5""##));
+    assert!(fixture.path("output").join("test_icon-neumorphism.png").exists());
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--overlay").arg("star");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown overlay kind 'star'"));
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_glyph_layers() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let lock_path = fixture.path("lock.svg");
+    fs::write(&lock_path, r#"<svg viewBox="0 0 24 24"><path d="M4 10H20V22H4Z"/></svg>"#)?;
+
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--layer").arg(format!(
+        "{},transform=translate(12, 12) scale(.5),color=#c62828,knockout=4",
+        lock_path.display()
+    ));
+    cmd.assert().success();
+
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains(r#"<g mask="url(#glyph-knockout-1)"><path class="icon-fg""#));
+    assert!(svg.contains(r##"<path class="icon-layer icon-layer-1" d="M14 17L22 17L22 23L14 23Z" fill="#c62828"/>"##));
+    Ok(())
+}
+
+#[test]
+fn test_monogram() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    fs::rename(fixture.source().join(SAMPLE_SVG_FILENAME), fixture.source().join("lock.svg"))?;
+
+    // The icon font maps the sample glyph to U+E000, which serves as a letter.
+    fixture.command("font", "font").assert().success();
+    let font_path = fixture.path("font").join("icons.ttf");
+
+    let monogram = |output: PathBuf, text: &str| {
+        let mut cmd = Command::cargo_bin("icon-cli").expect("the icon-cli binary is built");
+        cmd.arg("monogram")
+            .arg("--text")
+            .arg(text)
+            .arg("--font")
+            .arg(&font_path)
+            .arg("--output")
+            .arg(output)
+            .arg("--style")
+            .arg("glassmorphism");
+        cmd
+    };
+
+    for name in ["monogram.svg", "monogram.png"] {
+        let output = fixture.path("out").join(name);
+        monogram(output.clone(), "\u{E000}\u{E000}").assert().success();
+        assert!(output.exists());
+    }
+    let svg = fixture.read("out", "monogram.svg")?;
+    assert!(svg.contains(r#"class="icon-fg""#));
+    let png = fs::read(fixture.path("out").join("monogram.png"))?;
+    assert!(png.starts_with(b"\x89PNG"));
+
+    monogram(fixture.path("too-long.svg"), "ABCD")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 to 3 letters"));
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_animation() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--animation")
+        .arg("pulse")
+        .arg("--animate-on")
+        .arg("hover")
+        .arg("--animation-duration")
+        .arg("0.8")
+        .arg("--optimize")
+        .arg("safe");
+    cmd.assert().success();
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains("#animated-icon:hover .icon-anim{animation:icon-pulse 0.8s ease-in-out infinite}"), "{}", svg);
+    assert!(svg.contains(r#"<g id="animated-icon">"#));
+
+    let mut cmd = fixture.mass_export("smil", "neumorphism");
+    cmd.arg("--animation")
+        .arg("draw-on")
+        .arg("--animation-syntax")
+        .arg("smil")
+        .arg("--format")
+        .arg("svg")
+        .arg("--format")
+        .arg("png");
+    cmd.assert().success();
+    let svg = fixture.read("smil", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains(r#"<animate attributeName="stroke-dashoffset""#));
+    assert!(fixture.path("smil").join("test_icon-neumorphism.png").exists());
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_states() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--state")
+        .arg("pressed")
+        .arg("--state")
+        .arg("disabled")
+        .arg("--format")
+        .arg("svg")
+        .arg("--format")
+        .arg("png");
+    cmd.assert().success();
+    let pressed = fixture.read("output", "test_icon-neumorphism-pressed.svg")?;
+    assert!(pressed.contains(r#"operator="out""#));
+    let disabled = fixture.read("output", "test_icon-neumorphism-disabled.svg")?;
+    assert!(disabled.contains(r#"<g opacity="0.4">"#));
+    assert!(fixture.path("output").join("test_icon-neumorphism-disabled.png").exists());
+    assert!(!fixture.path("output").join("test_icon-neumorphism.svg").exists());
+
+    let mut cmd = fixture.mass_export("stateful", "neumorphism");
+    cmd.arg("--state").arg("hover").arg("--stateful").arg("--optimize").arg("safe");
+    cmd.assert().success();
+    let svg = fixture.read("stateful", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains(".state-hover{display:inline}"), "{}", svg);
+    assert!(svg.contains(r#"class="icon-state state-hover""#));
+    Ok(())
+}
+
+#[test]
+fn test_mass_export_pattern() -> Result<(), Box<dyn std::error::Error>> {
+    let fixture = Fixture::new()?;
+    let mut cmd = fixture.mass_export("output", "neumorphism");
+    cmd.arg("--pattern")
+        .arg("stripes,color=#ffffff,scale=8,opacity=0.3")
+        .arg("--format")
+        .arg("svg")
+        .arg("--format")
+        .arg("png");
+    cmd.assert().success();
+    let svg = fixture.read("output", "test_icon-neumorphism.svg")?;
+    assert!(svg.contains(r#"<pattern id="base-pattern" patternUnits="userSpaceOnUse" width="8" height="8" patternTransform="rotate(45)">"#));
+    assert!(svg.contains(r#"fill="url(#base-pattern)" opacity="0.3"/>"#));
+    assert!(fixture.path("output").join("test_icon-neumorphism.png").exists());
+
+    let mut cmd = fixture.mass_export("bad", "neumorphism");
+    cmd.arg("--pattern").arg("image:missing.png");
+    cmd.assert().failure().stderr(predicate::str::contains("Failed to read pattern image"));
+    Ok(())
+}
@@ -0,0 +1,377 @@
+use crate::geometry;
+use crate::path_data::{format_number, parse_path, transform_path};
+use crate::svg_processor::{self, SvgIcon};
+use crate::{CustomStyles, IconEngineError, StylePreset};
+
+/// How much a pulse grows the glyph at its peak.
+const PULSE_SCALE: f32 = 1.08;
+/// How much breathing grows the neumorphism shadows at their peak.
+const BREATHE_SCALE: f32 = 1.5;
+/// The share of a draw-on spent tracing the outline before the fill fades in.
+const DRAW_SHARE: f32 = 0.7;
+/// The width, in canvas units, of the outline traced by a draw-on.
+const DRAW_STROKE_WIDTH: f32 = 1.5;
+/// The id of the group wrapping an animated icon. Hover animations start on
+/// its pointer events and the stylesheet rules are scoped under it, so they
+/// leave other icons in the same document alone once
+/// [`crate::sprite::namespace_ids`] has made it unique.
+pub const ANIMATION_ROOT_ID: &str = "animated-icon";
+
+/// The motion applied to the icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum AnimationKind {
+    /// Scales the glyph up and back around its center, repeatedly.
+    Pulse,
+    /// Rotates the glyph around its center, repeatedly.
+    Spin,
+    /// Grows and shrinks the neumorphism shadows, repeatedly.
+    Breathe,
+    /// Traces the glyph's outline, then fades its fill in, once.
+    DrawOn,
+}
+
+/// The markup an animation is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum AnimationSyntax {
+    /// An embedded `<style>` with CSS keyframes.
+    #[default]
+    Css,
+    /// SMIL `<animate>` elements, which also play in `<img>` tags.
+    Smil,
+}
+
+/// When an animation plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum AnimationTrigger {
+    /// As soon as the icon is shown.
+    #[default]
+    Always,
+    /// While the pointer is over the icon.
+    Hover,
+}
+
+/// An animation added to the icon by [`crate::generate_icon`].
+///
+/// Animations only ever move away from and back to the icon as drawn
+/// without them, so consumers that strip `<style>` or SMIL elements still
+/// get the complete static icon. Breathing animates filter attributes, which
+/// CSS cannot reach, so it is always written in SMIL.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Animation {
+    pub kind: AnimationKind,
+    pub syntax: AnimationSyntax,
+    pub trigger: AnimationTrigger,
+    /// The length of one cycle in seconds.
+    pub duration: f32,
+}
+
+impl Animation {
+    /// An animation that plays on load, in CSS, at the kind's usual pace.
+    pub fn new(kind: AnimationKind) -> Self {
+        let duration = match kind {
+            AnimationKind::Pulse => 1.2,
+            AnimationKind::Spin => 2.0,
+            AnimationKind::Breathe => 3.0,
+            AnimationKind::DrawOn => 1.5,
+        };
+        Self { kind, syntax: AnimationSyntax::Css, trigger: AnimationTrigger::Always, duration }
+    }
+
+    /// Returns the SMIL timing attributes, starting with a space.
+    pub(crate) fn smil_timing(&self, repeat: bool) -> String {
+        let mut timing = format!(r#" dur="{}s""#, format_number(self.duration));
+        if repeat {
+            timing.push_str(r#" repeatCount="indefinite""#);
+        } else {
+            timing.push_str(r#" fill="freeze""#);
+        }
+        if self.trigger == AnimationTrigger::Hover {
+            timing.push_str(&format!(r#" begin="{}.mouseover""#, ANIMATION_ROOT_ID));
+            if repeat {
+                timing.push_str(&format!(r#" end="{}.mouseout""#, ANIMATION_ROOT_ID));
+            }
+        }
+        timing
+    }
+
+    /// Returns the SMIL `values` of a breathing shadow attribute at rest value `value`.
+    pub(crate) fn breathe_values(value: f32) -> String {
+        let peak = format_number(value * BREATHE_SCALE);
+        let value = format_number(value);
+        format!("{};{};{}", value, peak, value)
+    }
+
+    /// Returns the CSS selector prefix that limits a rule to the trigger.
+    fn css_scope(&self) -> String {
+        match self.trigger {
+            AnimationTrigger::Always => format!("#{} ", ANIMATION_ROOT_ID),
+            AnimationTrigger::Hover => format!("#{}:hover ", ANIMATION_ROOT_ID),
+        }
+    }
+}
+
+/// Wraps the icon's content in the group animations refer to, for animated
+/// icons.
+pub(crate) fn animation_root(styles: &CustomStyles, content: String) -> String {
+    match styles.animation {
+        Some(_) => format!(r#"<g id="{}">{}</g>"#, ANIMATION_ROOT_ID, content),
+        None => content,
+    }
+}
+
+/// The foreground of an animated icon and the stylesheet driving it.
+pub(crate) struct AnimatedForeground {
+    /// A `<style>` element, or an empty string for SMIL animations.
+    pub style: String,
+    pub foreground: String,
+}
+
+/// Adds `CustomStyles::animation` to the foreground markup. Breathing is
+/// added to the shadow filter by the style generator instead.
+pub(crate) fn animate_foreground(
+    icon: &SvgIcon,
+    styles: &CustomStyles,
+    preset: StylePreset,
+    foreground: String,
+    icon_color: &str,
+) -> Result<AnimatedForeground, IconEngineError> {
+    let Some(animation) = styles.animation else {
+        return Ok(AnimatedForeground { style: String::new(), foreground });
+    };
+    if animation.duration.is_nan() || animation.duration <= 0.0 {
+        return Err(IconEngineError::InvalidInput(format!(
+            "Animation duration must be positive, got {}",
+            animation.duration
+        )));
+    }
+
+    let fit = svg_processor::placement_matrix(icon, styles)?;
+    let segments = parse_path(&icon.path_data)?;
+    let css = animation.syntax == AnimationSyntax::Css;
+    let scope = animation.css_scope();
+    let root = format!("#{} ", ANIMATION_ROOT_ID);
+
+    let (style, foreground) = match animation.kind {
+        AnimationKind::Breathe => {
+            if preset != StylePreset::Neumorphism || !styles.shadows {
+                return Err(IconEngineError::InvalidInput(
+                    "Breathing animates the neumorphism shadows, which this icon does not have".to_string(),
+                ));
+            }
+            (String::new(), foreground)
+        }
+        AnimationKind::Pulse | AnimationKind::Spin => {
+            // The center of the geometry the fit measured, layers included.
+            let center = geometry::bounds(&transform_path(&svg_processor::fit_segments(icon, styles), &fit))
+                .unwrap_or_else(|| styles.content_box())
+                .center();
+            let (cx, cy) = (format_number(center.x), format_number(center.y));
+            let pulse = animation.kind == AnimationKind::Pulse;
+            if css {
+                let keyframes = if pulse {
+                    format!("@keyframes icon-pulse{{50%{{transform:scale({})}}}}", PULSE_SCALE)
+                } else {
+                    "@keyframes icon-spin{to{transform:rotate(360deg)}}".to_string()
+                };
+                let (name, easing) = if pulse { ("icon-pulse", "ease-in-out") } else { ("icon-spin", "linear") };
+                let style = format!(
+                    "{root}.icon-anim{{transform-box:view-box;transform-origin:{cx}px {cy}px}}{keyframes}{scope}.icon-anim{{animation:{name} {duration}s {easing} infinite}}",
+                    root = root,
+                    cx = cx,
+                    cy = cy,
+                    keyframes = keyframes,
+                    scope = scope,
+                    name = name,
+                    duration = format_number(animation.duration),
+                    easing = easing
+                );
+                (style, format!(r#"<g class="icon-anim">{}</g>"#, foreground))
+            } else {
+                let timing = animation.smil_timing(true);
+                let motion = if pulse {
+                    // Scaling about the center is a scale plus a translation
+                    // keeping the center in place.
+                    let shift = |c: f32| format_number(c * (1.0 - PULSE_SCALE));
+                    format!(
+                        r#"<animateTransform attributeName="transform" type="translate" values="0 0;{} {};0 0" additive="sum"{t}/><animateTransform attributeName="transform" type="scale" values="1;{};1" additive="sum"{t}/>"#,
+                        shift(center.x),
+                        shift(center.y),
+                        PULSE_SCALE,
+                        t = timing
+                    )
+                } else {
+                    format!(
+                        r#"<animateTransform attributeName="transform" type="rotate" from="0 {cx} {cy}" to="360 {cx} {cy}"{t}/>"#,
+                        cx = cx,
+                        cy = cy,
+                        t = timing
+                    )
+                };
+                (String::new(), format!(r#"<g class="icon-anim">{}{}</g>"#, foreground, motion))
+            }
+        }
+        AnimationKind::DrawOn => {
+            // The outline is traced in the coordinates the path is written in.
+            let (length, width) = if styles.bake_transform {
+                (geometry::length(&transform_path(&segments, &fit)), DRAW_STROKE_WIDTH)
+            } else {
+                let scale = (fit.a * fit.d - fit.b * fit.c).abs().sqrt();
+                (geometry::length(&segments), DRAW_STROKE_WIDTH / scale)
+            };
+            let (length, width) = (format_number(length), format_number(width));
+            if css {
+                let name = format!("icon-draw-{}", length.replace('.', "_"));
+                let share = format_number(DRAW_SHARE * 100.0);
+                let style = format!(
+                    "{root}.icon-fg{{stroke:{color};stroke-width:0;stroke-dasharray:{length}}}@keyframes {name}{{0%{{stroke-dashoffset:{length};stroke-width:{width}px;fill-opacity:0}}{share}%{{stroke-dashoffset:0;stroke-width:{width}px;fill-opacity:0}}100%{{stroke-dashoffset:0;stroke-width:0;fill-opacity:1}}}}{scope}.icon-fg{{animation:{name} {duration}s ease-in-out both}}",
+                    root = root,
+                    color = icon_color,
+                    length = length,
+                    name = name,
+                    width = width,
+                    share = share,
+                    scope = scope,
+                    duration = format_number(animation.duration)
+                );
+                (style, foreground)
+            } else {
+                let timing = animation.smil_timing(false);
+                let key_times = format!(r#" keyTimes="0;{};1""#, format_number(DRAW_SHARE));
+                let children = format!(
+                    r#"<animate attributeName="stroke-dashoffset" values="{l};0;0"{k}{t}/><animate attributeName="stroke-width" values="{w};{w};0"{k}{t}/><animate attributeName="fill-opacity" values="0;0;1"{k}{t}/>"#,
+                    l = length,
+                    w = width,
+                    k = key_times,
+                    t = timing
+                );
+                let attributes = format!(
+                    r#" stroke="{}" stroke-width="0" stroke-dasharray="{}">{}</path>"#,
+                    icon_color, length, children
+                );
+                (String::new(), with_main_path_content(&foreground, &attributes))
+            }
+        }
+    };
+
+    let style = if style.is_empty() { style } else { format!("<style>{}</style>", style) };
+    Ok(AnimatedForeground { style, foreground })
+}
+
+/// Replaces the `/>` closing the `icon-fg` path with `replacement`.
+fn with_main_path_content(foreground: &str, replacement: &str) -> String {
+    let Some(start) = foreground.find(r#"<path class="icon-fg""#) else {
+        return foreground.to_string();
+    };
+    match foreground[start..].find("/>") {
+        Some(end) => {
+            let end = start + end;
+            format!("{}{}{}", &foreground[..end], replacement, &foreground[end + 2..])
+        }
+        None => foreground.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_icon;
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"/></svg>"#;
+
+    fn animated(kind: AnimationKind, syntax: AnimationSyntax) -> CustomStyles {
+        let animation = Animation { syntax, trigger: AnimationTrigger::Hover, ..Animation::new(kind) };
+        CustomStyles { animation: Some(animation), ..CustomStyles::default() }
+    }
+
+    /// Drops `<style>` elements and SMIL animation elements, as sanitizers do.
+    fn strip_animation(svg: &str) -> String {
+        let mut out = svg.to_string();
+        for (open, close) in [("<style>", "</style>"), ("<animateTransform ", "/>"), ("<animate ", "/>")] {
+            while let Some(start) = out.find(open) {
+                let end = start + out[start..].find(close).unwrap() + close.len();
+                out.replace_range(start..end, "");
+            }
+        }
+        // Unwrap the root and animated groups, which always close last.
+        for group in [r#"<g id="animated-icon">"#, r#"<g class="icon-anim">"#] {
+            if let Some(start) = out.find(group) {
+                out.replace_range(start..start + group.len(), "");
+                let end = out.rfind("</g>").unwrap();
+                out.replace_range(end..end + "</g>".len(), "");
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn css_animations_fall_back_to_the_static_icon() {
+        let still = generate_icon(TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        for kind in [AnimationKind::Pulse, AnimationKind::Spin, AnimationKind::DrawOn] {
+            let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(kind, AnimationSyntax::Css)).unwrap();
+            roxmltree::Document::parse(&svg).unwrap();
+            assert!(svg.contains("<style>") && svg.contains("#animated-icon:hover "), "{}", svg);
+            assert_eq!(strip_animation(&svg), still);
+        }
+    }
+
+    #[test]
+    fn draw_on_uses_the_outline_length() {
+        // The triangle's sides are 2 * sqrt(10^2 + 20^2) + 20 viewBox units long.
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::DrawOn, AnimationSyntax::Css)).unwrap();
+        assert!(svg.contains("#animated-icon .icon-fg{stroke:#333333;stroke-width:0;stroke-dasharray:64.721}"), "{}", svg);
+        // 1.5px at the fit scale of 4.
+        assert!(svg.contains("stroke-width:0.375px"));
+
+        let smil = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::DrawOn, AnimationSyntax::Smil)).unwrap();
+        roxmltree::Document::parse(&smil).unwrap();
+        assert!(smil.contains(r#"stroke-dasharray="64.721"><animate attributeName="stroke-dashoffset" values="64.721;0;0""#));
+        assert!(smil.contains(r#"fill="freeze" begin="animated-icon.mouseover"/></path>"#));
+    }
+
+    #[test]
+    fn motion_centers_on_the_fitted_geometry() {
+        // A second shape is not drawn, but a glyph layer is; the tight fit
+        // centers the glyph and layer together, and so does the spin.
+        let source = r#"<svg viewBox="0 0 24 24"><path d="M4 2H20V22H4Z"/><circle cx="2" cy="2" r="2"/></svg>"#;
+        let layer = crate::glyph_stack::GlyphLayer {
+            transform: crate::path_data::Transform::translate(12.0, 0.0),
+            ..crate::glyph_stack::GlyphLayer::new(source)
+        };
+        let styles = CustomStyles {
+            fit: crate::FitMode::Tight,
+            glyph_layers: vec![layer],
+            ..animated(AnimationKind::Spin, AnimationSyntax::Smil)
+        };
+        let svg = generate_icon(source, StylePreset::Neumorphism, &styles).unwrap();
+        assert!(svg.contains(r#"from="0 64 64" to="360 64 64""#), "{}", svg);
+    }
+
+    #[test]
+    fn namespacing_keeps_triggers_and_rules_on_their_icon() {
+        let css = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::Pulse, AnimationSyntax::Css)).unwrap();
+        let css = crate::sprite::namespace_ids(&css, "home");
+        assert!(css.contains(r#"<g id="home-animated-icon">"#));
+        assert!(css.contains("#home-animated-icon .icon-anim{transform-box:view-box"), "{}", css);
+        assert!(css.contains("#home-animated-icon:hover .icon-anim{animation:icon-pulse"));
+
+        let smil = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::Spin, AnimationSyntax::Smil)).unwrap();
+        let smil = crate::sprite::namespace_ids(&smil, "home");
+        assert!(smil.contains(r#"begin="home-animated-icon.mouseover" end="home-animated-icon.mouseout""#));
+    }
+
+    #[test]
+    fn smil_motion_and_breathing() {
+        let spin = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::Spin, AnimationSyntax::Smil)).unwrap();
+        assert!(spin.contains(r#"type="rotate" from="0 64 64" to="360 64 64" dur="2s" repeatCount="indefinite" begin="animated-icon.mouseover" end="animated-icon.mouseout""#));
+        assert!(spin.contains(r#"<g id="animated-icon">"#));
+
+        let breathe = generate_icon(TEST_SVG, StylePreset::Neumorphism, &animated(AnimationKind::Breathe, AnimationSyntax::Css)).unwrap();
+        roxmltree::Document::parse(&breathe).unwrap();
+        assert!(breathe.contains(r#"<animate attributeName="stdDeviation" values="6.144;9.216;6.144""#), "{}", breathe);
+        let flat = CustomStyles { shadows: false, ..animated(AnimationKind::Breathe, AnimationSyntax::Smil) };
+        assert!(generate_icon(TEST_SVG, StylePreset::Neumorphism, &flat).is_err());
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashSet;
+
+use serde_json::json;
+
+use crate::raster::RasterImage;
+use crate::IconEngineError;
+
+/// The position of one icon inside a packed atlas.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AtlasFrame {
+    pub name: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A packed texture atlas and the frames it contains.
+#[derive(Debug, Clone)]
+pub struct Atlas {
+    pub image: RasterImage,
+    /// The frames in name order.
+    pub frames: Vec<AtlasFrame>,
+}
+
+/// The largest width or height of an atlas, the texture limit of most GPUs.
+pub const MAX_ATLAS_SIZE: u32 = 16384;
+
+/// Packs named images into a single atlas using shelf packing.
+///
+/// Images are sorted by name, then placed tallest first, left to right in
+/// rows no wider than `max_width`, with `spacing` transparent pixels between
+/// them. The same input therefore always yields the same atlas, whatever
+/// its order. Names must be unique, and atlases larger than
+/// [`MAX_ATLAS_SIZE`] on either side are rejected.
+pub fn pack_atlas(
+    mut images: Vec<(String, RasterImage)>,
+    spacing: u32,
+    max_width: u32,
+) -> Result<Atlas, IconEngineError> {
+    if images.is_empty() {
+        return Err(IconEngineError::InvalidInput("An atlas needs at least one image".to_string()));
+    }
+    if max_width > MAX_ATLAS_SIZE {
+        return Err(too_large("wide", max_width));
+    }
+    images.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut names = HashSet::new();
+    if let Some((name, _)) = images.iter().find(|(name, _)| !names.insert(name)) {
+        return Err(IconEngineError::InvalidInput(format!("Duplicate atlas frame name: {}", name)));
+    }
+
+    let widest = images.iter().map(|(_, i)| i.width).max().unwrap_or(0);
+    if widest > max_width {
+        return Err(IconEngineError::InvalidInput(format!(
+            "An image is {} pixels wide, more than the atlas maximum of {}",
+            widest, max_width
+        )));
+    }
+
+    // Aim for a roughly square atlas, but never narrower than the widest image.
+    let area: u64 = images
+        .iter()
+        .map(|(_, i)| (i.width + spacing) as u64 * (i.height + spacing) as u64)
+        .sum();
+    let width_limit = ((area as f64).sqrt().ceil() as u32).next_power_of_two().clamp(widest, max_width);
+
+    // A stable sort keeps the name order among images of equal height.
+    images.sort_by_key(|(_, image)| std::cmp::Reverse(image.height));
+
+    // Positions are tracked in u64 so that checking them against the maximum
+    // cannot overflow; anything that passes fits in u32.
+    let mut frames = Vec::with_capacity(images.len());
+    let (mut x, mut y, mut row_height, mut atlas_width) = (0u64, 0u64, 0u64, 0u64);
+    for (name, image) in &images {
+        let (width, height) = (image.width as u64, image.height as u64);
+        if x > 0 && x + width > width_limit as u64 {
+            x = 0;
+            y += row_height + spacing as u64;
+            row_height = 0;
+        }
+        if y + height > MAX_ATLAS_SIZE as u64 {
+            return Err(too_large("tall", (y + height).min(u32::MAX as u64) as u32));
+        }
+        frames.push(AtlasFrame { name: name.clone(), x: x as u32, y: y as u32, width: image.width, height: image.height });
+        atlas_width = atlas_width.max(x + width);
+        row_height = row_height.max(height);
+        x += width + spacing as u64;
+    }
+    let (atlas_width, atlas_height) = (atlas_width as usize, (y + row_height) as usize);
+
+    let byte_len = atlas_width
+        .checked_mul(atlas_height)
+        .and_then(|pixels| pixels.checked_mul(4))
+        .ok_or_else(|| IconEngineError::InvalidInput("The atlas is too large to allocate".to_string()))?;
+    let mut data = vec![0u8; byte_len];
+    for (frame, (_, image)) in frames.iter().zip(&images) {
+        let row_bytes = image.width as usize * 4;
+        for row in 0..image.height as usize {
+            let src = row * row_bytes;
+            let dst = ((frame.y as usize + row) * atlas_width + frame.x as usize) * 4;
+            data[dst..dst + row_bytes].copy_from_slice(&image.data[src..src + row_bytes]);
+        }
+    }
+
+    frames.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(Atlas {
+        image: RasterImage { width: atlas_width as u32, height: atlas_height as u32, data },
+        frames,
+    })
+}
+
+fn too_large(dimension: &str, size: u32) -> IconEngineError {
+    IconEngineError::InvalidInput(format!(
+        "The atlas would be {} pixels {}, more than the maximum of {}",
+        size, dimension, MAX_ATLAS_SIZE
+    ))
+}
+
+impl Atlas {
+    /// Describes the atlas in TexturePacker's "JSON (Hash)" format.
+    pub fn to_texturepacker_json(&self, image_file: &str) -> Result<String, IconEngineError> {
+        let frames: serde_json::Map<String, serde_json::Value> = self
+            .frames
+            .iter()
+            .map(|f| {
+                let entry = json!({
+                    "frame": { "x": f.x, "y": f.y, "w": f.width, "h": f.height },
+                    "rotated": false,
+                    "trimmed": false,
+                    "spriteSourceSize": { "x": 0, "y": 0, "w": f.width, "h": f.height },
+                    "sourceSize": { "w": f.width, "h": f.height },
+                });
+                (f.name.clone(), entry)
+            })
+            .collect();
+
+        let doc = json!({
+            "frames": frames,
+            "meta": {
+                "app": "icon-engine",
+                "version": "1.0",
+                "image": image_file,
+                "format": "RGBA8888",
+                "size": { "w": self.image.width, "h": self.image.height },
+                "scale": "1",
+            },
+        });
+        serde_json::to_string_pretty(&doc).map_err(|e| IconEngineError::InvalidInput(e.to_string()))
+    }
+
+    /// Builds a stylesheet with one `background-position` class per frame,
+    /// named `{class_prefix}-{frame name}`.
+    pub fn to_css(&self, image_file: &str, class_prefix: &str) -> String {
+        let mut css = format!(
+            ".{}{{background-image:url({});background-repeat:no-repeat;display:inline-block}}\n",
+            class_prefix, image_file
+        );
+        for f in &self.frames {
+            css.push_str(&format!(
+                ".{}-{}{{background-position:-{}px -{}px;width:{}px;height:{}px}}\n",
+                class_prefix, f.name, f.x, f.y, f.width, f.height
+            ));
+        }
+        css
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(size: u32, value: u8) -> RasterImage {
+        RasterImage { width: size, height: size, data: vec![value; (size * size * 4) as usize] }
+    }
+
+    #[test]
+    fn packing_is_deterministic_and_non_overlapping() {
+        let images = vec![
+            ("b".to_string(), solid(16, 1)),
+            ("a".to_string(), solid(32, 2)),
+            ("c".to_string(), solid(16, 3)),
+        ];
+        let first = pack_atlas(images.clone(), 2, 64).unwrap();
+        let mut reversed = images;
+        reversed.reverse();
+        let second = pack_atlas(reversed, 2, 64).unwrap();
+        assert_eq!(first.frames, second.frames);
+        assert_eq!(first.image, second.image);
+
+        let names: Vec<&str> = first.frames.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["a", "b", "c"]);
+        assert_eq!((first.frames[0].x, first.frames[0].y), (0, 0));
+        assert_eq!((first.frames[1].x, first.frames[1].y), (34, 0));
+        assert_eq!((first.frames[2].x, first.frames[2].y), (0, 34));
+
+        // Pixels are copied into their frame.
+        let c = &first.frames[2];
+        let offset = ((c.y * first.image.width + c.x) * 4) as usize;
+        assert_eq!(first.image.data[offset], 3);
+    }
+
+    #[test]
+    fn metadata_lists_every_frame() {
+        let atlas = pack_atlas(vec![("home".to_string(), solid(8, 0))], 0, 64).unwrap();
+        let json = atlas.to_texturepacker_json("atlas.png").unwrap();
+        assert!(json.contains("\"home\""));
+        assert!(json.contains("\"image\": \"atlas.png\""));
+        assert!(atlas.to_css("atlas.png", "icon").contains(".icon-home{background-position:-0px -0px;width:8px;height:8px}"));
+    }
+
+    #[test]
+    fn rejects_images_wider_than_the_atlas() {
+        assert!(pack_atlas(vec![("big".to_string(), solid(128, 0))], 0, 64).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_atlases_and_duplicate_names() {
+        // Two rows of 9000 pixel tall images exceed the maximum height.
+        let tall = |name: &str| (name.to_string(), RasterImage { width: 1, height: 9000, data: vec![0; 9000 * 4] });
+        assert!(pack_atlas(vec![tall("a"), tall("b")], 0, 1).is_err());
+        assert!(pack_atlas(vec![tall("a")], 0, 1).is_ok());
+        assert!(pack_atlas(vec![("a".to_string(), solid(8, 0))], 0, MAX_ATLAS_SIZE + 1).is_err());
+        assert!(pack_atlas(vec![("a".to_string(), solid(8, 0)), ("a".to_string(), solid(8, 1))], 0, 64).is_err());
+    }
+}
@@ -0,0 +1,218 @@
+/// An opaque sRGB color with 8-bit channels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Parses a hex color (`#RGB` or `#RRGGBB`) or one of the basic color
+    /// keywords (`white`, `black`).
+    pub fn parse(input: &str) -> Option<Rgb> {
+        let trimmed = input.trim();
+        match trimmed.to_ascii_lowercase().as_str() {
+            "white" => return Some(Rgb::new(255, 255, 255)),
+            "black" => return Some(Rgb::new(0, 0, 0)),
+            _ => {}
+        }
+
+        let hex = trimmed.strip_prefix('#')?;
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        match hex.len() {
+            3 => {
+                let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+                Some(Rgb::new(digit(0)?, digit(1)?, digit(2)?))
+            }
+            6 => {
+                let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+                Some(Rgb::new(byte(0)?, byte(2)?, byte(4)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Formats the color as a lowercase `#rrggbb` string.
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+
+    /// Converts the color to HSL, with all components in the `0.0..=1.0` range.
+    pub fn to_hsl(self) -> (f32, f32, f32) {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+
+        if max == min {
+            return (0.0, 0.0, l);
+        }
+
+        let d = max - min;
+        let s = if l > 0.5 { d / (2.0 - max - min) } else { d / (max + min) };
+        let h = if max == r {
+            (g - b) / d + if g < b { 6.0 } else { 0.0 }
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+
+        (h / 6.0, s, l)
+    }
+
+    /// Builds a color from HSL components in the `0.0..=1.0` range.
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Rgb {
+        if s == 0.0 {
+            let v = (l * 255.0).round() as u8;
+            return Rgb::new(v, v, v);
+        }
+
+        let q = if l < 0.5 { l * (1.0 + s) } else { l + s - l * s };
+        let p = 2.0 * l - q;
+        let channel = |t: f32| {
+            let t = t.rem_euclid(1.0);
+            let v = if t < 1.0 / 6.0 {
+                p + (q - p) * 6.0 * t
+            } else if t < 0.5 {
+                q
+            } else if t < 2.0 / 3.0 {
+                p + (q - p) * (2.0 / 3.0 - t) * 6.0
+            } else {
+                p
+            };
+            (v * 255.0).round() as u8
+        };
+
+        Rgb::new(channel(h + 1.0 / 3.0), channel(h), channel(h - 1.0 / 3.0))
+    }
+
+    /// Returns the WCAG 2.x relative luminance of the color.
+    pub fn relative_luminance(self) -> f32 {
+        let linear = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.03928 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// Returns the WCAG contrast ratio between two colors, from 1.0 to 21.0.
+    pub fn contrast_ratio(self, other: Rgb) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Linearly interpolates between two colors in sRGB space.
+    pub fn mix(self, other: Rgb, t: f32) -> Rgb {
+        let lerp = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        Rgb::new(lerp(self.r, other.r), lerp(self.g, other.g), lerp(self.b, other.b))
+    }
+
+    /// Mirrors the color's lightness while keeping its hue and saturation,
+    /// turning a light-theme color into its dark-theme counterpart.
+    pub fn invert_lightness(self) -> Rgb {
+        let (h, s, l) = self.to_hsl();
+        Rgb::from_hsl(h, s, 1.0 - l)
+    }
+
+    /// Drops the color's saturation, keeping its lightness.
+    pub fn desaturate(self) -> Rgb {
+        let (h, _, l) = self.to_hsl();
+        Rgb::from_hsl(h, 0.0, l)
+    }
+}
+
+/// Mirrors the lightness of a color string, leaving colors that cannot be
+/// parsed (e.g. `rgba(...)` or `url(...)`) untouched.
+pub fn invert_color_str(color: &str) -> String {
+    Rgb::parse(color)
+        .map(|c| c.invert_lightness().to_hex())
+        .unwrap_or_else(|| color.to_string())
+}
+
+/// Turns a color string grey, leaving colors that cannot be parsed untouched.
+pub fn desaturate_color_str(color: &str) -> String {
+    Rgb::parse(color)
+        .map(|c| c.desaturate().to_hex())
+        .unwrap_or_else(|| color.to_string())
+}
+
+/// Parses any color accepted by [`Rgb::parse`] as well as the `rgb(...)` and
+/// `rgba(...)` functions, returning the color and its alpha in `0.0..=1.0`.
+pub fn parse_with_alpha(input: &str) -> Option<(Rgb, f32)> {
+    let trimmed = input.trim();
+    let args = trimmed
+        .strip_prefix("rgba(")
+        .or_else(|| trimmed.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'));
+    let Some(args) = args else {
+        return Rgb::parse(trimmed).map(|c| (c, 1.0));
+    };
+
+    let parts: Vec<&str> = args.split(',').map(str::trim).collect();
+    let channel = |i: usize| parts.get(i)?.parse::<f32>().ok().map(|v| v.clamp(0.0, 255.0).round() as u8);
+    let alpha = match parts.len() {
+        3 => 1.0,
+        4 => parts[3].parse::<f32>().ok()?.clamp(0.0, 1.0),
+        _ => return None,
+    };
+    Some((Rgb::new(channel(0)?, channel(1)?, channel(2)?), alpha))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex_forms() {
+        assert_eq!(Rgb::parse("#ff8000"), Some(Rgb::new(255, 128, 0)));
+        assert_eq!(Rgb::parse("#FFF"), Some(Rgb::new(255, 255, 255)));
+        assert_eq!(Rgb::parse("black"), Some(Rgb::new(0, 0, 0)));
+        assert_eq!(Rgb::parse("#12345"), None);
+        assert_eq!(Rgb::parse("rgba(0,0,0,0.5)"), None);
+    }
+
+    #[test]
+    fn parse_with_alpha_accepts_rgb_functions() {
+        assert_eq!(parse_with_alpha("rgba(255, 255, 255, 0.3)"), Some((Rgb::new(255, 255, 255), 0.3)));
+        assert_eq!(parse_with_alpha("rgb(1,2,3)"), Some((Rgb::new(1, 2, 3), 1.0)));
+        assert_eq!(parse_with_alpha("#333"), Some((Rgb::new(51, 51, 51), 1.0)));
+        assert_eq!(parse_with_alpha("rgba(1,2)"), None);
+    }
+
+    #[test]
+    fn hsl_round_trip() {
+        for color in [Rgb::new(255, 0, 0), Rgb::new(51, 51, 51), Rgb::new(18, 120, 200)] {
+            let (h, s, l) = color.to_hsl();
+            assert_eq!(Rgb::from_hsl(h, s, l), color);
+        }
+    }
+
+    #[test]
+    fn contrast_ratio_extremes() {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 0.01);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn invert_lightness_of_grays() {
+        assert_eq!(invert_color_str("#333333"), "#cccccc");
+        assert_eq!(invert_color_str("#e0e0e0"), "#1f1f1f");
+        assert_eq!(invert_color_str("url(#x)"), "url(#x)");
+    }
+}
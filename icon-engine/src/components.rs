@@ -0,0 +1,563 @@
+use roxmltree::{Document, Node};
+
+use crate::color::parse_with_alpha;
+use crate::path_data::{self, format_number, PathSegment};
+use crate::sprite::{namespace_ids, sanitize_id};
+use crate::states::IconState;
+use crate::style_generator::{
+    base_fill, base_fill_opacity, gradient_points, shadow_colors, shadow_geometry, GLASS_STROKE, GLASS_STROKE_WIDTH,
+};
+use crate::{contrast, generate_icon, glyph_stack, native, overlay, svg_processor, CustomStyles, IconEngineError, StylePreset};
+
+/// The class `generate_icon` puts on the foreground path, whose fill becomes
+/// the component's `color` prop.
+const FOREGROUND_CLASS: &str = "icon-fg";
+
+/// A framework or platform that icons can be wrapped for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum ComponentTarget {
+    /// A typed React function component (`.tsx`).
+    React,
+    /// A Vue single-file component using `<script setup>`.
+    Vue,
+    /// A Svelte component.
+    Svelte,
+    /// An Android `<vector>` drawable resource.
+    VectorDrawable,
+    /// A SwiftUI `Shape` for the glyph and a `View` composing the icon.
+    #[cfg_attr(feature = "cli", value(name = "swiftui"))]
+    SwiftUi,
+}
+
+/// One generated component source file.
+#[derive(Debug, Clone)]
+pub struct Component {
+    /// The exported type name, e.g. `HomeIcon`.
+    pub name: String,
+    pub file_name: String,
+    pub source: String,
+}
+
+/// Turns an icon name like `video player` into a component name like
+/// `VideoPlayerIcon`.
+pub fn component_name(name: &str) -> String {
+    let pascal: String = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase()).unwrap_or_default();
+            std::iter::once(first).chain(chars).collect::<String>()
+        })
+        .collect();
+
+    if pascal.is_empty() || pascal.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("Icon{}", pascal)
+    } else if pascal.ends_with("Icon") {
+        pascal
+    } else {
+        format!("{}Icon", pascal)
+    }
+}
+
+/// Generates the component wrapping one icon for `target`. Components take a
+/// `size` (the rendered width) and a `color` (the glyph fill) that default to
+/// the values in `styles`.
+pub fn generate_component(
+    target: ComponentTarget,
+    icon_name: &str,
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<Component, IconEngineError> {
+    let name = component_name(icon_name);
+    let color = contrast::resolve_icon_color(styles, preset);
+
+    let (file_name, source) = match target {
+        ComponentTarget::React | ComponentTarget::Vue | ComponentTarget::Svelte => {
+            // Ids are namespaced so several components can share a page.
+            let svg = namespace_ids(&generate_icon(icon_data, preset, styles)?, &sanitize_id(icon_name));
+            let doc = Document::parse(&svg).map_err(|e| IconEngineError::SvgParsingError(e.to_string()))?;
+            let view_box = format!("0 0 {} {}", styles.width, styles.height);
+
+            match target {
+                ComponentTarget::React => {
+                    let mut markup = String::new();
+                    write_markup(doc.root_element(), Dialect::Jsx, 6, &mut markup);
+                    (format!("{}.tsx", name), react_component(&name, &view_box, styles.width, &color, &markup))
+                }
+                ComponentTarget::Vue => {
+                    let mut markup = String::new();
+                    write_markup(doc.root_element(), Dialect::Vue, 4, &mut markup);
+                    (format!("{}.vue", name), vue_component(&view_box, styles.width, &color, &markup))
+                }
+                _ => {
+                    let mut markup = String::new();
+                    write_markup(doc.root_element(), Dialect::Svelte, 2, &mut markup);
+                    (format!("{}.svelte", name), svelte_component(&view_box, styles.width, &color, &markup))
+                }
+            }
+        }
+        ComponentTarget::VectorDrawable => {
+            (android_resource_name(icon_name), native::to_vector_drawable(icon_data, preset, styles)?.source)
+        }
+        ComponentTarget::SwiftUi => (format!("{}.swift", name), swiftui_view(&name, icon_data, preset, styles)?),
+    };
+
+    Ok(Component { name, file_name, source })
+}
+
+/// Builds the index file re-exporting every component, for the targets that
+/// use one.
+pub fn index_file(target: ComponentTarget, components: &[Component]) -> Option<(String, String)> {
+    let extension = match target {
+        ComponentTarget::React => "",
+        ComponentTarget::Vue => ".vue",
+        ComponentTarget::Svelte => ".svelte",
+        ComponentTarget::VectorDrawable | ComponentTarget::SwiftUi => return None,
+    };
+    let mut names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+    names.sort();
+    let exports: String = names
+        .iter()
+        .map(|name| format!("export {{ default as {0} }} from \"./{0}{1}\";\n", name, extension))
+        .collect();
+    Some(("index.ts".to_string(), exports))
+}
+
+/// Android resource names may only contain lowercase letters, digits and
+/// underscores.
+fn android_resource_name(icon_name: &str) -> String {
+    let snake: String = sanitize_id(icon_name)
+        .to_ascii_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("ic_{}.xml", snake)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dialect {
+    Jsx,
+    Vue,
+    Svelte,
+}
+
+/// Writes the element children of `node` one per line, binding the
+/// foreground fill to the `color` prop.
+///
+/// Text, such as the CSS of a `<style>` or a badge's label, is written as a
+/// string expression (`{"..."}`, or `{{ "..." }}` in Vue) so its braces and
+/// quotes survive every dialect. Vue drops `<style>` tags from templates and
+/// Svelte reserves them, so those two render it as a dynamic element.
+fn write_markup(node: Node, dialect: Dialect, indent: usize, out: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            let text = child.text().unwrap_or_default();
+            if !text.trim().is_empty() {
+                let expression = match dialect {
+                    Dialect::Jsx | Dialect::Svelte => format!("{{{}}}", js_string(text)),
+                    Dialect::Vue => format!("{{{{ {} }}}}", js_string(text)),
+                };
+                out.push_str(&format!("{}{}\n", " ".repeat(indent), expression));
+            }
+            continue;
+        }
+        if !child.is_element() {
+            continue;
+        }
+
+        let tag = match (child.tag_name().name(), dialect) {
+            ("style", Dialect::Vue) => r#"component is="style""#.to_string(),
+            ("style", Dialect::Svelte) => r#"svelte:element this="style""#.to_string(),
+            (tag, _) => tag.to_string(),
+        };
+        let closing_tag = tag.split(' ').next().unwrap_or_default();
+        out.push_str(&" ".repeat(indent));
+        out.push('<');
+        out.push_str(&tag);
+
+        let is_foreground = child.attribute("class") == Some(FOREGROUND_CLASS);
+        for attr in child.attributes() {
+            out.push(' ');
+            if is_foreground && attr.name() == "fill" {
+                out.push_str(match dialect {
+                    Dialect::Jsx | Dialect::Svelte => "fill={color}",
+                    Dialect::Vue => ":fill=\"color\"",
+                });
+                continue;
+            }
+            let name = match dialect {
+                Dialect::Jsx => jsx_attribute_name(attr.name()),
+                Dialect::Vue | Dialect::Svelte => attr.name().to_string(),
+            };
+            let value = attr.value().replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;");
+            out.push_str(&format!("{}=\"{}\"", name, value));
+        }
+
+        let has_content = child
+            .children()
+            .any(|n| n.is_element() || (n.is_text() && !n.text().unwrap_or_default().trim().is_empty()));
+        if has_content {
+            out.push_str(">\n");
+            write_markup(child, dialect, indent + 2, out);
+            out.push_str(&format!("{}</{}>\n", " ".repeat(indent), closing_tag));
+        } else {
+            out.push_str(" />\n");
+        }
+    }
+}
+
+/// Quotes text as a JavaScript string literal. Braces and `<` are escaped
+/// too, so the literal cannot end a Vue interpolation or open a tag.
+fn js_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '{' | '}' | '<' | '\u{2028}' | '\u{2029}' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Maps an SVG attribute to its React prop, e.g. `stop-color` to `stopColor`.
+fn jsx_attribute_name(name: &str) -> String {
+    if name == "class" {
+        return "className".to_string();
+    }
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '-' || c == ':' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn react_component(name: &str, view_box: &str, size: u32, color: &str, markup: &str) -> String {
+    format!(
+        r#"import type {{ SVGProps }} from "react";
+
+export interface {name}Props extends SVGProps<SVGSVGElement> {{
+  /** The rendered width and height. */
+  size?: number | string;
+  /** The fill of the glyph. */
+  color?: string;
+}}
+
+export default function {name}({{ size = {size}, color = "{color}", ...props }}: {name}Props) {{
+  return (
+    <svg width={{size}} height={{size}} viewBox="{view_box}" xmlns="http://www.w3.org/2000/svg" {{...props}}>
+{markup}    </svg>
+  );
+}}
+"#,
+        name = name,
+        size = size,
+        color = color,
+        view_box = view_box,
+        markup = markup
+    )
+}
+
+fn vue_component(view_box: &str, size: u32, color: &str, markup: &str) -> String {
+    format!(
+        r#"<script setup lang="ts">
+withDefaults(
+  defineProps<{{
+    /** The rendered width and height. */
+    size?: number | string;
+    /** The fill of the glyph. */
+    color?: string;
+  }}>(),
+  {{ size: {size}, color: "{color}" }},
+);
+</script>
+
+<template>
+  <svg :width="size" :height="size" viewBox="{view_box}" xmlns="http://www.w3.org/2000/svg">
+{markup}  </svg>
+</template>
+"#,
+        size = size,
+        color = color,
+        view_box = view_box,
+        markup = markup
+    )
+}
+
+fn svelte_component(view_box: &str, size: u32, color: &str, markup: &str) -> String {
+    format!(
+        r#"<script lang="ts">
+  /** The rendered width and height. */
+  export let size: number | string = {size};
+  /** The fill of the glyph. */
+  export let color = "{color}";
+</script>
+
+<svg width={{size}} height={{size}} viewBox="{view_box}" xmlns="http://www.w3.org/2000/svg" {{...$$restProps}}>
+{markup}</svg>
+"#,
+        size = size,
+        color = color,
+        view_box = view_box,
+        markup = markup
+    )
+}
+
+/// Formats a color as a SwiftUI `Color` initializer.
+fn swift_color(color: &str) -> Result<String, IconEngineError> {
+    let (rgb, alpha) = parse_with_alpha(color)
+        .ok_or_else(|| IconEngineError::InvalidInput(format!("Unsupported color for SwiftUI: {}", color)))?;
+    let channel = |v: u8| format_number(v as f32 / 255.0);
+    Ok(format!(
+        "Color(red: {}, green: {}, blue: {}, opacity: {})",
+        channel(rgb.r),
+        channel(rgb.g),
+        channel(rgb.b),
+        format_number(alpha)
+    ))
+}
+
+/// Builds a SwiftUI file with a `Shape` tracing the glyph and a `View` that
+/// layers it over the styled base, scaled by `size`. The glyph is fitted and
+/// placed as in `generate_icon`.
+fn swiftui_view(
+    name: &str,
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<String, IconEngineError> {
+    overlay::reject_overlays(styles, "SwiftUI")?;
+    glyph_stack::reject_glyph_layers(styles, "SwiftUI")?;
+    let icon = svg_processor::parse_svg(icon_data)?;
+    // The glyph is traced where the SVG output draws it, so fit and placement
+    // carry over.
+    let placed = path_data::transform_path(
+        &path_data::parse_path(&icon.path_data)?,
+        &svg_processor::placement_matrix(&icon, styles)?,
+    );
+    let segments = path_data::arcs_to_cubics(&placed);
+    let point = |p: path_data::Point| format!("CGPoint(x: {}, y: {})", format_number(p.x), format_number(p.y));
+    let commands: String = segments
+        .iter()
+        .map(|segment| {
+            let command = match *segment {
+                PathSegment::MoveTo(p) => format!("path.move(to: {})", point(p)),
+                PathSegment::LineTo(p) => format!("path.addLine(to: {})", point(p)),
+                PathSegment::CubicTo(c1, c2, p) => {
+                    format!("path.addCurve(to: {}, control1: {}, control2: {})", point(p), point(c1), point(c2))
+                }
+                PathSegment::QuadTo(c, p) => format!("path.addQuadCurve(to: {}, control: {})", point(p), point(c)),
+                PathSegment::ClosePath => "path.closeSubpath()".to_string(),
+                // Arcs were converted to cubics above.
+                PathSegment::ArcTo { .. } => String::new(),
+            };
+            format!("        {}\n", command)
+        })
+        .collect();
+
+    let fill = match &styles.gradient {
+        Some(gradient) => {
+            let ((x1, y1), (x2, y2)) = gradient_points(gradient);
+            format!(
+                "LinearGradient(colors: [{}, {}], startPoint: UnitPoint(x: {}, y: {}), endPoint: UnitPoint(x: {}, y: {}))",
+                swift_color(&gradient.start_color)?,
+                swift_color(&gradient.stop_color)?,
+                format_number(x1),
+                format_number(y1),
+                format_number(x2),
+                format_number(y2)
+            )
+        }
+        None => swift_color(&base_fill(styles, preset))?,
+    };
+
+    let radius = format_number(styles.corner_radius);
+    let mut base_modifiers = format!("                .fill({})\n", fill);
+    match preset {
+        // Pressed icons' inner shadows are left out.
+        StylePreset::Neumorphism if styles.shadows && styles.state != IconState::Pressed => {
+            let (offset, blur) = shadow_geometry(styles);
+            let shadows = shadow_colors(styles);
+            for (color, direction) in [(&shadows.dark, 1.0), (&shadows.light, -1.0)] {
+                base_modifiers.push_str(&format!(
+                    "                .shadow(color: {}, radius: {} * unit, x: {} * unit, y: {} * unit)\n",
+                    swift_color(color)?,
+                    format_number(blur),
+                    format_number(offset * direction),
+                    format_number(offset * direction)
+                ));
+            }
+        }
+        StylePreset::Neumorphism => {}
+        StylePreset::Glassmorphism | StylePreset::FrostedGlass => {
+            base_modifiers.push_str(&format!(
+                "                .opacity({})\n                .overlay(RoundedRectangle(cornerRadius: {} * unit).stroke({}, lineWidth: {} * unit))\n",
+                format_number(base_fill_opacity(preset)),
+                radius,
+                swift_color(GLASS_STROKE)?,
+                format_number(GLASS_STROKE_WIDTH)
+            ));
+        }
+    }
+
+    Ok(format!(
+        r#"import SwiftUI
+
+/// The glyph of `{name}`, placed on its {width}x{height} canvas and scaled to fit.
+struct {name}Shape: Shape {{
+    func path(in rect: CGRect) -> Path {{
+        var path = Path()
+{commands}
+        let scale = min(rect.width / {width}, rect.height / {height})
+        let transform = CGAffineTransform(translationX: rect.midX - {width} * scale / 2, y: rect.midY - {height} * scale / 2)
+            .scaledBy(x: scale, y: scale)
+        return path.applying(transform)
+    }}
+}}
+
+struct {name}: View {{
+    /// The rendered width.
+    var size: CGFloat = {width}
+    /// The fill of the glyph.
+    var color: Color = {color}
+
+    var body: some View {{
+        let unit = size / {width}
+        ZStack {{
+            RoundedRectangle(cornerRadius: {radius} * unit)
+{base_modifiers}            {name}Shape()
+                .fill(color)
+        }}
+        .frame(width: {width} * unit, height: {height} * unit)
+    }}
+}}
+"#,
+        name = name,
+        commands = commands,
+        width = styles.width,
+        height = styles.height,
+        color = swift_color(&contrast::resolve_icon_color(styles, preset))?,
+        radius = radius,
+        base_modifiers = base_modifiers
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::{Animation, AnimationKind};
+    use crate::overlay::{Overlay, OverlayShape};
+    use crate::{Alignment, FitMode, Placement};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    fn generate(target: ComponentTarget) -> Component {
+        generate_component(target, "video player", TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default())
+            .unwrap()
+    }
+
+    #[test]
+    fn component_names_are_pascal_case() {
+        assert_eq!(component_name("video player"), "VideoPlayerIcon");
+        assert_eq!(component_name("arrow_left-icon"), "ArrowLeftIcon");
+        assert_eq!(component_name("3d box"), "Icon3dBox");
+    }
+
+    #[test]
+    fn react_component_binds_props() {
+        let component = generate(ComponentTarget::React);
+        assert_eq!(component.file_name, "VideoPlayerIcon.tsx");
+        let source = &component.source;
+        assert!(source.contains("export default function VideoPlayerIcon({ size = 128, color = \"#333333\", ...props }"));
+        assert!(source.contains("<path className=\"icon-fg\" d=\"M12 2L2 22h20L12 2z\" fill={color} />"));
+        assert!(source.contains("floodColor="));
+        assert!(source.contains("filter=\"url(#video-player-neumorphism-shadow)\""));
+    }
+
+    #[test]
+    fn vue_and_svelte_bind_color() {
+        let vue = generate(ComponentTarget::Vue);
+        assert!(vue.source.contains(":fill=\"color\""));
+        assert!(vue.source.contains("flood-color="));
+        let svelte = generate(ComponentTarget::Svelte);
+        assert!(svelte.source.contains("export let color = \"#333333\";"));
+        assert!(svelte.source.contains("fill={color}"));
+    }
+
+    #[test]
+    fn components_keep_text_and_styles() {
+        let styles = CustomStyles {
+            overlays: vec![Overlay {
+                shape: OverlayShape::Badge { text: "3 <new>".to_string(), text_color: "#ffffff".to_string() },
+                color: "#d32f2f".to_string(),
+                anchor: Alignment::TopRight,
+                size: 40.0,
+                cutout: None,
+            }],
+            animation: Some(Animation::new(AnimationKind::Pulse)),
+            ..CustomStyles::default()
+        };
+        let generate = |target| generate_component(target, "bell", TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+
+        let react = generate(ComponentTarget::React).source;
+        assert!(react.contains("{\"3 \\u003cnew>\"}"), "{}", react);
+        assert!(react.contains("<style>\n"));
+        assert!(react.contains("@keyframes"));
+        assert!(!react.contains("{color}{"));
+
+        let vue = generate(ComponentTarget::Vue).source;
+        assert!(vue.contains("<component is=\"style\">\n"));
+        assert!(vue.contains("{{ \"3 \\u003cnew>\" }}"));
+        assert!(vue.contains("</component>"));
+
+        let svelte = generate(ComponentTarget::Svelte).source;
+        assert!(svelte.contains("<svelte:element this=\"style\">\n"));
+        assert!(svelte.contains("</svelte:element>"));
+        // Braces in the CSS are escaped inside the string.
+        assert!(svelte.contains("\\u007b"));
+    }
+
+    #[test]
+    fn native_targets() {
+        let android = generate(ComponentTarget::VectorDrawable);
+        assert_eq!(android.file_name, "ic_video_player.xml");
+        let swift = generate(ComponentTarget::SwiftUi);
+        assert!(swift.source.contains("struct VideoPlayerIconShape: Shape"));
+        // The glyph is traced on the canvas, fitted into the padding.
+        assert!(swift.source.contains("path.move(to: CGPoint(x: 64, y: 24))"));
+        assert!(swift.source.contains(".shadow(color: Color(red: 0, green: 0, blue: 0, opacity: 0.12)"));
+
+        let placement = Placement { align: Alignment::Top, flip_vertical: true, ..Placement::default() };
+        let styles = CustomStyles { placement, fit: FitMode::Tight, ..CustomStyles::default() };
+        let placed = generate_component(ComponentTarget::SwiftUi, "play", TEST_SVG, StylePreset::Neumorphism, &styles)
+            .unwrap();
+        assert!(placed.source.contains("path.move(to: CGPoint(x: 64, y: 112))"), "{}", placed.source);
+    }
+
+    #[test]
+    fn index_exports_components() {
+        let components = vec![generate(ComponentTarget::Vue)];
+        let (file, source) = index_file(ComponentTarget::Vue, &components).unwrap();
+        assert_eq!(file, "index.ts");
+        assert_eq!(source, "export { default as VideoPlayerIcon } from \"./VideoPlayerIcon.vue\";\n");
+        assert!(index_file(ComponentTarget::SwiftUi, &components).is_none());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::raster::{encode_png, RasterImage};
+use crate::IconEngineError;
+
+/// The pixel sizes written to `.ico` files by default.
+pub const ICO_SIZES: &[u32] = &[16, 24, 32, 48, 64, 128, 256];
+
+/// The pixel sizes written to `.icns` files by default.
+pub const ICNS_SIZES: &[u32] = &[16, 32, 64, 128, 256, 512, 1024];
+
+/// Packs square images into a Windows `.ico` file with PNG-compressed entries.
+///
+/// Entries keep the order of `images`; sizes above 256 pixels are rejected
+/// because the directory cannot describe them.
+pub fn encode_ico(images: &[RasterImage]) -> Result<Vec<u8>, IconEngineError> {
+    if images.is_empty() {
+        return Err(IconEngineError::InvalidInput("An ICO file needs at least one image".to_string()));
+    }
+
+    let pngs = images
+        .iter()
+        .map(|image| {
+            if image.width > 256 || image.height > 256 {
+                return Err(IconEngineError::InvalidInput(format!(
+                    "ICO entries cannot exceed 256x256, got {}x{}",
+                    image.width, image.height
+                )));
+            }
+            encode_png(image)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&0u16.to_le_bytes()); // reserved
+    out.extend_from_slice(&1u16.to_le_bytes()); // type: icon
+    out.extend_from_slice(&(images.len() as u16).to_le_bytes());
+
+    let mut offset = 6 + 16 * images.len() as u32;
+    for (image, png) in images.iter().zip(&pngs) {
+        // A stored dimension of 0 means 256 pixels.
+        out.push((image.width % 256) as u8);
+        out.push((image.height % 256) as u8);
+        out.push(0); // palette size
+        out.push(0); // reserved
+        out.extend_from_slice(&1u16.to_le_bytes()); // color planes
+        out.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+        out.extend_from_slice(&(png.len() as u32).to_le_bytes());
+        out.extend_from_slice(&offset.to_le_bytes());
+        offset += png.len() as u32;
+    }
+    for png in &pngs {
+        out.extend_from_slice(png);
+    }
+
+    Ok(out)
+}
+
+/// Packs square images into a macOS `.icns` file with PNG-compressed entries.
+///
+/// Each image must have one of the pixel sizes in [`ICNS_SIZES`]. Images that
+/// double a smaller size are also stored as that size's retina (`@2x`)
+/// element, so a 32 pixel image fills both `icp5` and `ic11`.
+pub fn encode_icns(images: &[RasterImage]) -> Result<Vec<u8>, IconEngineError> {
+    if images.is_empty() {
+        return Err(IconEngineError::InvalidInput("An ICNS file needs at least one image".to_string()));
+    }
+
+    let mut body = Vec::new();
+    for image in images {
+        let os_types = icns_types(image)?;
+        let png = encode_png(image)?;
+        for os_type in os_types {
+            body.extend_from_slice(*os_type);
+            body.extend_from_slice(&(png.len() as u32 + 8).to_be_bytes());
+            body.extend_from_slice(&png);
+        }
+    }
+
+    let mut out = Vec::with_capacity(body.len() + 8);
+    out.extend_from_slice(b"icns");
+    out.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Maps a square image to the PNG-capable ICNS element types for its size:
+/// its standard element and, for doubled sizes, the retina one.
+fn icns_types(image: &RasterImage) -> Result<&'static [&'static [u8; 4]], IconEngineError> {
+    let os_types: &[&[u8; 4]] = match (image.width, image.height) {
+        (16, 16) => &[b"icp4"],
+        (32, 32) => &[b"icp5", b"ic11"],
+        (64, 64) => &[b"icp6", b"ic12"],
+        (128, 128) => &[b"ic07"],
+        (256, 256) => &[b"ic08", b"ic13"],
+        (512, 512) => &[b"ic09", b"ic14"],
+        (1024, 1024) => &[b"ic10"],
+        (w, h) => {
+            return Err(IconEngineError::InvalidInput(format!(
+                "ICNS has no slot for a {}x{} image",
+                w, h
+            )))
+        }
+    };
+    Ok(os_types)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blank(size: u32) -> RasterImage {
+        RasterImage { width: size, height: size, data: vec![0; (size * size * 4) as usize] }
+    }
+
+    #[test]
+    fn ico_directory_points_at_png_entries() {
+        let ico = encode_ico(&[blank(16), blank(256)]).unwrap();
+        assert_eq!(&ico[..6], &[0, 0, 1, 0, 2, 0]);
+
+        // The second entry stores 256 as 0 and starts right after the first PNG.
+        let second = &ico[6 + 16..6 + 32];
+        assert_eq!(second[0], 0);
+        let first_len = u32::from_le_bytes(ico[6 + 8..6 + 12].try_into().unwrap());
+        let second_offset = u32::from_le_bytes(second[12..16].try_into().unwrap());
+        assert_eq!(second_offset, 6 + 32 + first_len);
+        assert_eq!(&ico[second_offset as usize..second_offset as usize + 4], b"\x89PNG");
+    }
+
+    #[test]
+    fn ico_rejects_oversized_images() {
+        assert!(encode_ico(&[blank(512)]).is_err());
+    }
+
+    #[test]
+    fn icns_length_covers_all_elements() {
+        let icns = encode_icns(&[blank(16), blank(32)]).unwrap();
+        assert_eq!(&icns[..4], b"icns");
+        assert_eq!(u32::from_be_bytes(icns[4..8].try_into().unwrap()) as usize, icns.len());
+        assert_eq!(&icns[8..12], b"icp4");
+        assert!(encode_icns(&[blank(20)]).is_err());
+    }
+
+    #[test]
+    fn icns_stores_doubled_sizes_as_retina_elements() {
+        let icns = encode_icns(&[blank(32)]).unwrap();
+        let first_len = u32::from_be_bytes(icns[12..16].try_into().unwrap()) as usize;
+        assert_eq!(&icns[8..12], b"icp5");
+        assert_eq!(&icns[8 + first_len..12 + first_len], b"ic11");
+        assert_eq!(icns[8..8 + first_len][8..], icns[8 + first_len..][8..first_len]);
+    }
+}
@@ -0,0 +1,256 @@
+use crate::color::Rgb;
+use crate::overlay::OverlayShape;
+use crate::style_generator::{base_fill, base_fill_opacity};
+use crate::{CustomStyles, IconEngineError, StylePreset};
+
+/// The `icon_color` value that asks the engine to pick a compliant foreground.
+pub const AUTO_ICON_COLOR: &str = "auto";
+
+/// The WCAG 2.1 minimum contrast for graphical objects (success criterion 1.4.11).
+pub const WCAG_NON_TEXT_CONTRAST: f32 = 3.0;
+
+/// The highest contrast ratio two colors can have (black on white).
+pub const MAX_CONTRAST: f32 = 21.0;
+
+/// Checks that a minimum contrast ratio is attainable, i.e. within 1..=21.
+pub fn validate_min_contrast(min_contrast: f32) -> Result<(), IconEngineError> {
+    if !(1.0..=MAX_CONTRAST).contains(&min_contrast) {
+        return Err(IconEngineError::InvalidInput(format!(
+            "Minimum contrast must be between 1 and {}, got {}",
+            MAX_CONTRAST, min_contrast
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the colors the foreground is drawn against.
+///
+/// A solid base yields its fill; a gradient yields both stops and their
+/// midpoint. Colors that cannot be parsed are skipped, so the result may be
+/// empty.
+///
+/// Glass bases are translucent, so each color is composited at the preset's
+/// fill opacity over both a black and a white backdrop: the foreground has
+/// to stand out whatever page the icon is placed on.
+pub fn base_samples(styles: &CustomStyles, preset: StylePreset) -> Vec<Rgb> {
+    let opacity = base_fill_opacity(preset);
+    let colors = opaque_base_samples(styles, preset);
+    if opacity >= 1.0 {
+        return colors;
+    }
+    [Rgb::new(0, 0, 0), Rgb::new(255, 255, 255)]
+        .into_iter()
+        .flat_map(|backdrop| colors.iter().map(move |&color| backdrop.mix(color, opacity)))
+        .collect()
+}
+
+fn opaque_base_samples(styles: &CustomStyles, preset: StylePreset) -> Vec<Rgb> {
+    match &styles.gradient {
+        Some(gradient) => {
+            match (Rgb::parse(&gradient.start_color), Rgb::parse(&gradient.stop_color)) {
+                (Some(start), Some(stop)) => vec![start, start.mix(stop, 0.5), stop],
+                (start, stop) => start.into_iter().chain(stop).collect(),
+            }
+        }
+        None => Rgb::parse(&base_fill(styles, preset)).into_iter().collect(),
+    }
+}
+
+/// Returns the lowest contrast ratio between `color` and any base sample, or
+/// `None` if either side cannot be evaluated.
+pub fn min_contrast(color: &str, styles: &CustomStyles, preset: StylePreset) -> Option<f32> {
+    let foreground = Rgb::parse(color)?;
+    base_samples(styles, preset)
+        .into_iter()
+        .map(|sample| foreground.contrast_ratio(sample))
+        .reduce(f32::min)
+}
+
+/// Resolves the foreground color, replacing [`AUTO_ICON_COLOR`] with a color
+/// that meets `styles.min_contrast` against every base sample.
+///
+/// The automatic color keeps the average hue of the base and walks its
+/// lightness towards black or white, whichever contrasts better, stopping at
+/// the first compliant value.
+pub fn resolve_icon_color(styles: &CustomStyles, preset: StylePreset) -> String {
+    if !styles.icon_color.eq_ignore_ascii_case(AUTO_ICON_COLOR) {
+        return styles.icon_color.clone();
+    }
+
+    let samples = base_samples(styles, preset);
+    let Some(average) = average_color(&samples) else {
+        return "#000000".to_string();
+    };
+
+    let lowest = |candidate: Rgb| {
+        samples
+            .iter()
+            .map(|&sample| candidate.contrast_ratio(sample))
+            .fold(f32::INFINITY, f32::min)
+    };
+
+    let black = Rgb::new(0, 0, 0);
+    let white = Rgb::new(255, 255, 255);
+    let towards_dark = lowest(black) >= lowest(white);
+
+    let (h, s, l) = average.to_hsl();
+    let steps = 50;
+    for i in 1..=steps {
+        let t = i as f32 / steps as f32;
+        let lightness = if towards_dark { l * (1.0 - t) } else { l + (1.0 - l) * t };
+        let candidate = Rgb::from_hsl(h, s, lightness);
+        if lowest(candidate) >= styles.min_contrast {
+            return candidate.to_hex();
+        }
+    }
+
+    if towards_dark { black } else { white }.to_hex()
+}
+
+/// Checks the resolved foreground against `styles.min_contrast`, returning the
+/// measured ratio. Colors that cannot be evaluated pass with `None`.
+///
+/// Glyph layers with their own color are checked against the base too, at
+/// their opacity. Overlays are checked as well: their fill against the base,
+/// and badge and ribbon labels against that fill. The lowest ratio of all is
+/// returned.
+pub fn check_contrast(
+    styles: &CustomStyles,
+    preset: StylePreset,
+) -> Result<Option<f32>, IconEngineError> {
+    validate_min_contrast(styles.min_contrast)?;
+    let color = resolve_icon_color(styles, preset);
+    let mut ratios = vec![min_contrast(&color, styles, preset)];
+    for layer in &styles.glyph_layers {
+        let Some(color) = layer.color.as_deref().and_then(Rgb::parse) else {
+            continue;
+        };
+        let samples = base_samples(styles, preset);
+        let layer_ratios = samples.into_iter().map(|sample| sample.mix(color, layer.opacity).contrast_ratio(sample));
+        ratios.push(layer_ratios.reduce(f32::min));
+    }
+    for overlay in &styles.overlays {
+        ratios.push(min_contrast(&overlay.color, styles, preset));
+        if let OverlayShape::Badge { text_color, .. } | OverlayShape::Ribbon { text_color, .. } = &overlay.shape {
+            let label = Rgb::parse(text_color).zip(Rgb::parse(&overlay.color));
+            ratios.push(label.map(|(text, fill)| text.contrast_ratio(fill)));
+        }
+    }
+    match ratios.into_iter().flatten().reduce(f32::min) {
+        Some(ratio) if ratio < styles.min_contrast => Err(IconEngineError::InsufficientContrast {
+            ratio,
+            required: styles.min_contrast,
+        }),
+        ratio => Ok(ratio),
+    }
+}
+
+fn average_color(colors: &[Rgb]) -> Option<Rgb> {
+    if colors.is_empty() {
+        return None;
+    }
+    let n = colors.len() as u32;
+    let sum = |f: fn(&Rgb) -> u8| colors.iter().map(|c| f(c) as u32).sum::<u32>() / n;
+    Some(Rgb::new(sum(|c| c.r) as u8, sum(|c| c.g) as u8, sum(|c| c.b) as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyph_stack::GlyphLayer;
+    use crate::overlay::Overlay;
+    use crate::{Alignment, Gradient};
+
+    fn dark_gradient_styles(icon_color: &str) -> CustomStyles {
+        CustomStyles {
+            icon_color: icon_color.to_string(),
+            gradient: Some(Gradient {
+                angle: 45,
+                start_color: "#101030".to_string(),
+                stop_color: "#302010".to_string(),
+            }),
+            ..CustomStyles::default()
+        }
+    }
+
+    #[test]
+    fn dark_foreground_on_dark_gradient_fails() {
+        let styles = dark_gradient_styles("#333333");
+        let result = check_contrast(&styles, StylePreset::Neumorphism);
+        assert!(matches!(result, Err(IconEngineError::InsufficientContrast { .. })));
+    }
+
+    #[test]
+    fn overlays_and_their_labels_are_checked() {
+        let badge = |color: &str, text_color: &str| Overlay {
+            shape: OverlayShape::Badge { text: "3".to_string(), text_color: text_color.to_string() },
+            color: color.to_string(),
+            anchor: Alignment::TopRight,
+            size: 32.0,
+            cutout: None,
+        };
+        let styles = |overlay| CustomStyles { overlays: vec![overlay], ..CustomStyles::default() };
+        let passes = |overlay| check_contrast(&styles(overlay), StylePreset::Neumorphism).is_ok();
+
+        assert!(passes(badge("#d32f2f", "#ffffff")));
+        // The base is a light grey, so a pale badge disappears into it.
+        assert!(!passes(badge("#eeeeee", "#000000")));
+        assert!(!passes(badge("#d32f2f", "#c62828")));
+    }
+
+    #[test]
+    fn colored_glyph_layers_are_checked_at_their_opacity() {
+        let layer = |opacity: f32| GlyphLayer {
+            color: Some("#c62828".to_string()),
+            opacity,
+            ..GlyphLayer::new("<svg/>")
+        };
+        let styles = |layer| CustomStyles { glyph_layers: vec![layer], ..CustomStyles::default() };
+        assert!(check_contrast(&styles(layer(1.0)), StylePreset::Neumorphism).is_ok());
+        let faint = check_contrast(&styles(layer(0.2)), StylePreset::Neumorphism);
+        assert!(matches!(faint, Err(IconEngineError::InsufficientContrast { .. })));
+    }
+
+    #[test]
+    fn auto_color_meets_minimum() {
+        let styles = dark_gradient_styles(AUTO_ICON_COLOR);
+        let color = resolve_icon_color(&styles, StylePreset::Neumorphism);
+        let ratio = min_contrast(&color, &styles, StylePreset::Neumorphism).unwrap();
+        assert!(ratio >= WCAG_NON_TEXT_CONTRAST, "{} only reaches {}", color, ratio);
+        assert!(check_contrast(&styles, StylePreset::Neumorphism).is_ok());
+    }
+
+    #[test]
+    fn auto_color_on_light_base_is_dark() {
+        let styles = CustomStyles {
+            icon_color: AUTO_ICON_COLOR.to_string(),
+            ..CustomStyles::default()
+        };
+        let color = Rgb::parse(&resolve_icon_color(&styles, StylePreset::Neumorphism)).unwrap();
+        assert!(color.relative_luminance() < 0.3);
+    }
+
+    #[test]
+    fn glass_is_checked_against_its_backdrops() {
+        let styles = CustomStyles { icon_color: "#333333".to_string(), ..CustomStyles::default() };
+        // Opaque white would pass, but the 20% white base over a black page
+        // is about as dark as the glyph.
+        assert!(check_contrast(&styles, StylePreset::Glassmorphism).is_err());
+
+        let auto = CustomStyles { icon_color: AUTO_ICON_COLOR.to_string(), ..styles };
+        let color = resolve_icon_color(&auto, StylePreset::FrostedGlass);
+        let ratio = min_contrast(&color, &auto, StylePreset::FrostedGlass).unwrap();
+        assert!(ratio >= WCAG_NON_TEXT_CONTRAST, "{} only reaches {}", color, ratio);
+    }
+
+    #[test]
+    fn rejects_unattainable_minimums() {
+        for min_contrast in [0.5, 22.0, f32::NAN] {
+            let styles = CustomStyles { min_contrast, ..CustomStyles::default() };
+            assert!(matches!(
+                check_contrast(&styles, StylePreset::Neumorphism),
+                Err(IconEngineError::InvalidInput(_))
+            ));
+        }
+    }
+}
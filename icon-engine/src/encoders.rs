@@ -0,0 +1,176 @@
+use image_webp::{ColorType, WebPEncoder};
+
+use crate::raster::RasterImage;
+use crate::IconEngineError;
+
+/// Quality and compression settings shared by the WebP and AVIF encoders.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// Writes WebP with the exact lossless codec and AVIF at the highest
+    /// quality.
+    pub lossless: bool,
+    /// The lossy WebP and AVIF quality from 0 to 100, used when `lossless` is
+    /// off.
+    pub quality: u8,
+    /// When above 0 and `lossless` is off, writes WebP with the lossless
+    /// codec after dropping this many low bits (up to 7) from each color
+    /// channel, instead of with the lossy codec.
+    pub webp_quantize: u8,
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        Self { lossless: false, quality: 90, webp_quantize: 0 }
+    }
+}
+
+/// The largest accepted `EncodeOptions::webp_quantize`.
+pub const MAX_WEBP_QUANTIZE: u8 = 7;
+
+/// Encodes a raster image as WebP with alpha.
+///
+/// Lossless output and quantized output use the pure-Rust VP8L encoder.
+/// `webp_quantize` rounds color channels to fewer levels before the lossless
+/// pass (as libwebp's near-lossless mode does), which shrinks gradients and
+/// shadows considerably. Otherwise the image is written with libwebp's lossy
+/// VP8 codec at `quality`, which needs the `webp-lossy` feature. Fully
+/// transparent pixels always have their color zeroed since it is never visible.
+pub fn encode_webp(image: &RasterImage, options: EncodeOptions) -> Result<Vec<u8>, IconEngineError> {
+    if options.webp_quantize > MAX_WEBP_QUANTIZE {
+        return Err(IconEngineError::InvalidInput(format!(
+            "WebP quantization must be at most {} bits, got {}",
+            MAX_WEBP_QUANTIZE, options.webp_quantize
+        )));
+    }
+    if !options.lossless && options.webp_quantize == 0 {
+        return encode_lossy_webp(image, options.quality);
+    }
+    let bits = if options.lossless { 0 } else { options.webp_quantize as u32 };
+    let pixels = prepare_pixels(&image.data, bits);
+
+    let mut out = Vec::new();
+    WebPEncoder::new(&mut out)
+        .encode(&pixels, image.width, image.height, ColorType::Rgba8)
+        .map_err(|e| IconEngineError::RenderError(e.to_string()))?;
+    Ok(out)
+}
+
+#[cfg(feature = "webp-lossy")]
+fn encode_lossy_webp(image: &RasterImage, quality: u8) -> Result<Vec<u8>, IconEngineError> {
+    let pixels = prepare_pixels(&image.data, 0);
+    let encoded = webp::Encoder::from_rgba(&pixels, image.width, image.height)
+        .encode_simple(false, quality.min(100) as f32)
+        .map_err(|e| IconEngineError::RenderError(format!("WebP encoding failed: {:?}", e)))?;
+    Ok(encoded.to_vec())
+}
+
+#[cfg(not(feature = "webp-lossy"))]
+fn encode_lossy_webp(_image: &RasterImage, _quality: u8) -> Result<Vec<u8>, IconEngineError> {
+    Err(IconEngineError::InvalidInput(
+        "Lossy WebP needs the webp-lossy feature; use lossless or quantized WebP instead".to_string(),
+    ))
+}
+
+/// Encodes a raster image as AVIF with alpha.
+///
+/// The pure-Rust AV1 encoder has no lossless mode: `lossless` only selects
+/// quality 100, which is visually lossless but still converts to YUV and
+/// may change pixel values slightly.
+#[cfg(feature = "avif")]
+pub fn encode_avif(image: &RasterImage, options: EncodeOptions) -> Result<Vec<u8>, IconEngineError> {
+    let quality = if options.lossless { 100.0 } else { options.quality.min(100) as f32 };
+    let pixels: Vec<ravif::RGBA8> = prepare_pixels(&image.data, 0)
+        .chunks_exact(4)
+        .map(|p| ravif::RGBA8::new(p[0], p[1], p[2], p[3]))
+        .collect();
+
+    let encoded = ravif::Encoder::new()
+        .with_quality(quality)
+        .with_alpha_quality(quality)
+        .with_speed(6)
+        .encode_rgba(ravif::Img::new(pixels.as_slice(), image.width as usize, image.height as usize))
+        .map_err(|e| IconEngineError::RenderError(e.to_string()))?;
+    Ok(encoded.avif_file)
+}
+
+/// Zeroes the color of invisible pixels and rounds color channels to a
+/// multiple of `2^bits`.
+fn prepare_pixels(data: &[u8], bits: u32) -> Vec<u8> {
+    let step = 1u32 << bits;
+    data.chunks_exact(4)
+        .flat_map(|p| {
+            if p[3] == 0 {
+                return [0, 0, 0, 0];
+            }
+            let quantize = |v: u8| (((v as u32 + step / 2) / step) * step).min(255) as u8;
+            [quantize(p[0]), quantize(p[1]), quantize(p[2]), p[3]]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A gradient with low-bit noise, like an anti-aliased soft shadow.
+    fn noisy_gradient(size: u32) -> RasterImage {
+        let data = (0..size * size)
+            .flat_map(|i| {
+                let (x, y) = (i % size, i / size);
+                let noise = (i.wrapping_mul(2_654_435_761) >> 28) as u8;
+                let channel = |v: u32| ((v * 239 / size) as u8).saturating_add(noise);
+                [channel(x), channel(y), channel((x + y) / 2), 255]
+            })
+            .collect();
+        RasterImage { width: size, height: size, data }
+    }
+
+    #[test]
+    fn webp_has_riff_header() {
+        let lossless = EncodeOptions { lossless: true, ..EncodeOptions::default() };
+        let webp = encode_webp(&noisy_gradient(16), lossless).unwrap();
+        assert_eq!(&webp[..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+        assert_eq!(&webp[12..16], b"VP8L");
+    }
+
+    #[cfg(feature = "webp-lossy")]
+    #[test]
+    fn lossy_webp_follows_quality() {
+        let mut image = noisy_gradient(64);
+        image.data[3] = 0;
+        let encode = |quality| encode_webp(&image, EncodeOptions { quality, ..EncodeOptions::default() }).unwrap();
+        let (low, high) = (encode(10), encode(90));
+        // Extended format with an alpha chunk ahead of the lossy bitstream.
+        assert_eq!(&high[12..16], b"VP8X");
+        assert!(high.windows(4).any(|w| w == b"ALPH"));
+        assert!(high.windows(4).any(|w| w == b"VP8 "));
+        assert!(low.len() < high.len());
+    }
+
+    #[test]
+    fn quantization_is_smaller() {
+        let image = noisy_gradient(64);
+        let lossless = EncodeOptions { lossless: true, ..EncodeOptions::default() };
+        let exact = encode_webp(&image, lossless).unwrap();
+        let quantized = encode_webp(&image, EncodeOptions { webp_quantize: 4, ..EncodeOptions::default() }).unwrap();
+        assert!(quantized.len() < exact.len());
+        assert_eq!(&quantized[12..16], b"VP8L");
+        let lossless = EncodeOptions { webp_quantize: 4, ..lossless };
+        assert_eq!(encode_webp(&image, lossless).unwrap(), exact);
+        let invalid = EncodeOptions { webp_quantize: 8, ..EncodeOptions::default() };
+        assert!(encode_webp(&image, invalid).is_err());
+    }
+
+    #[cfg(feature = "avif")]
+    #[test]
+    fn avif_has_ftyp_box() {
+        let avif = encode_avif(&noisy_gradient(8), EncodeOptions::default()).unwrap();
+        assert_eq!(&avif[4..12], b"ftypavif");
+    }
+
+    #[test]
+    fn transparent_pixels_lose_their_color() {
+        assert_eq!(prepare_pixels(&[10, 20, 30, 0, 13, 250, 7, 255], 2), vec![0, 0, 0, 0, 12, 252, 8, 255]);
+    }
+}
@@ -0,0 +1,788 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use crate::path_data::{self, PathSegment, Point};
+use crate::svg_processor;
+use crate::IconEngineError;
+
+/// The em square every glyph is normalized to.
+pub const UNITS_PER_EM: u16 = 1000;
+/// The top of the em square above the baseline; the rest hangs below it so
+/// icons line up with surrounding text.
+const ASCENT: i16 = 850;
+const DESCENT: i16 = ASCENT - UNITS_PER_EM as i16;
+
+/// The first and last codepoints of the Basic Multilingual Plane's Private
+/// Use Area, where icon codepoints are assigned.
+pub const PUA_START: u32 = 0xE000;
+pub const PUA_END: u32 = 0xF8FF;
+
+/// The largest distance, in font units, between a cubic curve and the
+/// quadratic curves that replace it.
+const CURVE_TOLERANCE: f32 = 0.5;
+
+/// One on- or off-curve TrueType outline point in font units.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct OutlinePoint {
+    x: i16,
+    y: i16,
+    on_curve: bool,
+}
+
+/// An icon converted to a TrueType glyph.
+#[derive(Debug, Clone)]
+pub struct FontGlyph {
+    pub name: String,
+    pub codepoint: u32,
+    contours: Vec<Vec<OutlinePoint>>,
+}
+
+impl FontGlyph {
+    /// Converts the shapes of a source SVG into a glyph, scaling its viewBox
+    /// to fit the em square and centering it. Every path, rect, circle etc.
+    /// becomes part of the outline, filled with the nonzero rule.
+    pub fn from_svg(name: &str, codepoint: u32, svg_data: &str) -> Result<Self, IconEngineError> {
+        if !(PUA_START..=PUA_END).contains(&codepoint) {
+            return Err(IconEngineError::InvalidInput(format!(
+                "Codepoint U+{:04X} for '{}' is outside the Private Use Area",
+                codepoint, name
+            )));
+        }
+
+        let icon = svg_processor::parse_svg(svg_data)?;
+        if icon.elements.is_empty() {
+            return Err(IconEngineError::SvgParsingError(format!("'{}' has no shapes to draw", name)));
+        }
+        let segments: Vec<PathSegment> =
+            icon.elements.iter().flat_map(|element| path_data::arcs_to_cubics(&element.segments)).collect();
+
+        let em = UNITS_PER_EM as f32;
+        let scale = em / icon.viewbox.width.max(icon.viewbox.height);
+        let offset_x = (em - icon.viewbox.width * scale) / 2.0 - icon.viewbox_origin.x * scale;
+        let offset_y = (em - icon.viewbox.height * scale) / 2.0 - icon.viewbox_origin.y * scale;
+        // Font units grow upwards from the baseline, SVG units grow downwards.
+        let to_font = |p: Point| Point::new(p.x * scale + offset_x, ASCENT as f32 - (p.y * scale + offset_y));
+
+        let mut contours = Vec::new();
+        let mut contour: Vec<OutlinePoint> = Vec::new();
+        let mut current = Point::new(0.0, 0.0);
+        let mut start = current;
+
+        for segment in segments {
+            if contour.is_empty() && !matches!(segment, PathSegment::MoveTo(_) | PathSegment::ClosePath) {
+                // Drawing straight after a close path continues from its start.
+                contour.push(on_curve(start));
+            }
+            match segment {
+                PathSegment::MoveTo(p) => {
+                    finish_contour(&mut contours, &mut contour);
+                    start = to_font(p);
+                    current = start;
+                    contour.push(on_curve(start));
+                }
+                PathSegment::LineTo(p) => {
+                    current = to_font(p);
+                    contour.push(on_curve(current));
+                }
+                PathSegment::QuadTo(c, p) => {
+                    contour.push(off_curve(to_font(c)));
+                    current = to_font(p);
+                    contour.push(on_curve(current));
+                }
+                PathSegment::CubicTo(c1, c2, p) => {
+                    let end = to_font(p);
+                    for (c, p) in cubic_to_quads(current, to_font(c1), to_font(c2), end) {
+                        contour.push(off_curve(c));
+                        contour.push(on_curve(p));
+                    }
+                    current = end;
+                }
+                // Arcs were converted to cubics above.
+                PathSegment::ArcTo { .. } => {}
+                PathSegment::ClosePath => {
+                    finish_contour(&mut contours, &mut contour);
+                    current = start;
+                }
+            }
+        }
+        finish_contour(&mut contours, &mut contour);
+
+        Ok(Self { name: name.to_string(), codepoint, contours })
+    }
+
+    fn points(&self) -> impl Iterator<Item = &OutlinePoint> {
+        self.contours.iter().flatten()
+    }
+
+    /// Returns `(x_min, y_min, x_max, y_max)`, or zeros for an empty glyph.
+    fn bounds(&self) -> (i16, i16, i16, i16) {
+        self.points().fold(None, |acc: Option<(i16, i16, i16, i16)>, p| {
+            Some(match acc {
+                None => (p.x, p.y, p.x, p.y),
+                Some((x0, y0, x1, y1)) => (x0.min(p.x), y0.min(p.y), x1.max(p.x), y1.max(p.y)),
+            })
+        })
+        .unwrap_or((0, 0, 0, 0))
+    }
+}
+
+fn on_curve(p: Point) -> OutlinePoint {
+    OutlinePoint { x: p.x.round() as i16, y: p.y.round() as i16, on_curve: true }
+}
+
+fn off_curve(p: Point) -> OutlinePoint {
+    OutlinePoint { on_curve: false, ..on_curve(p) }
+}
+
+/// Closes the contour being built, dropping a final point that repeats the
+/// first one; TrueType contours are always closed.
+fn finish_contour(contours: &mut Vec<Vec<OutlinePoint>>, contour: &mut Vec<OutlinePoint>) {
+    if contour.len() > 1 && contour.last() == contour.first() {
+        contour.pop();
+    }
+    if contour.len() > 1 {
+        contours.push(std::mem::take(contour));
+    } else {
+        contour.clear();
+    }
+}
+
+/// Approximates a cubic Bézier with quadratic ones, returning each as its
+/// `(control, end)` pair. The cubic is split evenly into as many pieces as
+/// needed to stay within [`CURVE_TOLERANCE`].
+fn cubic_to_quads(p0: Point, c1: Point, c2: Point, p3: Point) -> Vec<(Point, Point)> {
+    // The error of a single quadratic is sqrt(3)/36 * |p3 - 3c2 + 3c1 - p0|
+    // and shrinks with the cube of the number of pieces.
+    let dx = p3.x - 3.0 * c2.x + 3.0 * c1.x - p0.x;
+    let dy = p3.y - 3.0 * c2.y + 3.0 * c1.y - p0.y;
+    let error = 3f32.sqrt() / 36.0 * (dx * dx + dy * dy).sqrt();
+    let pieces = (error / CURVE_TOLERANCE).cbrt().ceil().clamp(1.0, 16.0) as usize;
+
+    let at = |t: f32| {
+        let mt = 1.0 - t;
+        let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+        Point::new(
+            a * p0.x + b * c1.x + c * c2.x + d * p3.x,
+            a * p0.y + b * c1.y + c * c2.y + d * p3.y,
+        )
+    };
+    let derivative = |t: f32| {
+        let mt = 1.0 - t;
+        let (a, b, c) = (3.0 * mt * mt, 6.0 * mt * t, 3.0 * t * t);
+        Point::new(
+            a * (c1.x - p0.x) + b * (c2.x - c1.x) + c * (p3.x - c2.x),
+            a * (c1.y - p0.y) + b * (c2.y - c1.y) + c * (p3.y - c2.y),
+        )
+    };
+
+    (0..pieces)
+        .map(|i| {
+            let (t0, t1) = (i as f32 / pieces as f32, (i + 1) as f32 / pieces as f32);
+            let h = (t1 - t0) / 3.0;
+            let (q0, q3) = (at(t0), if i + 1 == pieces { p3 } else { at(t1) });
+            let (d0, d1) = (derivative(t0), derivative(t1));
+            let q1 = Point::new(q0.x + h * d0.x, q0.y + h * d0.y);
+            let q2 = Point::new(q3.x - h * d1.x, q3.y - h * d1.y);
+            let control = Point::new(
+                (3.0 * (q1.x + q2.x) - q0.x - q3.x) / 4.0,
+                (3.0 * (q1.y + q2.y) - q0.y - q3.y) / 4.0,
+            );
+            (control, q3)
+        })
+        .collect()
+}
+
+/// Gives every name without a codepoint in `mapping` the lowest unused
+/// Private Use Area codepoint, in name order.
+///
+/// Existing entries are never changed or removed, so an icon keeps its
+/// codepoint across runs even when other icons are added or deleted.
+pub fn assign_codepoints(names: &[String], mapping: &mut BTreeMap<String, u32>) -> Result<(), IconEngineError> {
+    let mut sorted: Vec<&String> = names.iter().collect();
+    sorted.sort();
+
+    let mut next = PUA_START;
+    for name in sorted {
+        if mapping.contains_key(name) {
+            continue;
+        }
+        while mapping.values().any(|&cp| cp == next) {
+            next += 1;
+        }
+        if next > PUA_END {
+            return Err(IconEngineError::InvalidInput("The Private Use Area has no free codepoints left".to_string()));
+        }
+        mapping.insert(name.clone(), next);
+    }
+    Ok(())
+}
+
+/// Reads a codepoint mapping file: a JSON object from icon names to
+/// hexadecimal codepoints, e.g. `{"home": "e000"}`.
+pub fn parse_codepoint_map(json: &str) -> Result<BTreeMap<String, u32>, IconEngineError> {
+    let value: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| IconEngineError::InvalidInput(format!("Invalid codepoint map: {}", e)))?;
+    let object = value
+        .as_object()
+        .ok_or_else(|| IconEngineError::InvalidInput("A codepoint map must be a JSON object".to_string()))?;
+
+    object
+        .iter()
+        .map(|(name, cp)| {
+            cp.as_str()
+                .and_then(|s| u32::from_str_radix(s.trim_start_matches("U+").trim_start_matches("u+"), 16).ok())
+                .map(|cp| (name.clone(), cp))
+                .ok_or_else(|| {
+                    IconEngineError::InvalidInput(format!("Codepoint for '{}' must be a hexadecimal string", name))
+                })
+        })
+        .collect()
+}
+
+/// Writes a codepoint mapping in the format read by [`parse_codepoint_map`].
+pub fn codepoint_map_to_json(mapping: &BTreeMap<String, u32>) -> String {
+    let object: serde_json::Map<String, serde_json::Value> = mapping
+        .iter()
+        .map(|(name, cp)| (name.clone(), serde_json::Value::String(format!("{:04x}", cp))))
+        .collect();
+    serde_json::to_string_pretty(&object).unwrap_or_default()
+}
+
+/// Builds a stylesheet that loads the font and defines one
+/// `{class_prefix}-{name}` class per glyph, used as
+/// `<i class="{class_prefix} {class_prefix}-home"></i>`.
+pub fn font_css(family: &str, glyphs: &[FontGlyph], class_prefix: &str) -> String {
+    let mut css = format!(
+        "@font-face{{font-family:\"{family}\";src:url(\"{family}.woff2\") format(\"woff2\"),url(\"{family}.ttf\") format(\"truetype\");font-weight:normal;font-style:normal;font-display:block}}\n\
+         .{prefix}{{font-family:\"{family}\"!important;font-style:normal;font-weight:normal;font-variant:normal;line-height:1;speak:never;-webkit-font-smoothing:antialiased;-moz-osx-font-smoothing:grayscale}}\n",
+        family = family,
+        prefix = class_prefix
+    );
+    let mut sorted: Vec<&FontGlyph> = glyphs.iter().collect();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    for glyph in sorted {
+        css.push_str(&format!(".{}-{}::before{{content:\"\\{:x}\"}}\n", class_prefix, glyph.name, glyph.codepoint));
+    }
+    css
+}
+
+/// Assembles a TrueType font with one glyph per icon, all a full em wide.
+pub fn build_ttf(family: &str, glyphs: &[FontGlyph]) -> Result<Vec<u8>, IconEngineError> {
+    if glyphs.is_empty() {
+        return Err(IconEngineError::InvalidInput("A font needs at least one glyph".to_string()));
+    }
+    let mut sorted: Vec<&FontGlyph> = glyphs.iter().collect();
+    sorted.sort_by_key(|g| g.codepoint);
+    if let Some(pair) = sorted.windows(2).find(|pair| pair[0].codepoint == pair[1].codepoint) {
+        return Err(IconEngineError::InvalidInput(format!(
+            "'{}' and '{}' share codepoint U+{:04X}",
+            pair[0].name, pair[1].name, pair[0].codepoint
+        )));
+    }
+
+    // Glyph 0 is the empty .notdef glyph.
+    let mut glyf = Vec::new();
+    let mut loca = vec![0u32, 0];
+    let mut hmtx = Vec::new();
+    hmtx.extend_from_slice(&UNITS_PER_EM.to_be_bytes());
+    hmtx.extend_from_slice(&0i16.to_be_bytes());
+
+    let (mut x_min, mut y_min, mut x_max, mut y_max) = (i16::MAX, i16::MAX, i16::MIN, i16::MIN);
+    let (mut max_points, mut max_contours) = (0u16, 0u16);
+    for glyph in &sorted {
+        let bounds = glyph.bounds();
+        if !glyph.contours.is_empty() {
+            x_min = x_min.min(bounds.0);
+            y_min = y_min.min(bounds.1);
+            x_max = x_max.max(bounds.2);
+            y_max = y_max.max(bounds.3);
+            max_points = max_points.max(glyph.points().count() as u16);
+            max_contours = max_contours.max(glyph.contours.len() as u16);
+        }
+        encode_glyph(glyph, &mut glyf);
+        loca.push(glyf.len() as u32);
+        hmtx.extend_from_slice(&UNITS_PER_EM.to_be_bytes());
+        hmtx.extend_from_slice(&bounds.0.to_be_bytes());
+    }
+    if x_min > x_max {
+        (x_min, y_min, x_max, y_max) = (0, 0, 0, 0);
+    }
+    let num_glyphs = sorted.len() as u16 + 1;
+
+    let mut head = Vec::new();
+    head.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // version
+    head.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // font revision
+    head.extend_from_slice(&0u32.to_be_bytes()); // checksum adjustment, patched below
+    head.extend_from_slice(&0x5F0F_3CF5u32.to_be_bytes()); // magic number
+    head.extend_from_slice(&0x000Bu16.to_be_bytes()); // flags: baseline at 0, integer scaling
+    head.extend_from_slice(&UNITS_PER_EM.to_be_bytes());
+    head.extend_from_slice(&0i64.to_be_bytes()); // created, left at the epoch for reproducible output
+    head.extend_from_slice(&0i64.to_be_bytes()); // modified
+    for v in [x_min, y_min, x_max, y_max] {
+        head.extend_from_slice(&v.to_be_bytes());
+    }
+    head.extend_from_slice(&0u16.to_be_bytes()); // mac style
+    head.extend_from_slice(&8u16.to_be_bytes()); // lowest recommended ppem
+    head.extend_from_slice(&2i16.to_be_bytes()); // font direction hint
+    head.extend_from_slice(&1i16.to_be_bytes()); // long loca offsets
+    head.extend_from_slice(&0i16.to_be_bytes()); // glyph data format
+
+    let mut hhea = Vec::new();
+    hhea.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    hhea.extend_from_slice(&ASCENT.to_be_bytes());
+    hhea.extend_from_slice(&DESCENT.to_be_bytes());
+    hhea.extend_from_slice(&0i16.to_be_bytes()); // line gap
+    hhea.extend_from_slice(&UNITS_PER_EM.to_be_bytes()); // max advance width
+    hhea.extend_from_slice(&x_min.to_be_bytes()); // min left side bearing
+    hhea.extend_from_slice(&(UNITS_PER_EM as i16 - x_max).to_be_bytes()); // min right side bearing
+    hhea.extend_from_slice(&x_max.to_be_bytes()); // max extent
+    hhea.extend_from_slice(&1i16.to_be_bytes()); // caret slope rise
+    hhea.extend_from_slice(&[0; 14]); // caret slope run, caret offset, reserved, metric data format
+    hhea.extend_from_slice(&num_glyphs.to_be_bytes()); // number of horizontal metrics
+
+    let mut maxp = Vec::new();
+    maxp.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    maxp.extend_from_slice(&num_glyphs.to_be_bytes());
+    maxp.extend_from_slice(&max_points.to_be_bytes());
+    maxp.extend_from_slice(&max_contours.to_be_bytes());
+    maxp.extend_from_slice(&[0; 4]); // composite points and contours
+    maxp.extend_from_slice(&2u16.to_be_bytes()); // zones
+    maxp.extend_from_slice(&[0; 18]); // no hinting programs or composites
+
+    let first_char = sorted[0].codepoint as u16;
+    let last_char = sorted[sorted.len() - 1].codepoint as u16;
+    let mut os2 = Vec::new();
+    os2.extend_from_slice(&4u16.to_be_bytes()); // version
+    os2.extend_from_slice(&UNITS_PER_EM.to_be_bytes()); // average char width
+    os2.extend_from_slice(&400u16.to_be_bytes()); // weight class
+    os2.extend_from_slice(&5u16.to_be_bytes()); // width class
+    os2.extend_from_slice(&0u16.to_be_bytes()); // embedding: installable
+    for v in [650i16, 600, 0, 75, 650, 600, 0, 350, 50, 300] {
+        // Subscript, superscript and strikeout metrics.
+        os2.extend_from_slice(&v.to_be_bytes());
+    }
+    os2.extend_from_slice(&0i16.to_be_bytes()); // family class
+    os2.extend_from_slice(&[0; 10]); // panose
+    os2.extend_from_slice(&0u32.to_be_bytes()); // unicode ranges
+    os2.extend_from_slice(&(1u32 << 28).to_be_bytes()); // bit 60: Private Use Area
+    os2.extend_from_slice(&[0; 8]);
+    os2.extend_from_slice(b"NONE"); // vendor id
+    os2.extend_from_slice(&0x0040u16.to_be_bytes()); // regular
+    os2.extend_from_slice(&first_char.to_be_bytes());
+    os2.extend_from_slice(&last_char.to_be_bytes());
+    os2.extend_from_slice(&ASCENT.to_be_bytes()); // typographic ascender
+    os2.extend_from_slice(&DESCENT.to_be_bytes()); // typographic descender
+    os2.extend_from_slice(&0i16.to_be_bytes()); // typographic line gap
+    os2.extend_from_slice(&(ASCENT as u16).to_be_bytes()); // windows ascent
+    os2.extend_from_slice(&(-DESCENT as u16).to_be_bytes()); // windows descent
+    os2.extend_from_slice(&1u32.to_be_bytes()); // code page: Latin 1
+    os2.extend_from_slice(&0u32.to_be_bytes());
+    os2.extend_from_slice(&[0; 4]); // x-height and cap height
+    os2.extend_from_slice(&0u16.to_be_bytes()); // default char
+    os2.extend_from_slice(&32u16.to_be_bytes()); // break char
+    os2.extend_from_slice(&0u16.to_be_bytes()); // max context
+
+    let mut post = Vec::new();
+    post.extend_from_slice(&0x0003_0000u32.to_be_bytes()); // version 3: no glyph names
+    post.extend_from_slice(&0u32.to_be_bytes()); // italic angle
+    post.extend_from_slice(&(-75i16).to_be_bytes()); // underline position
+    post.extend_from_slice(&50i16.to_be_bytes()); // underline thickness
+    post.extend_from_slice(&[0; 20]); // proportional, no memory hints
+
+    let loca: Vec<u8> = loca.iter().flat_map(|offset| offset.to_be_bytes()).collect();
+    let codepoints: Vec<u16> = sorted.iter().map(|g| g.codepoint as u16).collect();
+
+    let tables: Vec<([u8; 4], Vec<u8>)> = vec![
+        (*b"OS/2", os2),
+        (*b"cmap", encode_cmap(&codepoints)),
+        (*b"glyf", glyf),
+        (*b"head", head),
+        (*b"hhea", hhea),
+        (*b"hmtx", hmtx),
+        (*b"loca", loca),
+        (*b"maxp", maxp),
+        (*b"name", encode_name(family)),
+        (*b"post", post),
+    ];
+    Ok(assemble_sfnt(&tables))
+}
+
+/// Appends a simple glyph description to `glyf`, padded to four bytes.
+fn encode_glyph(glyph: &FontGlyph, glyf: &mut Vec<u8>) {
+    if glyph.contours.is_empty() {
+        return;
+    }
+    let (x_min, y_min, x_max, y_max) = glyph.bounds();
+    glyf.extend_from_slice(&(glyph.contours.len() as i16).to_be_bytes());
+    for v in [x_min, y_min, x_max, y_max] {
+        glyf.extend_from_slice(&v.to_be_bytes());
+    }
+    let mut end = 0u16;
+    for contour in &glyph.contours {
+        end += contour.len() as u16;
+        glyf.extend_from_slice(&(end - 1).to_be_bytes());
+    }
+    glyf.extend_from_slice(&0u16.to_be_bytes()); // no instructions
+
+    // Coordinates are deltas; small ones are stored in a single byte with the
+    // sign in the flags, and zero deltas are not stored at all.
+    let (mut flags, mut xs, mut ys) = (Vec::new(), Vec::new(), Vec::new());
+    let (mut last_x, mut last_y) = (0i16, 0i16);
+    for point in glyph.points() {
+        let mut flag = point.on_curve as u8;
+        for (delta, out, short_bit, same_bit) in [
+            (point.x - last_x, &mut xs, 0x02, 0x10),
+            (point.y - last_y, &mut ys, 0x04, 0x20),
+        ] {
+            if delta == 0 {
+                flag |= same_bit;
+            } else if delta.unsigned_abs() < 256 {
+                flag |= short_bit;
+                if delta > 0 {
+                    flag |= same_bit;
+                }
+                out.push(delta.unsigned_abs() as u8);
+            } else {
+                out.extend_from_slice(&delta.to_be_bytes());
+            }
+        }
+        flags.push(flag);
+        (last_x, last_y) = (point.x, point.y);
+    }
+    glyf.extend_from_slice(&flags);
+    glyf.extend_from_slice(&xs);
+    glyf.extend_from_slice(&ys);
+    glyf.resize((glyf.len() + 3) & !3, 0);
+}
+
+/// Builds a `cmap` table with a format 4 subtable mapping sorted BMP
+/// codepoints to glyphs 1, 2, ... in order.
+fn encode_cmap(codepoints: &[u16]) -> Vec<u8> {
+    // Runs of consecutive codepoints share one segment.
+    let mut segments: Vec<(u16, u16, u16)> = Vec::new(); // (start, end, first glyph)
+    for (i, &cp) in codepoints.iter().enumerate() {
+        let glyph = i as u16 + 1;
+        match segments.last_mut() {
+            Some((_, end, _)) if *end + 1 == cp => *end = cp,
+            _ => segments.push((cp, cp, glyph)),
+        }
+    }
+    // The required final segment maps 0xFFFF to .notdef.
+    segments.push((0xFFFF, 0xFFFF, 0));
+
+    let seg_count = segments.len() as u16;
+    let entry_selector = 15 - seg_count.leading_zeros() as u16;
+    let search_range = 2u16 << entry_selector;
+
+    let mut subtable = Vec::new();
+    subtable.extend_from_slice(&4u16.to_be_bytes()); // format
+    subtable.extend_from_slice(&(16 + 8 * seg_count).to_be_bytes()); // length
+    subtable.extend_from_slice(&0u16.to_be_bytes()); // language
+    subtable.extend_from_slice(&(seg_count * 2).to_be_bytes());
+    subtable.extend_from_slice(&search_range.to_be_bytes());
+    subtable.extend_from_slice(&entry_selector.to_be_bytes());
+    subtable.extend_from_slice(&(seg_count * 2 - search_range).to_be_bytes());
+    for &(_, end, _) in &segments {
+        subtable.extend_from_slice(&end.to_be_bytes());
+    }
+    subtable.extend_from_slice(&0u16.to_be_bytes()); // reserved pad
+    for &(start, _, _) in &segments {
+        subtable.extend_from_slice(&start.to_be_bytes());
+    }
+    for &(start, _, glyph) in &segments {
+        // Glyph ids are computed modulo 65536.
+        subtable.extend_from_slice(&glyph.wrapping_sub(start).to_be_bytes());
+    }
+    for _ in &segments {
+        subtable.extend_from_slice(&0u16.to_be_bytes()); // id range offset
+    }
+
+    let mut cmap = Vec::new();
+    cmap.extend_from_slice(&0u16.to_be_bytes()); // version
+    cmap.extend_from_slice(&2u16.to_be_bytes()); // number of encoding records
+    for (platform, encoding) in [(0u16, 3u16), (3, 1)] {
+        // Unicode BMP and Windows Unicode BMP share the subtable.
+        cmap.extend_from_slice(&platform.to_be_bytes());
+        cmap.extend_from_slice(&encoding.to_be_bytes());
+        cmap.extend_from_slice(&20u32.to_be_bytes()); // subtable offset
+    }
+    cmap.extend_from_slice(&subtable);
+    cmap
+}
+
+/// Builds a `name` table with Windows English names for the family.
+fn encode_name(family: &str) -> Vec<u8> {
+    let postscript: String = family.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    let names = [
+        (1u16, family.to_string()),
+        (2, "Regular".to_string()),
+        (3, format!("{}:1.0", family)),
+        (4, family.to_string()),
+        (5, "Version 1.0".to_string()),
+        (6, postscript),
+    ];
+
+    let mut strings = Vec::new();
+    let mut name = Vec::new();
+    name.extend_from_slice(&0u16.to_be_bytes()); // format
+    name.extend_from_slice(&(names.len() as u16).to_be_bytes());
+    name.extend_from_slice(&(6 + 12 * names.len() as u16).to_be_bytes()); // string storage offset
+    for (id, value) in &names {
+        let encoded: Vec<u8> = value.encode_utf16().flat_map(|u| u.to_be_bytes()).collect();
+        name.extend_from_slice(&3u16.to_be_bytes()); // platform: Windows
+        name.extend_from_slice(&1u16.to_be_bytes()); // encoding: Unicode BMP
+        name.extend_from_slice(&0x0409u16.to_be_bytes()); // language: English (US)
+        name.extend_from_slice(&id.to_be_bytes());
+        name.extend_from_slice(&(encoded.len() as u16).to_be_bytes());
+        name.extend_from_slice(&(strings.len() as u16).to_be_bytes());
+        strings.extend_from_slice(&encoded);
+    }
+    name.extend_from_slice(&strings);
+    name
+}
+
+fn table_checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Writes the sfnt table directory and tables, which must be sorted by tag,
+/// and fills in the `head` checksum adjustment.
+fn assemble_sfnt(tables: &[([u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range = 16u16 << entry_selector;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // TrueType outlines
+    font.extend_from_slice(&num_tables.to_be_bytes());
+    font.extend_from_slice(&search_range.to_be_bytes());
+    font.extend_from_slice(&entry_selector.to_be_bytes());
+    font.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+
+    let mut offset = 12 + 16 * tables.len() as u32;
+    let mut head_offset = 0;
+    for (tag, data) in tables {
+        if tag == b"head" {
+            head_offset = offset as usize;
+        }
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&table_checksum(data).to_be_bytes());
+        font.extend_from_slice(&offset.to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += (data.len() as u32 + 3) & !3;
+    }
+    for (_, data) in tables {
+        font.extend_from_slice(data);
+        font.resize((font.len() + 3) & !3, 0);
+    }
+
+    let adjustment = 0xB1B0_AFBAu32.wrapping_sub(table_checksum(&font));
+    font[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    font
+}
+
+/// Wraps a TrueType font in a WOFF2 container.
+///
+/// Tables are stored untransformed (WOFF2's null transform for `glyf` and
+/// `loca` included) and compressed together with Brotli.
+pub fn ttf_to_woff2(ttf: &[u8]) -> Result<Vec<u8>, IconEngineError> {
+    let invalid = || IconEngineError::InvalidInput("Malformed TrueType font".to_string());
+    let read_u16 = |at: usize| ttf.get(at..at + 2).map(|b| u16::from_be_bytes([b[0], b[1]])).ok_or_else(invalid);
+    let read_u32 =
+        |at: usize| ttf.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]])).ok_or_else(invalid);
+
+    let num_tables = read_u16(4)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+    for i in 0..num_tables {
+        let record = 12 + 16 * i;
+        let tag: [u8; 4] = ttf.get(record..record + 4).ok_or_else(invalid)?.try_into().unwrap();
+        let offset = read_u32(record + 8)? as usize;
+        let length = read_u32(record + 12)? as usize;
+        tables.push((tag, ttf.get(offset..offset + length).ok_or_else(invalid)?));
+    }
+    // Decoders expect loca to directly follow glyf.
+    if let Some(loca) = tables.iter().position(|(tag, _)| tag == b"loca") {
+        let loca = tables.remove(loca);
+        let glyf = tables.iter().position(|(tag, _)| tag == b"glyf").map_or(tables.len(), |i| i + 1);
+        tables.insert(glyf, loca);
+    }
+
+    let mut directory = Vec::new();
+    let mut stream = Vec::new();
+    for (tag, data) in &tables {
+        match KNOWN_TAGS.iter().position(|known| *known == tag) {
+            Some(index) => {
+                let transform = if tag == b"glyf" || tag == b"loca" { 3 << 6 } else { 0 };
+                directory.push(index as u8 | transform);
+            }
+            None => {
+                directory.push(63);
+                directory.extend_from_slice(tag);
+            }
+        }
+        write_base128(&mut directory, data.len() as u32);
+        stream.extend_from_slice(data);
+    }
+
+    let mut compressed = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, 11, 22);
+        writer.write_all(&stream).map_err(|e| IconEngineError::RenderError(e.to_string()))?;
+    }
+
+    let total_sfnt_size = 12 + 16 * tables.len() + tables.iter().map(|(_, d)| (d.len() + 3) & !3).sum::<usize>();
+    let length = (48 + directory.len() + compressed.len() + 3) & !3;
+
+    let mut out = Vec::with_capacity(length);
+    out.extend_from_slice(b"wOF2");
+    out.extend_from_slice(&0x0001_0000u32.to_be_bytes()); // flavor: TrueType
+    out.extend_from_slice(&(length as u32).to_be_bytes());
+    out.extend_from_slice(&(tables.len() as u16).to_be_bytes());
+    out.extend_from_slice(&0u16.to_be_bytes()); // reserved
+    out.extend_from_slice(&(total_sfnt_size as u32).to_be_bytes());
+    out.extend_from_slice(&(compressed.len() as u32).to_be_bytes());
+    out.extend_from_slice(&1u16.to_be_bytes()); // major version
+    out.extend_from_slice(&0u16.to_be_bytes()); // minor version
+    out.extend_from_slice(&[0; 20]); // no metadata or private data
+    out.extend_from_slice(&directory);
+    out.extend_from_slice(&compressed);
+    out.resize(length, 0);
+    Ok(out)
+}
+
+/// The tags WOFF2 can refer to by index instead of spelling them out.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+    b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm", b"glyf", b"loca",
+    b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern", b"LTSH", b"PCLT", b"VDMX", b"vhea",
+    b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC", b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL",
+    b"SVG ", b"sbix", b"acnt", b"avar", b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar",
+    b"gvar", b"hsty", b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+    b"Gloc", b"Feat", b"Sill",
+];
+
+/// Writes a WOFF2 `UIntBase128`: big-endian groups of seven bits with the
+/// high bit set on all but the last byte.
+fn write_base128(out: &mut Vec<u8>, value: u32) {
+    let mut groups = vec![(value & 0x7F) as u8];
+    let mut rest = value >> 7;
+    while rest > 0 {
+        groups.push((rest & 0x7F) as u8 | 0x80);
+        rest >>= 7;
+    }
+    out.extend(groups.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+    const CIRCLE_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M2 12a10 10 0 1 0 20 0a10 10 0 1 0-20 0z"></path></svg>"#;
+
+    fn glyphs() -> Vec<FontGlyph> {
+        vec![
+            FontGlyph::from_svg("triangle", 0xE000, TEST_SVG).unwrap(),
+            FontGlyph::from_svg("circle", 0xE001, CIRCLE_SVG).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn ttf_maps_codepoints_to_normalized_outlines() {
+        let ttf = build_ttf("Test Icons", &glyphs()).unwrap();
+        let face = ttf_parser::Face::parse(&ttf, 0).unwrap();
+        assert_eq!(face.units_per_em(), UNITS_PER_EM);
+        assert_eq!(face.number_of_glyphs(), 3);
+
+        let triangle = face.glyph_index('\u{E000}').unwrap();
+        let bbox = face.glyph_bounding_box(triangle).unwrap();
+        // The 24-unit viewBox fills the em square: y 2..22 maps to 767..-67.
+        assert_eq!((bbox.x_min, bbox.x_max), (83, 917));
+        assert_eq!((bbox.y_min, bbox.y_max), (-67, 767));
+        assert_eq!(face.glyph_hor_advance(triangle), Some(UNITS_PER_EM));
+
+        let circle = face.glyph_index('\u{E001}').unwrap();
+        let bbox = face.glyph_bounding_box(circle).unwrap();
+        assert!((bbox.x_min - 83).abs() <= 2 && (bbox.x_max - 917).abs() <= 2, "{:?}", bbox);
+        assert!(face.glyph_index('\u{E002}').is_none());
+    }
+
+    #[test]
+    fn glyphs_merge_shapes_and_honor_the_viewbox_origin() {
+        // The triangle moved by the viewBox origin, split over a path and a polygon.
+        let shifted = r#"<svg viewBox="100 50 24 24"><path d="M112 52L102 72h10z"/><polygon points="112,52 112,72 122,72"/></svg>"#;
+        let ttf = build_ttf("Test Icons", &[FontGlyph::from_svg("shifted", 0xE000, shifted).unwrap()]).unwrap();
+        let face = ttf_parser::Face::parse(&ttf, 0).unwrap();
+        let bbox = face.glyph_bounding_box(face.glyph_index('\u{E000}').unwrap()).unwrap();
+        assert_eq!((bbox.x_min, bbox.x_max, bbox.y_min, bbox.y_max), (83, 917, -67, 767));
+    }
+
+    #[test]
+    fn ttf_checksums_are_consistent() {
+        let ttf = build_ttf("Test Icons", &glyphs()).unwrap();
+        assert_eq!(table_checksum(&ttf), 0xB1B0_AFBA);
+    }
+
+    #[test]
+    fn woff2_decompresses_to_the_font_tables() {
+        let ttf = build_ttf("Test Icons", &glyphs()).unwrap();
+        let woff2 = ttf_to_woff2(&ttf).unwrap();
+        let read_u32 = |at: usize| u32::from_be_bytes(woff2[at..at + 4].try_into().unwrap()) as usize;
+        assert_eq!(&woff2[..4], b"wOF2");
+        assert_eq!(read_u32(8), woff2.len());
+        assert_eq!(read_u32(16), ttf.len());
+
+        // Walk the table directory: a flag byte and a UIntBase128 length each.
+        let mut pos = 48;
+        let mut entries = Vec::new();
+        for _ in 0..u16::from_be_bytes([woff2[12], woff2[13]]) {
+            let tag = KNOWN_TAGS[(woff2[pos] & 0x3F) as usize];
+            pos += 1;
+            let mut length = 0;
+            loop {
+                length = (length << 7) | (woff2[pos] & 0x7F) as usize;
+                pos += 1;
+                if woff2[pos - 1] & 0x80 == 0 {
+                    break;
+                }
+            }
+            entries.push((tag, length));
+        }
+        let tags: Vec<&[u8; 4]> = entries.iter().map(|(tag, _)| *tag).collect();
+        let glyf = tags.iter().position(|t| *t == b"glyf").unwrap();
+        assert_eq!(tags[glyf + 1], b"loca");
+
+        let mut stream = Vec::new();
+        std::io::Read::read_to_end(&mut brotli::Decompressor::new(&woff2[pos..pos + read_u32(20)], 4096), &mut stream)
+            .unwrap();
+        assert_eq!(stream.len(), entries.iter().map(|(_, len)| len).sum::<usize>());
+        let head_start: usize = entries.iter().take_while(|(tag, _)| *tag != b"head").map(|(_, len)| len).sum();
+        assert_eq!(&stream[head_start + 12..head_start + 16], &0x5F0F_3CF5u32.to_be_bytes());
+    }
+
+    #[test]
+    fn codepoints_are_stable_across_runs() {
+        let mut mapping = BTreeMap::new();
+        assign_codepoints(&["lock".to_string(), "home".to_string()], &mut mapping).unwrap();
+        assert_eq!(mapping["home"], 0xE000);
+        assert_eq!(mapping["lock"], 0xE001);
+
+        let json = codepoint_map_to_json(&mapping);
+        let mut reloaded = parse_codepoint_map(&json).unwrap();
+        assign_codepoints(&["apple".to_string(), "lock".to_string()], &mut reloaded).unwrap();
+        assert_eq!(reloaded["lock"], 0xE001);
+        assert_eq!(reloaded["apple"], 0xE002);
+        assert_eq!(reloaded["home"], 0xE000);
+    }
+
+    #[test]
+    fn css_has_a_class_per_glyph() {
+        let css = font_css("icons", &glyphs(), "icon");
+        assert!(css.contains("url(\"icons.woff2\") format(\"woff2\")"));
+        assert!(css.contains(".icon-triangle::before{content:\"\\e000\"}"));
+        assert!(css.contains(".icon-circle::before{content:\"\\e001\"}"));
+    }
+}
@@ -0,0 +1,314 @@
+use std::f32::consts::PI;
+
+use crate::path_data::{arcs_to_cubics, end_point, CenterArc, PathSegment, Point};
+
+/// Line segments each cubic is split into when flattening.
+const CUBIC_STEPS: usize = 16;
+/// Scanlines used to measure a path's filled area.
+const COVERAGE_ROWS: usize = 256;
+
+/// An axis-aligned rectangle in user space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rect {
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+}
+
+impl Rect {
+    pub const fn new(min_x: f32, min_y: f32, max_x: f32, max_y: f32) -> Self {
+        Self { min_x, min_y, max_x, max_y }
+    }
+
+    pub fn width(&self) -> f32 {
+        self.max_x - self.min_x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.max_y - self.min_y
+    }
+
+    pub fn center(&self) -> Point {
+        Point::new((self.min_x + self.max_x) / 2.0, (self.min_y + self.max_y) / 2.0)
+    }
+
+    /// Grows the rectangle to include `p`.
+    pub fn include(&mut self, p: Point) {
+        self.min_x = self.min_x.min(p.x);
+        self.min_y = self.min_y.min(p.y);
+        self.max_x = self.max_x.max(p.x);
+        self.max_y = self.max_y.max(p.y);
+    }
+
+    /// Returns the smallest rectangle containing every point, if any.
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut rect = Self::new(first.x, first.y, first.x, first.y);
+        points.for_each(|p| rect.include(p));
+        Some(rect)
+    }
+}
+
+/// The filled region of a path under the nonzero fill rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coverage {
+    /// The filled area in square user units.
+    pub area: f32,
+    /// The center of mass of the filled area, where the glyph's visual
+    /// weight sits.
+    pub centroid: Point,
+}
+
+/// Approximates each subpath as a closed polygon.
+pub fn flatten(segments: &[PathSegment]) -> Vec<Vec<Point>> {
+    let mut polygons: Vec<Vec<Point>> = Vec::new();
+    let mut current = Point::new(0.0, 0.0);
+    let mut start = current;
+
+    for segment in arcs_to_cubics(segments) {
+        let points = match segment {
+            PathSegment::MoveTo(p) => {
+                polygons.push(vec![p]);
+                start = p;
+                current = p;
+                continue;
+            }
+            PathSegment::LineTo(p) => vec![p],
+            PathSegment::CubicTo(c1, c2, p) => (1..=CUBIC_STEPS)
+                .map(|i| {
+                    let t = i as f32 / CUBIC_STEPS as f32;
+                    let mt = 1.0 - t;
+                    let (w0, w1, w2, w3) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                    Point::new(
+                        w0 * current.x + w1 * c1.x + w2 * c2.x + w3 * p.x,
+                        w0 * current.y + w1 * c1.y + w2 * c2.y + w3 * p.y,
+                    )
+                })
+                .collect(),
+            PathSegment::QuadTo(c, p) => (1..=CUBIC_STEPS / 2)
+                .map(|i| {
+                    let t = i as f32 / (CUBIC_STEPS / 2) as f32;
+                    let mt = 1.0 - t;
+                    let (w0, w1, w2) = (mt * mt, 2.0 * mt * t, t * t);
+                    Point::new(w0 * current.x + w1 * c.x + w2 * p.x, w0 * current.y + w1 * c.y + w2 * p.y)
+                })
+                .collect(),
+            PathSegment::ClosePath => {
+                current = start;
+                continue;
+            }
+            // Arcs were converted to cubics above.
+            PathSegment::ArcTo { .. } => continue,
+        };
+        if let Some(&last) = points.last() {
+            current = last;
+        }
+        match polygons.last_mut() {
+            Some(polygon) => polygon.extend(points),
+            // Drawing without a leading move starts at the origin.
+            None => polygons.push([vec![Point::new(0.0, 0.0)], points].concat()),
+        }
+    }
+    polygons
+}
+
+/// Returns the approximate length of a path's outline, as used for
+/// `stroke-dasharray` draw-on effects. Every subpath is measured as closed,
+/// so open ones come out slightly long, which still draws them completely.
+pub fn length(segments: &[PathSegment]) -> f32 {
+    flatten(segments)
+        .iter()
+        .map(|polygon| {
+            let edges = polygon.iter().zip(polygon.iter().cycle().skip(1));
+            edges.map(|(a, b)| (b.x - a.x).hypot(b.y - a.y)).sum::<f32>()
+        })
+        .sum()
+}
+
+/// Returns the parameters in `(0, 1)` where a 1D cubic Bézier has a local
+/// extremum.
+fn cubic_extrema(p0: f32, p1: f32, p2: f32, p3: f32) -> Vec<f32> {
+    // The derivative, divided by 3, is a*t^2 + b*t + c.
+    let a = -p0 + 3.0 * p1 - 3.0 * p2 + p3;
+    let b = 2.0 * (p0 - 2.0 * p1 + p2);
+    let c = p1 - p0;
+    let roots = if a.abs() < 1e-6 {
+        if b.abs() < 1e-6 { Vec::new() } else { vec![-c / b] }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            Vec::new()
+        } else {
+            let root = discriminant.sqrt();
+            vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+/// Returns the exact bounding box of a path, including the extrema of its
+/// Bézier curves and elliptical arcs. Control points outside the curve do
+/// not count.
+pub fn bounds(segments: &[PathSegment]) -> Option<Rect> {
+    let mut points = Vec::new();
+    let mut current = Point::new(0.0, 0.0);
+    let mut start = current;
+
+    for segment in segments {
+        match *segment {
+            PathSegment::MoveTo(p) => {
+                start = p;
+                points.push(p);
+            }
+            PathSegment::LineTo(p) => points.push(p),
+            PathSegment::CubicTo(c1, c2, p) => {
+                let at = |t: f32| {
+                    let mt = 1.0 - t;
+                    let (w0, w1, w2, w3) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                    Point::new(
+                        w0 * current.x + w1 * c1.x + w2 * c2.x + w3 * p.x,
+                        w0 * current.y + w1 * c1.y + w2 * c2.y + w3 * p.y,
+                    )
+                };
+                let extrema = [cubic_extrema(current.x, c1.x, c2.x, p.x), cubic_extrema(current.y, c1.y, c2.y, p.y)];
+                points.extend(extrema.concat().into_iter().map(at));
+                points.push(p);
+            }
+            PathSegment::QuadTo(c, p) => {
+                let at = |t: f32| {
+                    let mt = 1.0 - t;
+                    Point::new(
+                        mt * mt * current.x + 2.0 * mt * t * c.x + t * t * p.x,
+                        mt * mt * current.y + 2.0 * mt * t * c.y + t * t * p.y,
+                    )
+                };
+                for (p0, p1, p2) in [(current.x, c.x, p.x), (current.y, c.y, p.y)] {
+                    let denominator = p0 - 2.0 * p1 + p2;
+                    let t = if denominator == 0.0 { -1.0 } else { (p0 - p1) / denominator };
+                    if t > 0.0 && t < 1.0 {
+                        points.push(at(t));
+                    }
+                }
+                points.push(p);
+            }
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to } => {
+                if let Some(arc) = CenterArc::new(current, rx, ry, x_axis_rotation, large_arc, sweep, to) {
+                    // The angles where the ellipse's x and y are extremal, and their opposites.
+                    let (sin_phi, cos_phi) = arc.phi.sin_cos();
+                    let x_angle = (-arc.ry * sin_phi).atan2(arc.rx * cos_phi);
+                    let y_angle = (arc.ry * cos_phi).atan2(arc.rx * sin_phi);
+                    for t in [x_angle, x_angle + PI, y_angle, y_angle + PI] {
+                        if arc.contains_angle(t) {
+                            points.push(arc.point_at(t));
+                        }
+                    }
+                }
+                points.push(to);
+            }
+            PathSegment::ClosePath => {
+                current = start;
+                continue;
+            }
+        }
+        current = end_point(segment).unwrap_or(current);
+    }
+    Rect::from_points(points)
+}
+
+/// Measures the filled area of a path and its centroid by intersecting
+/// scanlines with the flattened outline. Returns `None` for paths that fill
+/// nothing.
+pub fn coverage(segments: &[PathSegment]) -> Option<Coverage> {
+    let polygons = flatten(segments);
+    let bounds = Rect::from_points(polygons.iter().flatten().copied())?;
+    let row_height = bounds.height() / COVERAGE_ROWS as f32;
+    if row_height <= 0.0 || bounds.width() <= 0.0 {
+        return None;
+    }
+
+    let (mut area, mut moment_x, mut moment_y) = (0.0f64, 0.0f64, 0.0f64);
+    let mut crossings: Vec<(f32, i32)> = Vec::new();
+    for row in 0..COVERAGE_ROWS {
+        let y = bounds.min_y + (row as f32 + 0.5) * row_height;
+        crossings.clear();
+        for polygon in &polygons {
+            for (i, &p0) in polygon.iter().enumerate() {
+                let p1 = polygon[(i + 1) % polygon.len()];
+                if (p0.y <= y) != (p1.y <= y) {
+                    let x = p0.x + (y - p0.y) * (p1.x - p0.x) / (p1.y - p0.y);
+                    crossings.push((x, if p1.y > p0.y { 1 } else { -1 }));
+                }
+            }
+        }
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut winding = 0;
+        for pair in crossings.windows(2) {
+            winding += pair[0].1;
+            if winding != 0 {
+                let (x0, x1) = (pair[0].0 as f64, pair[1].0 as f64);
+                let span = (x1 - x0) * row_height as f64;
+                area += span;
+                moment_x += span * (x0 + x1) / 2.0;
+                moment_y += span * y as f64;
+            }
+        }
+    }
+
+    (area > 0.0).then(|| Coverage {
+        area: area as f32,
+        centroid: Point::new((moment_x / area) as f32, (moment_y / area) as f32),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::path_data::parse_path;
+
+    fn assert_bounds(d: &str, expected: Rect) {
+        let actual = bounds(&parse_path(d).unwrap()).unwrap();
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(
+            close(actual.min_x, expected.min_x)
+                && close(actual.min_y, expected.min_y)
+                && close(actual.max_x, expected.max_x)
+                && close(actual.max_y, expected.max_y),
+            "{}: {:?}",
+            d,
+            actual
+        );
+    }
+
+    #[test]
+    fn bounds_include_curve_extrema() {
+        // A circle of radius 10 drawn as two arcs.
+        assert_bounds("M2 12A10 10 0 0 0 22 12A10 10 0 0 0 2 12Z", Rect::new(2.0, 2.0, 22.0, 22.0));
+        // A quarter arc only reaches its own extremes.
+        assert_bounds("M10 0A10 10 0 0 1 20 10", Rect::new(10.0, 0.0, 20.0, 10.0));
+        // An ellipse rotated by 90 degrees swaps its radii.
+        assert_bounds("M12 2A10 4 90 0 0 12 22A10 4 90 0 0 12 2Z", Rect::new(8.0, 2.0, 16.0, 22.0));
+        // The control points of a symmetric cubic reach 10, the curve only 7.5.
+        assert_bounds("M0 0C0 10 10 10 10 0", Rect::new(0.0, 0.0, 10.0, 7.5));
+        assert_bounds("M0 0Q5 10 10 0", Rect::new(0.0, 0.0, 10.0, 5.0));
+        assert_bounds("M0 0L10 0M5 5L6 -3", Rect::new(0.0, -3.0, 10.0, 5.0));
+    }
+
+    #[test]
+    fn coverage_measures_area_and_centroid() {
+        // A right triangle's centroid sits a third of the way from its legs.
+        let triangle = parse_path("M0 0L0 30L30 30Z").unwrap();
+        let coverage = coverage(&triangle).unwrap();
+        assert!((coverage.area - 450.0).abs() < 1.0, "{}", coverage.area);
+        assert!((coverage.centroid.x - 10.0).abs() < 0.1 && (coverage.centroid.y - 20.0).abs() < 0.1);
+
+        // Holes wound the other way are not filled; overlapping shapes count once.
+        let ring = parse_path("M0 0H10V10H0ZM2 2V8H8V2Z").unwrap();
+        assert!((super::coverage(&ring).unwrap().area - 64.0).abs() < 0.5);
+        let overlap = parse_path("M0 0H10V10H0ZM0 0H10V10H0Z").unwrap();
+        assert!((super::coverage(&overlap).unwrap().area - 100.0).abs() < 0.5);
+        assert!(super::coverage(&parse_path("M0 0L10 0").unwrap()).is_none());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::geometry;
+use crate::path_data::{format_number, parse_path, to_path_data, transform_path, PathSegment, Point, Transform};
+use crate::svg_processor::{self, SvgIcon};
+use crate::{CustomStyles, IconEngineError};
+
+const KNOCKOUT_MASK_ID: &str = "glyph-knockout";
+
+/// A source glyph stacked above the main one, e.g. a lock over a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlyphLayer {
+    /// The source SVG, parsed like the main icon.
+    pub icon_data: String,
+    /// Places the layer in the main glyph's viewBox, after the layer's own
+    /// viewBox has been scaled to the main one.
+    pub transform: Transform,
+    /// The fill of the layer; the icon color when unset.
+    pub color: Option<String>,
+    pub opacity: f32,
+    /// The width of a gap, in canvas units, cut out of the layers below
+    /// around this layer's outline so overlapping glyphs stay legible.
+    pub knockout: Option<f32>,
+}
+
+impl GlyphLayer {
+    /// A layer drawn over the main glyph as is, in the icon color.
+    pub fn new(icon_data: &str) -> Self {
+        Self {
+            icon_data: icon_data.to_string(),
+            transform: Transform::IDENTITY,
+            color: None,
+            opacity: 1.0,
+            knockout: None,
+        }
+    }
+}
+
+/// Fails for styles with glyph layers, for outputs that cannot draw them.
+pub(crate) fn reject_glyph_layers(styles: &CustomStyles, output: &str) -> Result<(), IconEngineError> {
+    if styles.glyph_layers.is_empty() {
+        return Ok(());
+    }
+    Err(IconEngineError::InvalidInput(format!("Glyph layers are not supported in {} output", output)))
+}
+
+/// Maps a layer's source coordinates into the main glyph's viewBox: its
+/// viewBox is scaled to the main one, then the layer's own transform applies.
+fn layer_matrix(icon: &SvgIcon, source: &SvgIcon, layer: &GlyphLayer) -> Transform {
+    let to_main = (icon.viewbox.width / source.viewbox.width).min(icon.viewbox.height / source.viewbox.height);
+    Transform::scale(to_main, to_main).then(layer.transform)
+}
+
+/// Returns the outlines of `CustomStyles::glyph_layers` in the main glyph's
+/// viewBox, for fitting the whole stack. Layers that cannot be parsed are
+/// skipped; [`stack_markup`] reports them.
+pub(crate) fn layer_outlines(icon: &SvgIcon, styles: &CustomStyles) -> Vec<Vec<PathSegment>> {
+    styles
+        .glyph_layers
+        .iter()
+        .filter_map(|layer| {
+            let source = svg_processor::parse_svg(&layer.icon_data).ok()?;
+            let segments = parse_path(&source.path_data).ok()?;
+            Some(transform_path(&segments, &layer_matrix(icon, &source, layer)))
+        })
+        .collect()
+}
+
+/// Returns the main glyph with `CustomStyles::glyph_layers` stacked above it,
+/// in a group applying the fit transform or, with `bake_transform`, with the
+/// fit baked into every path.
+pub(crate) fn stack_markup(icon: &SvgIcon, styles: &CustomStyles, icon_color: &str) -> Result<String, IconEngineError> {
+    let fit = svg_processor::placement_matrix(icon, styles)?;
+    // The coordinate system the paths are written in, and its size in canvas units.
+    let (frame, frame_scale) = if styles.bake_transform {
+        (fit, 1.0)
+    } else {
+        (Transform::IDENTITY, (fit.a * fit.d - fit.b * fit.c).abs().sqrt())
+    };
+
+    let main = transform_path(&parse_path(&icon.path_data)?, &frame);
+    let main_data = if styles.bake_transform { to_path_data(&main) } else { icon.path_data.clone() };
+    let mut content = format!(r#"<path class="icon-fg" d="{}" fill="{}"/>"#, main_data, icon_color);
+    let mut below = geometry::bounds(&main);
+    let mut masks = String::new();
+
+    for (index, layer) in styles.glyph_layers.iter().enumerate() {
+        if !(0.0..=1.0).contains(&layer.opacity) {
+            return Err(IconEngineError::InvalidInput(format!(
+                "Glyph layer opacity must be between 0 and 1, got {}",
+                layer.opacity
+            )));
+        }
+        let source = svg_processor::parse_svg(&layer.icon_data)?;
+        let matrix = layer_matrix(icon, &source, layer).then(frame);
+        let segments = transform_path(&parse_path(&source.path_data)?, &matrix);
+        let data = to_path_data(&segments);
+
+        if let (Some(knockout), Some(area)) = (layer.knockout, below) {
+            let id = format!("{}-{}", KNOCKOUT_MASK_ID, index + 1);
+            let gap = format_number(2.0 * knockout / frame_scale);
+            // The mask must cover everything below; a unit of slack keeps
+            // antialiased edges from being clipped.
+            masks.push_str(&format!(
+                r#"<mask id="{id}" maskUnits="userSpaceOnUse" x="{x}" y="{y}" width="{w}" height="{h}"><rect x="{x}" y="{y}" width="{w}" height="{h}" fill="white"/><path d="{d}" fill="black" stroke="black" stroke-width="{gap}" stroke-linejoin="round"/></mask>"#,
+                id = id,
+                x = format_number(area.min_x - 1.0),
+                y = format_number(area.min_y - 1.0),
+                w = format_number(area.width() + 2.0),
+                h = format_number(area.height() + 2.0),
+                d = data,
+                gap = gap
+            ));
+            content = format!(r#"<g mask="url(#{})">{}</g>"#, id, content);
+        }
+
+        let opacity = if layer.opacity < 1.0 {
+            format!(r#" opacity="{}""#, format_number(layer.opacity))
+        } else {
+            String::new()
+        };
+        content.push_str(&format!(
+            r#"<path class="icon-layer icon-layer-{}" d="{}" fill="{}"{}/>"#,
+            index + 1,
+            data,
+            layer.color.as_deref().unwrap_or(icon_color),
+            opacity
+        ));
+        if let Some(bounds) = geometry::bounds(&segments) {
+            let area = below.get_or_insert(bounds);
+            area.include(Point::new(bounds.min_x, bounds.min_y));
+            area.include(Point::new(bounds.max_x, bounds.max_y));
+        }
+    }
+
+    let defs = if masks.is_empty() { String::new() } else { format!("<defs>{}</defs>", masks) };
+    if styles.bake_transform {
+        return Ok(format!("{}{}", defs, content));
+    }
+    Ok(format!(
+        r#"<g transform="{}">{}{}</g>"#,
+        svg_processor::placement_transform(icon, styles)?,
+        defs,
+        content
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, FitMode, StylePreset};
+
+    const DOCUMENT: &str = r#"<svg viewBox="0 0 24 24"><path d="M4 2H20V22H4Z"/></svg>"#;
+    const LOCK: &str = r#"<svg viewBox="0 0 12 12"><path d="M2 5H10V11H2Z"/></svg>"#;
+
+    #[test]
+    fn stacks_layers_with_knockouts() {
+        let lock = GlyphLayer {
+            transform: Transform::parse("translate(12 12) scale(0.5)").unwrap(),
+            color: Some("#c62828".to_string()),
+            opacity: 0.8,
+            knockout: Some(4.0),
+            ..GlyphLayer::new(LOCK)
+        };
+        let styles = CustomStyles { glyph_layers: vec![lock], ..CustomStyles::default() };
+        let svg = generate_icon(DOCUMENT, StylePreset::Neumorphism, &styles).unwrap();
+        roxmltree::Document::parse(&svg).unwrap();
+
+        // The 12-unit viewBox doubles to the main 24, then the layer's own
+        // transform halves it into the bottom-right quarter.
+        assert!(svg.contains(r##"<path class="icon-layer icon-layer-1" d="M14 17L22 17L22 23L14 23Z" fill="#c62828" opacity="0.8"/>"##));
+        assert!(svg.contains(r#"<g mask="url(#glyph-knockout-1)"><path class="icon-fg" d="M4 2H20V22H4Z""#));
+        // A 4px gap at the fit scale of 4 is one viewBox unit on each side.
+        assert!(svg.contains(r#"stroke-width="2""#));
+        assert!(svg.contains(r#"<mask id="glyph-knockout-1" maskUnits="userSpaceOnUse" x="3" y="1" width="18" height="22">"#));
+    }
+
+    #[test]
+    fn layers_are_fitted_with_the_main_glyph_and_validated() {
+        let beside = GlyphLayer { transform: Transform::translate(12.0, 0.0), ..GlyphLayer::new(LOCK) };
+        let styles = CustomStyles { glyph_layers: vec![beside], fit: FitMode::Tight, ..CustomStyles::default() };
+        let icon = svg_processor::parse_svg(DOCUMENT).unwrap();
+        // The document spans x 4..20 and the lock 16..32, so 28 units fill 96 pixels.
+        let placement = svg_processor::glyph_placement(&icon, &styles);
+        assert!((placement.scale_x - 96.0 / 28.0).abs() < 1e-3, "{:?}", placement);
+        assert!((placement.tx + 4.0 * placement.scale_x - 16.0).abs() < 1e-3, "{:?}", placement);
+
+        for opacity in [-0.1, 1.5, f32::NAN] {
+            let styles = CustomStyles {
+                glyph_layers: vec![GlyphLayer { opacity, ..GlyphLayer::new(LOCK) }],
+                ..CustomStyles::default()
+            };
+            assert!(generate_icon(DOCUMENT, StylePreset::Neumorphism, &styles).is_err());
+        }
+    }
+
+    #[test]
+    fn bakes_stacked_layers() {
+        let styles = CustomStyles {
+            glyph_layers: vec![GlyphLayer { knockout: Some(2.0), ..GlyphLayer::new(LOCK) }],
+            bake_transform: true,
+            ..CustomStyles::default()
+        };
+        let markup = stack_markup(&svg_processor::parse_svg(DOCUMENT).unwrap(), &styles, "black").unwrap();
+        assert!(!markup.contains("transform"));
+        assert!(markup.contains(r#"<path class="icon-fg" d="M32 24L96 24L96 104L32 104Z""#));
+        assert!(markup.contains(r#"<path class="icon-layer icon-layer-1" d="M32 56L96 56L96 104L32 104Z" fill="black"/>"#));
+        assert!(markup.contains(r#"stroke-width="4""#));
+    }
+}
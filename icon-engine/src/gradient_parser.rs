@@ -1,3 +1,4 @@
+use crate::palette::PALETTE_REF_PREFIX;
 use crate::{Gradient, IconEngineError};
 
 /// Parses a simple `linear-gradient()` CSS string.
@@ -37,10 +38,11 @@ pub fn parse_gradient(input: &str) -> Result<Gradient, IconEngineError> {
     let start_color = parts[1].to_string();
     let stop_color = parts[2].to_string();
 
-    // Perform a basic sanity check on colors
-    if !start_color.starts_with('#') || !stop_color.starts_with('#') {
+    // Perform a basic sanity check on colors; palette references are resolved later.
+    let is_color = |c: &str| c.starts_with('#') || c.starts_with(PALETTE_REF_PREFIX);
+    if !is_color(&start_color) || !is_color(&stop_color) {
          return Err(IconEngineError::InvalidInput(
-            "Colors must be in hex format (e.g., #RRGGBB)".to_string(),
+            "Colors must be in hex format (e.g., #RRGGBB) or palette references (e.g., @primary)".to_string(),
         ));
     }
 
@@ -88,6 +90,12 @@ mod tests {
         assert!(parse_gradient("linear-gradient(45, #ff0000, #0000ff)").is_err());
     }
 
+    #[test]
+    fn parse_palette_reference_succeeds() {
+        let result = parse_gradient("linear-gradient(0deg, @primary, #000000)").unwrap();
+        assert_eq!(result.start_color, "@primary");
+    }
+
     #[test]
     fn parse_invalid_color_format_fails() {
          assert!(parse_gradient("linear-gradient(45deg, red, #0000ff)").is_err());
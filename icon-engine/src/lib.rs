@@ -1,7 +1,37 @@
 pub mod svg_processor;
+pub mod animation;
 pub mod style_generator;
 pub mod gradient_parser;
+pub mod color;
+pub mod theme;
+pub mod contrast;
+pub mod palette;
+pub mod sprite;
+pub mod path_data;
+pub mod geometry;
+pub mod glyph_stack;
+pub mod native;
+pub mod components;
+pub mod optimize;
+pub mod overlay;
+pub mod pattern;
+pub mod states;
+#[cfg(feature = "raster")]
+pub mod raster;
+#[cfg(feature = "raster")]
+pub mod containers;
+#[cfg(feature = "raster")]
+pub mod encoders;
+#[cfg(feature = "raster")]
+pub mod atlas;
+#[cfg(feature = "font")]
+pub mod font;
+#[cfg(feature = "pdf")]
+pub mod print;
+#[cfg(feature = "monogram")]
+pub mod monogram;
 
+use geometry::Rect;
 use thiserror::Error;
 
 /// Public-facing error type for the icon generation process.
@@ -11,6 +41,10 @@ pub enum IconEngineError {
     SvgParsingError(String),
     #[error("Invalid input provided: {0}")]
     InvalidInput(String),
+    #[error("Foreground contrast {ratio:.2}:1 is below the required {required:.2}:1")]
+    InsufficientContrast { ratio: f32, required: f32 },
+    #[error("Failed to render icon: {0}")]
+    RenderError(String),
     #[error("An unknown error has occurred")]
     Unknown,
 }
@@ -24,6 +58,74 @@ pub enum StylePreset {
     FrostedGlass,
 }
 
+/// How the glyph is scaled and positioned within the padded area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum FitMode {
+    /// Fits the source viewBox, keeping the whitespace drawn into it.
+    #[default]
+    ViewBox,
+    /// Fits the bounding box of the path geometry.
+    Tight,
+    /// Fits like `Tight`, then moves the glyph's visual centroid towards the
+    /// center as far as the padding allows.
+    Optical,
+    /// Scales the glyph so its filled area covers `CustomStyles::target_coverage`
+    /// of the padded area, never beyond a tight fit, and centers it optically.
+    /// Gives a set of icons a consistent visual weight.
+    VisualArea,
+}
+
+/// One of the nine anchors a glyph can be aligned to within the padded area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Alignment {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    #[default]
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+/// Adjusts where and how the glyph is drawn after it has been fitted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    /// Where the glyph's bounds sit within the padded area.
+    pub align: Alignment,
+    /// Moves the glyph by `(x, y)` canvas units after aligning it.
+    pub offset: (f32, f32),
+    /// Multiplies the fitted scale.
+    pub scale: f32,
+    /// Sets the longer side of the glyph's box in canvas units instead of
+    /// fitting it into the padded area; `scale` still applies.
+    pub size: Option<f32>,
+    /// Clockwise rotation in degrees.
+    pub rotation: f32,
+    /// Mirrors the glyph left to right, e.g. for right-to-left variants.
+    pub flip_horizontal: bool,
+    /// Mirrors the glyph top to bottom.
+    pub flip_vertical: bool,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            align: Alignment::Center,
+            offset: (0.0, 0.0),
+            scale: 1.0,
+            size: None,
+            rotation: 0.0,
+            flip_horizontal: false,
+            flip_vertical: false,
+        }
+    }
+}
+
 /// Represents a CSS linear gradient.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Gradient {
@@ -32,6 +134,109 @@ pub struct Gradient {
     pub stop_color: String,
 }
 
+/// The pair of shadow colors used by the neumorphism preset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowColors {
+    /// The shadow cast towards the bottom-right.
+    pub dark: String,
+    /// The highlight cast towards the top-left.
+    pub light: String,
+}
+
+/// A padding length: canvas units, or a percentage of the canvas width (left
+/// and right) or height (top and bottom).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Percent(f32),
+}
+
+impl Length {
+    /// Resolves the length against a canvas side of `extent` units.
+    pub fn resolve(self, extent: f32) -> f32 {
+        match self {
+            Length::Px(value) => value,
+            Length::Percent(percent) => extent * percent / 100.0,
+        }
+    }
+}
+
+/// Padding for each side of the canvas, overriding `CustomStyles::padding`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Insets {
+    pub top: Length,
+    pub right: Length,
+    pub bottom: Length,
+    pub left: Length,
+}
+
+impl Insets {
+    /// Parses CSS `padding` shorthand: one to four lengths in top, right,
+    /// bottom, left order, each a plain number of units or a percentage,
+    /// e.g. `"10%"` or `"8 12 16 12"`.
+    pub fn parse(input: &str) -> Result<Self, IconEngineError> {
+        let lengths = input
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let invalid = || IconEngineError::InvalidInput(format!("Invalid padding length: {}", part));
+                let (number, percent) = match part.strip_suffix('%') {
+                    Some(number) => (number, true),
+                    None => (part.strip_suffix("px").unwrap_or(part), false),
+                };
+                let value = number.parse::<f32>().map_err(|_| invalid())?;
+                if !value.is_finite() || value < 0.0 {
+                    return Err(invalid());
+                }
+                Ok(if percent { Length::Percent(value) } else { Length::Px(value) })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let (top, right, bottom, left) = match lengths[..] {
+            [all] => (all, all, all, all),
+            [vertical, horizontal] => (vertical, horizontal, vertical, horizontal),
+            [top, horizontal, bottom] => (top, horizontal, bottom, horizontal),
+            [top, right, bottom, left] => (top, right, bottom, left),
+            _ => {
+                return Err(IconEngineError::InvalidInput(
+                    "Padding must have one to four lengths".to_string(),
+                ))
+            }
+        };
+        Ok(Self { top, right, bottom, left })
+    }
+}
+
+/// A platform template for the area the glyph must stay inside, as a share
+/// of the canvas. Takes precedence over `CustomStyles::padding` and `insets`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SafeZone {
+    /// The square inscribed in the 66dp safe-zone circle of a 108dp Android
+    /// adaptive icon layer, so no launcher mask clips the glyph.
+    AndroidAdaptive,
+    /// The outer keyline square of Apple's app icon grid, 10% in from each
+    /// edge.
+    IosGrid,
+    /// The middle half of a Windows tile, raised so the app name below it
+    /// stays clear.
+    WindowsTile,
+}
+
+impl SafeZone {
+    /// Returns the `(top, right, bottom, left)` insets as shares of the canvas.
+    fn insets(self) -> (f32, f32, f32, f32) {
+        match self {
+            SafeZone::AndroidAdaptive => {
+                let inset = (108.0 - 66.0 / std::f32::consts::SQRT_2) / 2.0 / 108.0;
+                (inset, inset, inset, inset)
+            }
+            SafeZone::IosGrid => (0.1, 0.1, 0.1, 0.1),
+            SafeZone::WindowsTile => (0.2, 0.25, 0.3, 0.25),
+        }
+    }
+}
+
 /// Defines all user-configurable properties for the generated icon.
 #[derive(Debug, Clone)]
 pub struct CustomStyles {
@@ -41,6 +246,40 @@ pub struct CustomStyles {
     pub padding: u32,
     pub icon_color: String,
     pub gradient: Option<Gradient>,
+    /// A texture drawn over the base fill or gradient.
+    pub pattern: Option<pattern::BasePattern>,
+    /// The minimum WCAG contrast ratio used when `icon_color` is `"auto"` and
+    /// by `contrast::check_contrast`.
+    pub min_contrast: f32,
+    /// Overrides the preset's default base fill when no gradient is set.
+    pub base_color: Option<String>,
+    /// Overrides the preset's default shadow colors.
+    pub shadow_colors: Option<ShadowColors>,
+    /// Draws the preset's shadows; small raster sizes turn them into a blur.
+    pub shadows: bool,
+    /// Applies the glyph's fit to its path coordinates instead of wrapping it
+    /// in a `<g transform>`, for consumers that handle group transforms poorly.
+    pub bake_transform: bool,
+    /// How the glyph is fitted into the padded area.
+    pub fit: FitMode,
+    /// The share of the padded area the glyph's filled region should cover
+    /// with `FitMode::VisualArea`.
+    pub target_coverage: f32,
+    /// Alignment, offset, scaling, rotation and mirroring of the glyph.
+    pub placement: Placement,
+    /// Per-side padding that replaces the uniform `padding`.
+    pub insets: Option<Insets>,
+    /// A platform safe zone that replaces `padding` and `insets`.
+    pub safe_zone: Option<SafeZone>,
+    /// Badges and secondary glyphs drawn over the icon, in order.
+    pub overlays: Vec<overlay::Overlay>,
+    /// Further source glyphs stacked above the main one, bottom to top.
+    pub glyph_layers: Vec<glyph_stack::GlyphLayer>,
+    /// Animates the icon; only `generate_icon` writes animations.
+    pub animation: Option<animation::Animation>,
+    /// The interaction state drawn; derive a state's styles, e.g. its greyed
+    /// out colors, with [`states::state_styles`].
+    pub state: states::IconState,
 }
 
 impl Default for CustomStyles {
@@ -52,10 +291,53 @@ impl Default for CustomStyles {
             padding: 16,
             icon_color: "#333333".to_string(),
             gradient: None,
+            pattern: None,
+            min_contrast: contrast::WCAG_NON_TEXT_CONTRAST,
+            base_color: None,
+            shadow_colors: None,
+            shadows: true,
+            bake_transform: false,
+            fit: FitMode::ViewBox,
+            target_coverage: 0.4,
+            placement: Placement::default(),
+            insets: None,
+            safe_zone: None,
+            overlays: Vec::new(),
+            glyph_layers: Vec::new(),
+            animation: None,
+            state: states::IconState::Normal,
         }
     }
 }
 
+impl CustomStyles {
+    /// Returns the area of the canvas the glyph is fitted into: the canvas
+    /// minus the safe zone's, the insets' or the uniform padding, in that
+    /// order of precedence. Padding larger than the canvas leaves an empty box.
+    pub fn content_box(&self) -> Rect {
+        let (width, height) = (self.width as f32, self.height as f32);
+        let (top, right, bottom, left) = match (self.safe_zone, self.insets) {
+            (Some(zone), _) => {
+                let (top, right, bottom, left) = zone.insets();
+                (top * height, right * width, bottom * height, left * width)
+            }
+            (None, Some(insets)) => (
+                insets.top.resolve(height),
+                insets.right.resolve(width),
+                insets.bottom.resolve(height),
+                insets.left.resolve(width),
+            ),
+            (None, None) => {
+                let padding = self.padding as f32;
+                (padding, padding, padding, padding)
+            }
+        };
+        let max_x = (width - right).max(left);
+        let max_y = (height - bottom).max(top);
+        Rect::new(left, top, max_x, max_y)
+    }
+}
+
 /// The main library function to generate an SVG icon.
 pub fn generate_icon(
     icon_data: &str,
@@ -64,26 +346,94 @@ pub fn generate_icon(
 ) -> Result<String, IconEngineError> {
     // 1. Parse and prepare the foreground icon
     let icon = svg_processor::parse_svg(icon_data)?;
-    let transform = svg_processor::calculate_transform(icon.viewbox, styles);
+    let icon_color = contrast::resolve_icon_color(styles, style_preset);
+    let foreground = foreground_markup(&icon, styles, &icon_color)?;
+    let animated = animation::animate_foreground(&icon, styles, style_preset, foreground, &icon_color)?;
 
     // 2. Generate the styled base and definitions as strings
-    let (defs_str, base_rect_str) = style_generator::create_styled_base_str(styles, style_preset);
+    let (defs_str, base_rect_str) = style_generator::try_create_styled_base_str(styles, style_preset)?;
+
+    // 3. Draw the overlays on top, cutting their rings out of the icon, and
+    //    the interaction state over everything
+    let overlays = overlay::overlay_markup(styles)?;
+    let icon = overlays.masked(&format!("{}{}", base_rect_str, animated.foreground));
+    let content = states::state_markup(styles, format!("{}{}", icon, overlays.shapes));
+    let content = animation::animation_root(styles, content);
 
-    // 3. Manually assemble the final SVG string
+    // 4. Manually assemble the final SVG string
     let final_svg = format!(
-        r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg">{defs}{base_rect}<g transform="{transform}"><path d="{path_data}" fill="{icon_color}"/></g></svg>"#,
+        r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg">{style}{defs}{mask}{content}</svg>"#,
         width = styles.width,
         height = styles.height,
+        style = animated.style,
         defs = defs_str,
-        base_rect = base_rect_str,
-        transform = transform,
-        path_data = icon.path_data,
-        icon_color = styles.icon_color
+        mask = overlays.mask.as_deref().unwrap_or_default(),
+        content = content
     );
 
     Ok(final_svg)
 }
 
+/// The base and the foreground of an icon as two standalone SVGs, for
+/// platforms that composite the layers themselves (e.g. Android adaptive icons).
+#[derive(Debug, Clone)]
+pub struct IconLayers {
+    pub background: String,
+    pub foreground: String,
+}
+
+/// Generates the styled base and the foreground glyph as separate SVGs of the
+/// same canvas size. Overlays are drawn in the foreground, and their cutout
+/// rings are cut out of both layers.
+pub fn generate_icon_layers(
+    icon_data: &str,
+    style_preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<IconLayers, IconEngineError> {
+    let icon = svg_processor::parse_svg(icon_data)?;
+    let (defs_str, base_rect_str) = style_generator::try_create_styled_base_str(styles, style_preset)?;
+    let icon_color = contrast::resolve_icon_color(styles, style_preset);
+    let foreground = foreground_markup(&icon, styles, &icon_color)?;
+    let overlays = overlay::overlay_markup(styles)?;
+    let mask = overlays.mask.as_deref().unwrap_or_default();
+
+    let open_tag = format!(
+        r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg">"#,
+        width = styles.width,
+        height = styles.height,
+    );
+
+    Ok(IconLayers {
+        background: format!("{}{}{}{}</svg>", open_tag, defs_str, mask, overlays.masked(&base_rect_str)),
+        foreground: format!("{}{}{}{}</svg>", open_tag, mask, overlays.masked(&foreground), overlays.shapes),
+    })
+}
+
+/// Returns the glyph path, either in a group applying the fit transform or
+/// with the fit baked into its coordinates, and any stacked glyph layers.
+fn foreground_markup(
+    icon: &svg_processor::SvgIcon,
+    styles: &CustomStyles,
+    icon_color: &str,
+) -> Result<String, IconEngineError> {
+    if !styles.glyph_layers.is_empty() {
+        return glyph_stack::stack_markup(icon, styles, icon_color);
+    }
+    if styles.bake_transform {
+        return Ok(format!(
+            r#"<path class="icon-fg" d="{}" fill="{}"/>"#,
+            svg_processor::fitted_path_data(icon, styles)?,
+            icon_color
+        ));
+    }
+    Ok(format!(
+        r#"<g transform="{}"><path class="icon-fg" d="{}" fill="{}"/></g>"#,
+        svg_processor::placement_transform(icon, styles)?,
+        icon.path_data,
+        icon_color
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -116,6 +466,80 @@ mod tests {
     }
 
     #[test]
+    fn test_neumorphism_without_shadows() {
+        let styles = CustomStyles {
+            shadows: false,
+            ..CustomStyles::default()
+        };
+        let svg_output = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+
+        assert!(!svg_output.contains("neumorphism-shadow"));
+        assert!(svg_output.contains("fill=\"#e0e0e0\""));
+    }
+
+    #[test]
+    fn test_layer_generation() {
+        let styles = CustomStyles::default();
+        let layers = generate_icon_layers(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+
+        assert!(layers.background.contains("class=\"icon-base\""));
+        assert!(!layers.background.contains("<path"));
+        assert!(layers.foreground.contains("d=\"M12 2L2 22h20L12 2z\""));
+        assert!(!layers.foreground.contains("<rect"));
+
+        let styles = CustomStyles {
+            overlays: vec![overlay::Overlay {
+                shape: overlay::OverlayShape::Dot,
+                color: "#d32f2f".to_string(),
+                anchor: Alignment::TopRight,
+                size: 24.0,
+                cutout: Some(4.0),
+            }],
+            ..CustomStyles::default()
+        };
+        let layers = generate_icon_layers(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+        assert!(layers.background.contains("mask=\"url(#overlay-cutout)\""));
+        assert!(!layers.background.contains("class=\"overlay\""));
+        assert!(layers.foreground.contains("mask=\"url(#overlay-cutout)\""));
+        assert!(layers.foreground.contains("<g class=\"overlay\"><circle"));
+    }
+
+    #[test]
+    fn test_baked_transform() {
+        let styles = CustomStyles {
+            bake_transform: true,
+            ..CustomStyles::default()
+        };
+        let svg_output = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+
+        assert!(!svg_output.contains("<g"));
+        assert!(svg_output.contains(r#"<path class="icon-fg" d="M64 24L24 104L104 104L64 24Z""#));
+    }
+
+    #[test]
+    fn test_content_box() {
+        assert_eq!(CustomStyles::default().content_box(), Rect::new(16.0, 16.0, 112.0, 112.0));
+
+        let insets = Insets::parse("10% 8").unwrap();
+        assert_eq!(insets.top, Length::Percent(10.0));
+        assert_eq!(insets.left, Length::Px(8.0));
+        let styles = CustomStyles { insets: Some(insets), ..CustomStyles::default() };
+        assert_eq!(styles.content_box(), Rect::new(8.0, 12.8, 120.0, 115.2));
+        for invalid in ["", "1 2 3 4 5", "-4", "ten"] {
+            assert!(Insets::parse(invalid).is_err(), "{}", invalid);
+        }
+
+        // Safe zones take precedence over insets and scale with the canvas.
+        let tile = CustomStyles { width: 200, height: 100, safe_zone: Some(SafeZone::WindowsTile), ..styles };
+        assert_eq!(tile.content_box(), Rect::new(50.0, 20.0, 150.0, 70.0));
+        let adaptive = CustomStyles { width: 108, height: 108, safe_zone: Some(SafeZone::AndroidAdaptive), ..CustomStyles::default() };
+        let content = adaptive.content_box();
+        assert!((content.width() - 66.0 / std::f32::consts::SQRT_2).abs() < 1e-3);
+        assert!((content.center().x - 54.0).abs() < 1e-3);
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_gradient_generation() {
         let mut styles = CustomStyles::default();
         styles.gradient = Some(Gradient {
@@ -0,0 +1,164 @@
+use ttf_parser::{Face, OutlineBuilder};
+
+use crate::path_data::{format_number, to_path_data, PathSegment, Point};
+use crate::{generate_icon, CustomStyles, IconEngineError, StylePreset};
+
+/// The most characters a monogram can hold.
+pub const MAX_MONOGRAM_LETTERS: usize = 3;
+
+/// Collects a glyph's outline as SVG path segments, flipping font units
+/// (y up from the baseline) into SVG units and shifting by the pen position.
+struct PathCollector {
+    segments: Vec<PathSegment>,
+    pen_x: f32,
+}
+
+impl PathCollector {
+    fn point(&self, x: f32, y: f32) -> Point {
+        Point::new(self.pen_x + x, -y)
+    }
+}
+
+impl OutlineBuilder for PathCollector {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.segments.push(PathSegment::MoveTo(self.point(x, y)));
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.segments.push(PathSegment::LineTo(self.point(x, y)));
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.segments.push(PathSegment::QuadTo(self.point(x1, y1), self.point(x, y)));
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.segments.push(PathSegment::CubicTo(self.point(x1, y1), self.point(x2, y2), self.point(x, y)));
+    }
+
+    fn close(&mut self) {
+        self.segments.push(PathSegment::ClosePath);
+    }
+}
+
+/// Sets one to three letters in a TrueType or OpenType font and returns them
+/// as a source SVG: a single path in font units, advancing by each glyph's
+/// horizontal metrics (without kerning).
+///
+/// The viewBox spans the letters' ink horizontally and the font's cap height
+/// down to the baseline vertically, grown to fit ascenders and descenders,
+/// so a fit into the padding box sizes the letters automatically while
+/// monograms in the same font share a baseline and cap height.
+pub fn monogram_svg(text: &str, font_data: &[u8]) -> Result<String, IconEngineError> {
+    let text = text.trim();
+    let letters = text.chars().count();
+    if letters == 0 || letters > MAX_MONOGRAM_LETTERS {
+        return Err(IconEngineError::InvalidInput(format!(
+            "Monograms take 1 to {} letters, got {:?}",
+            MAX_MONOGRAM_LETTERS, text
+        )));
+    }
+    let face = Face::parse(font_data, 0)
+        .map_err(|e| IconEngineError::InvalidInput(format!("Invalid font file: {}", e)))?;
+
+    let mut collector = PathCollector { segments: Vec::new(), pen_x: 0.0 };
+    let mut ink: Option<(f32, f32, f32, f32)> = None;
+    for letter in text.chars() {
+        let glyph = face
+            .glyph_index(letter)
+            .ok_or_else(|| IconEngineError::InvalidInput(format!("The font has no glyph for {:?}", letter)))?;
+        if let Some(bbox) = face.outline_glyph(glyph, &mut collector) {
+            let (x_min, x_max) = (collector.pen_x + bbox.x_min as f32, collector.pen_x + bbox.x_max as f32);
+            let (y_min, y_max) = (-(bbox.y_max as f32), -(bbox.y_min as f32));
+            ink = Some(match ink {
+                Some((a, b, c, d)) => (a.min(x_min), b.min(y_min), c.max(x_max), d.max(y_max)),
+                None => (x_min, y_min, x_max, y_max),
+            });
+        }
+        collector.pen_x += face.glyph_hor_advance(glyph).unwrap_or(0) as f32;
+    }
+    let (min_x, ink_top, max_x, ink_bottom) = ink.ok_or_else(|| {
+        IconEngineError::InvalidInput(format!("The font has no outlines for {:?}", text))
+    })?;
+
+    let cap_height = face.capital_height().unwrap_or_else(|| face.ascender()) as f32;
+    let top = ink_top.min(-cap_height);
+    let bottom = ink_bottom.max(0.0);
+    // Shift the letters so the viewBox starts at the origin, as `SvgIcon` ignores min-x/min-y.
+    for segment in &mut collector.segments {
+        let shift = |p: &mut Point| {
+            p.x -= min_x;
+            p.y -= top;
+        };
+        match segment {
+            PathSegment::MoveTo(p) | PathSegment::LineTo(p) => shift(p),
+            PathSegment::QuadTo(c, p) => {
+                shift(c);
+                shift(p);
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                shift(c1);
+                shift(c2);
+                shift(p);
+            }
+            PathSegment::ArcTo { to, .. } => shift(to),
+            PathSegment::ClosePath => {}
+        }
+    }
+
+    Ok(format!(
+        r#"<svg viewBox="0 0 {} {}" xmlns="http://www.w3.org/2000/svg"><path d="{}"/></svg>"#,
+        format_number(max_x - min_x),
+        format_number(bottom - top),
+        to_path_data(&collector.segments)
+    ))
+}
+
+/// Generates a styled icon from one to three letters, like
+/// [`generate_icon`] does for a source SVG.
+pub fn generate_monogram(
+    text: &str,
+    font_data: &[u8],
+    style_preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<String, IconEngineError> {
+    generate_icon(&monogram_svg(text, font_data)?, style_preset, styles)
+}
+
+#[cfg(all(test, feature = "font"))]
+mod tests {
+    use super::*;
+    use crate::font::{build_ttf, FontGlyph};
+
+    /// A font whose first two Private Use Area glyphs are a 20x20 square and
+    /// a 10-unit-wide bar sitting on the baseline.
+    fn test_font() -> Vec<u8> {
+        let glyphs = [
+            FontGlyph::from_svg("square", 0xE000, r#"<svg viewBox="0 0 20 20"><path d="M0 0H20V20H0Z"/></svg>"#),
+            FontGlyph::from_svg("bar", 0xE001, r#"<svg viewBox="0 0 20 20"><path d="M5 0H15V20H5Z"/></svg>"#),
+        ];
+        build_ttf("Test", &glyphs.into_iter().collect::<Result<Vec<_>, _>>().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn sets_letters_side_by_side() {
+        let svg = monogram_svg("\u{E000}\u{E001}", &test_font()).unwrap();
+        let icon = crate::svg_processor::parse_svg(&svg).unwrap();
+        // The square fills the 1000-unit em; the bar starts 250 units into the next one.
+        assert_eq!((icon.viewbox.width, icon.viewbox.height), (1750.0, 1000.0));
+        assert!(icon.path_data.starts_with("M0 "), "{}", icon.path_data);
+
+        let styled = generate_monogram("\u{E000}", &test_font(), StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        assert!(styled.contains("translate(16, 16) scale(0.096)"));
+    }
+
+    #[test]
+    fn rejects_unusable_text() {
+        let font = test_font();
+        for text in ["", "ABCD"] {
+            assert!(matches!(monogram_svg(text, &font), Err(IconEngineError::InvalidInput(_))));
+        }
+        assert!(monogram_svg("A", &font).unwrap_err().to_string().contains("no glyph"));
+        assert!(monogram_svg("A", b"not a font").is_err());
+    }
+}
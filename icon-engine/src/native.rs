@@ -0,0 +1,442 @@
+use crate::color::{parse_with_alpha, Rgb};
+use crate::animation::{AnimationKind, AnimationSyntax};
+use crate::path_data::{self, format_number, rounded_rect, to_path_data};
+use crate::states::{IconState, DISABLED_OPACITY};
+use crate::style_generator::{base_fill, base_fill_opacity, gradient_points, GLASS_STROKE, GLASS_STROKE_WIDTH};
+use crate::{contrast, glyph_stack, overlay, svg_processor, CustomStyles, Gradient, IconEngineError, StylePreset};
+
+/// An icon converted to a platform's vector format.
+#[derive(Debug, Clone)]
+pub struct NativeIcon {
+    pub source: String,
+    /// The effects of the SVG that the format cannot express, e.g.
+    /// `"neumorphism drop shadows (feDropShadow)"`.
+    pub dropped_effects: Vec<String>,
+}
+
+/// Lists the effects applied by `generate_icon` that the converters leave
+/// out: SVG filters, patterns and animations, which neither vector drawables
+/// nor XAML drawings support, and the focus ring and fade of
+/// `CustomStyles::state`.
+fn dropped_effects(preset: StylePreset, styles: &CustomStyles) -> Vec<String> {
+    let mut dropped = match preset {
+        StylePreset::Neumorphism if styles.shadows && styles.state == IconState::Pressed => {
+            vec!["pressed inset shadows (feComposite)".to_string()]
+        }
+        StylePreset::Neumorphism if styles.shadows => {
+            vec!["neumorphism drop shadows (feDropShadow)".to_string()]
+        }
+        _ => Vec::new(),
+    };
+    if styles.pattern.is_some() {
+        dropped.push("base pattern (<pattern>/feTurbulence)".to_string());
+    }
+    if let Some(animation) = styles.animation {
+        // Breathing is always written in SMIL.
+        let smil = animation.syntax == AnimationSyntax::Smil || animation.kind == AnimationKind::Breathe;
+        dropped.push(if smil { "animation (SMIL)" } else { "animation (CSS keyframes)" }.to_string());
+    }
+    match styles.state {
+        IconState::Focused => dropped.push("focus ring".to_string()),
+        IconState::Disabled => dropped.push(format!("disabled fade (opacity {})", format_number(DISABLED_OPACITY))),
+        _ => {}
+    }
+    dropped
+}
+
+fn parse_color(color: &str, target: &str) -> Result<(Rgb, f32), IconEngineError> {
+    parse_with_alpha(color)
+        .ok_or_else(|| IconEngineError::InvalidInput(format!("Unsupported color for {}: {}", target, color)))
+}
+
+fn argb_hex(rgb: Rgb, alpha: f32) -> String {
+    format!("#{:02X}{:02X}{:02X}{:02X}", (alpha * 255.0).round() as u8, rgb.r, rgb.g, rgb.b)
+}
+
+/// Formats a color as Android's `#RRGGBB`, or `#AARRGGBB` when translucent.
+pub fn android_color(color: &str) -> Result<String, IconEngineError> {
+    let (rgb, alpha) = parse_color(color, "Android")?;
+    Ok(if alpha < 1.0 {
+        argb_hex(rgb, alpha)
+    } else {
+        format!("#{:02X}{:02X}{:02X}", rgb.r, rgb.g, rgb.b)
+    })
+}
+
+/// Formats a color as XAML's `#AARRGGBB`.
+pub fn xaml_color(color: &str) -> Result<String, IconEngineError> {
+    let (rgb, alpha) = parse_color(color, "XAML")?;
+    Ok(argb_hex(rgb, alpha))
+}
+
+/// Formats one XML element with an attribute per line, the layout Android
+/// Studio and Visual Studio use for resources.
+fn xml_element(indent: usize, tag: &str, attributes: &[(&str, String)], children: Option<&str>) -> String {
+    let pad = " ".repeat(indent);
+    let mut out = format!("{}<{}", pad, tag);
+    for (name, value) in attributes {
+        out.push_str(&format!("\n{}    {}=\"{}\"", pad, name, value));
+    }
+    match children {
+        Some(children) => out.push_str(&format!(">\n{}{}</{}>\n", children, pad, tag)),
+        None => out.push_str(" />\n"),
+    }
+    out
+}
+
+/// Converts an icon to an Android `<vector>` drawable of the canvas size in
+/// dp: the base as a rounded-rectangle path and the glyph in a group that
+/// applies the same fit as [`crate::generate_icon`], or with the fit baked
+/// into its path when `CustomStyles::bake_transform` is set.
+///
+/// Gradients become `aapt:attr` gradient fills, which need API 24. Overlays
+/// and glyph layers are not supported.
+pub fn to_vector_drawable(
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<NativeIcon, IconEngineError> {
+    overlay::reject_overlays(styles, "VectorDrawable")?;
+    glyph_stack::reject_glyph_layers(styles, "VectorDrawable")?;
+    let icon = svg_processor::parse_svg(icon_data)?;
+    let placement = svg_processor::glyph_placement(&icon, styles);
+    let (width, height) = (styles.width as f32, styles.height as f32);
+
+    let mut base = vec![
+        ("android:name", "base".to_string()),
+        ("android:pathData", to_path_data(&rounded_rect(width, height, styles.corner_radius))),
+    ];
+    if styles.gradient.is_none() {
+        base.push(("android:fillColor", android_color(&base_fill(styles, preset))?));
+    }
+    if preset != StylePreset::Neumorphism {
+        base.push(("android:fillAlpha", format_number(base_fill_opacity(preset))));
+        base.push(("android:strokeColor", android_color(GLASS_STROKE)?));
+        base.push(("android:strokeWidth", format_number(GLASS_STROKE_WIDTH)));
+    }
+    let base_gradient = match &styles.gradient {
+        Some(gradient) => {
+            let ((x1, y1), (x2, y2)) = gradient_points(gradient);
+            let fill = xml_element(
+                12,
+                "gradient",
+                &[
+                    ("android:type", "linear".to_string()),
+                    ("android:startX", format_number(x1 * width)),
+                    ("android:startY", format_number(y1 * height)),
+                    ("android:endX", format_number(x2 * width)),
+                    ("android:endY", format_number(y2 * height)),
+                    ("android:startColor", android_color(&gradient.start_color)?),
+                    ("android:endColor", android_color(&gradient.stop_color)?),
+                ],
+                None,
+            );
+            Some(xml_element(8, "aapt:attr", &[("name", "android:fillColor".to_string())], Some(&fill)))
+        }
+        None => None,
+    };
+
+    let glyph = |indent: usize, path_data: String| -> Result<String, IconEngineError> {
+        Ok(xml_element(
+            indent,
+            "path",
+            &[
+                ("android:name", "icon_fg".to_string()),
+                ("android:pathData", path_data),
+                ("android:fillColor", android_color(&contrast::resolve_icon_color(styles, preset))?),
+            ],
+            None,
+        ))
+    };
+    let group = if styles.bake_transform {
+        glyph(4, svg_processor::fitted_path_data(&icon, styles)?)?
+    } else {
+        // Groups scale, then rotate, then translate, like `GlyphPlacement`.
+        let mut attributes = vec![
+            ("android:name", "glyph".to_string()),
+            ("android:translateX", format_number(placement.tx)),
+            ("android:translateY", format_number(placement.ty)),
+            ("android:scaleX", format_number(placement.scale_x)),
+            ("android:scaleY", format_number(placement.scale_y)),
+        ];
+        if placement.rotation != 0.0 {
+            attributes.push(("android:rotation", format_number(placement.rotation)));
+        }
+        xml_element(4, "group", &attributes, Some(&glyph(8, normalized_path_data(&icon)?)?))
+    };
+
+    let mut root = vec![("xmlns:android", "http://schemas.android.com/apk/res/android".to_string())];
+    if base_gradient.is_some() {
+        root.push(("xmlns:aapt", "http://schemas.android.com/aapt".to_string()));
+    }
+    root.extend([
+        ("android:width", format!("{}dp", styles.width)),
+        ("android:height", format!("{}dp", styles.height)),
+        ("android:viewportWidth", styles.width.to_string()),
+        ("android:viewportHeight", styles.height.to_string()),
+    ]);
+
+    let children = format!("{}{}", xml_element(4, "path", &base, base_gradient.as_deref()), group);
+    Ok(NativeIcon {
+        source: format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n{}",
+            xml_element(0, "vector", &root, Some(&children))
+        ),
+        dropped_effects: dropped_effects(preset, styles),
+    })
+}
+
+/// Returns the glyph's path data re-serialized with absolute commands and
+/// plain numbers, normalizing compact SVG syntax (`.5.5`, implicit commands)
+/// that XAML cannot read and older Android versions misparse.
+fn normalized_path_data(icon: &svg_processor::SvgIcon) -> Result<String, IconEngineError> {
+    Ok(to_path_data(&path_data::parse_path(&icon.path_data)?))
+}
+
+/// Builds a XAML brush for the base, translucent for glass presets.
+fn xaml_brush(indent: usize, fill: &str, gradient: Option<&Gradient>, opacity: f32) -> Result<String, IconEngineError> {
+    let mut attributes = Vec::new();
+    if opacity < 1.0 {
+        attributes.push(("Opacity", format_number(opacity)));
+    }
+    match gradient {
+        Some(gradient) => {
+            let ((x1, y1), (x2, y2)) = gradient_points(gradient);
+            attributes.push(("StartPoint", format!("{},{}", format_number(x1), format_number(y1))));
+            attributes.push(("EndPoint", format!("{},{}", format_number(x2), format_number(y2))));
+            let stops = [(&gradient.start_color, "0"), (&gradient.stop_color, "1")]
+                .iter()
+                .map(|(color, offset)| {
+                    Ok(xml_element(
+                        indent + 4,
+                        "GradientStop",
+                        &[("Color", xaml_color(color)?), ("Offset", offset.to_string())],
+                        None,
+                    ))
+                })
+                .collect::<Result<String, IconEngineError>>()?;
+            Ok(xml_element(indent, "LinearGradientBrush", &attributes, Some(&stops)))
+        }
+        None => {
+            attributes.insert(0, ("Color", xaml_color(fill)?));
+            Ok(xml_element(indent, "SolidColorBrush", &attributes, None))
+        }
+    }
+}
+
+/// Converts an icon to a XAML `ResourceDictionary` with two resources:
+///
+/// - `{key}Geometry`, a `PathGeometry` of the glyph in its own viewBox, for
+///   `<Path Data="{StaticResource ...}"/>`;
+/// - `{key}Image`, a `DrawingImage` of the whole icon, for `<Image>`.
+///
+/// `DrawingImage` is WPF-only; WinUI apps can use the geometry's path markup.
+/// Overlays and glyph layers are not supported.
+pub fn to_xaml(
+    key: &str,
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+) -> Result<NativeIcon, IconEngineError> {
+    overlay::reject_overlays(styles, "XAML")?;
+    glyph_stack::reject_glyph_layers(styles, "XAML")?;
+    let icon = svg_processor::parse_svg(icon_data)?;
+    let fit = svg_processor::placement_matrix(&icon, styles)?;
+    let figures = normalized_path_data(&icon)?;
+
+    let geometry = xml_element(
+        4,
+        "PathGeometry",
+        &[
+            ("x:Key", format!("{}Geometry", key)),
+            ("FillRule", "Nonzero".to_string()),
+            ("Figures", figures),
+        ],
+        None,
+    );
+
+    let rect = xml_element(
+        24,
+        "RectangleGeometry",
+        &[
+            ("Rect", format!("0,0,{},{}", styles.width, styles.height)),
+            ("RadiusX", format_number(styles.corner_radius)),
+            ("RadiusY", format_number(styles.corner_radius)),
+        ],
+        None,
+    );
+    let mut base_children = xml_element(20, "GeometryDrawing.Geometry", &[], Some(&rect));
+    let brush = xaml_brush(
+        24,
+        &base_fill(styles, preset),
+        styles.gradient.as_ref(),
+        base_fill_opacity(preset),
+    )?;
+    base_children.push_str(&xml_element(20, "GeometryDrawing.Brush", &[], Some(&brush)));
+    if preset != StylePreset::Neumorphism {
+        let pen = xml_element(
+            24,
+            "Pen",
+            &[("Brush", xaml_color(GLASS_STROKE)?), ("Thickness", format_number(GLASS_STROKE_WIDTH))],
+            None,
+        );
+        base_children.push_str(&xml_element(20, "GeometryDrawing.Pen", &[], Some(&pen)));
+    }
+    let base = xml_element(16, "GeometryDrawing", &[], Some(&base_children));
+
+    let glyph = xml_element(
+        20,
+        "GeometryDrawing",
+        &[
+            ("Brush", xaml_color(&contrast::resolve_icon_color(styles, preset))?),
+            ("Geometry", format!("{{StaticResource {}Geometry}}", key)),
+        ],
+        None,
+    );
+    let glyph_group = xml_element(
+        16,
+        "DrawingGroup",
+        &[(
+            "Transform",
+            [fit.a, fit.b, fit.c, fit.d, fit.e, fit.f].map(format_number).join(","),
+        )],
+        Some(&glyph),
+    );
+
+    let group = xml_element(12, "DrawingGroup", &[], Some(&format!("{}{}", base, glyph_group)));
+    let drawing = xml_element(8, "DrawingImage.Drawing", &[], Some(&group));
+    let image = xml_element(4, "DrawingImage", &[("x:Key", format!("{}Image", key))], Some(&drawing));
+
+    Ok(NativeIcon {
+        source: xml_element(
+            0,
+            "ResourceDictionary",
+            &[
+                ("xmlns", "http://schemas.microsoft.com/winfx/2006/xaml/presentation".to_string()),
+                ("xmlns:x", "http://schemas.microsoft.com/winfx/2006/xaml".to_string()),
+            ],
+            Some(&format!("{}{}", geometry, image)),
+        ),
+        dropped_effects: dropped_effects(preset, styles),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::Animation;
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    fn gradient_styles() -> CustomStyles {
+        CustomStyles {
+            gradient: Some(Gradient {
+                angle: 90,
+                start_color: "#ff0000".to_string(),
+                stop_color: "#0000ff".to_string(),
+            }),
+            ..CustomStyles::default()
+        }
+    }
+
+    #[test]
+    fn colors_put_alpha_first() {
+        assert_eq!(android_color("#e0e0e0").unwrap(), "#E0E0E0");
+        assert_eq!(android_color("rgba(255,255,255,0.3)").unwrap(), "#4DFFFFFF");
+        assert_eq!(xaml_color("#e0e0e0").unwrap(), "#FFE0E0E0");
+        assert!(android_color("url(#x)").is_err());
+    }
+
+    #[test]
+    fn vector_drawable_positions_the_glyph() {
+        let icon = to_vector_drawable(TEST_SVG, StylePreset::Glassmorphism, &CustomStyles::default()).unwrap();
+        let xml = icon.source;
+        assert!(roxmltree::Document::parse(&xml).is_ok());
+        assert!(xml.contains(r#"android:viewportWidth="128""#));
+        assert!(xml.contains(r#"android:pathData="M25 0L103 0A25 25 0 0 1 128 25"#));
+        assert!(xml.contains(r#"android:fillAlpha="0.2""#));
+        assert!(xml.contains(r#"android:translateX="16""#));
+        assert!(xml.contains(r#"android:scaleX="4""#));
+        assert!(xml.contains(r#"android:pathData="M12 2L2 22L22 22L12 2Z""#));
+        assert!(icon.dropped_effects.is_empty());
+
+        let baked = CustomStyles { bake_transform: true, ..CustomStyles::default() };
+        let xml = to_vector_drawable(TEST_SVG, StylePreset::Glassmorphism, &baked).unwrap().source;
+        assert!(!xml.contains("<group"));
+        assert!(xml.contains(r#"android:pathData="M64 24L24 104L104 104L64 24Z""#));
+    }
+
+    #[test]
+    fn overlays_and_glyph_layers_are_rejected() {
+        let styles = CustomStyles {
+            overlays: vec![crate::overlay::Overlay {
+                shape: crate::overlay::OverlayShape::Dot,
+                color: "#d32f2f".to_string(),
+                anchor: crate::Alignment::TopRight,
+                size: 24.0,
+                cutout: None,
+            }],
+            ..CustomStyles::default()
+        };
+        assert!(to_vector_drawable(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+        assert!(to_xaml("Icon", TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+
+        let styles = CustomStyles {
+            glyph_layers: vec![crate::glyph_stack::GlyphLayer::new(TEST_SVG)],
+            ..CustomStyles::default()
+        };
+        assert!(to_vector_drawable(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+        assert!(to_xaml("Icon", TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+    }
+
+    #[test]
+    fn vector_drawable_maps_gradients_and_reports_shadows() {
+        let icon = to_vector_drawable(TEST_SVG, StylePreset::Neumorphism, &gradient_styles()).unwrap();
+        assert!(roxmltree::Document::parse(&icon.source).is_ok());
+        assert!(icon.source.contains(r#"xmlns:aapt="http://schemas.android.com/aapt""#));
+        assert!(icon.source.contains(r#"android:startX="0""#));
+        assert!(icon.source.contains(r#"android:endX="128""#));
+        assert!(icon.source.contains(r##"android:endColor="#0000FF""##));
+        assert_eq!(icon.dropped_effects, vec!["neumorphism drop shadows (feDropShadow)"]);
+    }
+
+    #[test]
+    fn xaml_has_geometry_and_drawing_resources() {
+        let icon = to_xaml("HomeIcon", TEST_SVG, StylePreset::Neumorphism, &gradient_styles()).unwrap();
+        let doc = roxmltree::Document::parse(&icon.source).unwrap();
+        let keys: Vec<&str> = doc
+            .root_element()
+            .children()
+            .filter_map(|node| node.attribute(("http://schemas.microsoft.com/winfx/2006/xaml", "Key")))
+            .collect();
+        assert_eq!(keys, ["HomeIconGeometry", "HomeIconImage"]);
+
+        assert!(icon.source.contains(r#"Figures="M12 2L2 22L22 22L12 2Z""#));
+        assert!(icon.source.contains(r#"StartPoint="0,0.5""#));
+        assert!(icon.source.contains(r#"Transform="4,0,0,4,16,16""#));
+        assert!(icon.source.contains(r#"Geometry="{StaticResource HomeIconGeometry}""#));
+        assert_eq!(icon.dropped_effects.len(), 1);
+    }
+
+    #[test]
+    fn states_report_what_they_lose() {
+        let dropped = |state| {
+            let styles = CustomStyles { state, ..CustomStyles::default() };
+            to_vector_drawable(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap().dropped_effects
+        };
+        assert_eq!(dropped(IconState::Pressed), vec!["pressed inset shadows (feComposite)"]);
+        assert_eq!(dropped(IconState::Focused)[1], "focus ring");
+        assert_eq!(dropped(IconState::Disabled)[1], "disabled fade (opacity 0.4)");
+        assert_eq!(dropped(IconState::Hover), dropped(IconState::Normal));
+    }
+
+    #[test]
+    fn animations_are_reported() {
+        let dropped = |animation| {
+            let styles = CustomStyles { animation: Some(animation), shadows: false, ..CustomStyles::default() };
+            to_xaml("Icon", TEST_SVG, StylePreset::Neumorphism, &styles).unwrap().dropped_effects
+        };
+        assert_eq!(dropped(Animation::new(AnimationKind::Spin)), vec!["animation (CSS keyframes)"]);
+        assert_eq!(dropped(Animation::new(AnimationKind::Breathe)), vec!["animation (SMIL)"]);
+    }
+}
@@ -0,0 +1,358 @@
+use std::collections::HashSet;
+
+use roxmltree::{Document, Node, NodeId};
+
+use crate::path_data::{compact_number, minify_path_data, parse_path, parse_transform_list};
+use crate::IconEngineError;
+
+/// Attributes whose values are plain numbers, lists or percentages.
+const NUMERIC_ATTRIBUTES: &[&str] = &[
+    "x", "y", "x1", "y1", "x2", "y2", "cx", "cy", "r", "rx", "ry", "fx", "fy", "width", "height", "dx", "dy",
+    "stdDeviation", "stroke-width", "opacity", "fill-opacity", "stroke-opacity", "stop-opacity", "viewBox",
+];
+
+const COLOR_ATTRIBUTES: &[&str] = &["fill", "stroke", "stop-color", "flood-color", "color"];
+
+const TRANSFORM_ATTRIBUTES: &[&str] = &["transform", "gradientTransform", "patternTransform"];
+
+/// Inherited presentation attributes that can be dropped when they restate
+/// the initial value and no ancestor overrides it.
+const PRESENTATION_DEFAULTS: &[(&str, &str)] = &[
+    ("opacity", "1"),
+    ("fill-opacity", "1"),
+    ("stroke-opacity", "1"),
+    ("stop-opacity", "1"),
+    ("stroke-width", "1"),
+];
+
+/// How aggressively [`optimize`] rewrites an SVG.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum OptimizeLevel {
+    /// Leaves the SVG untouched.
+    #[default]
+    Off,
+    /// Rounds numbers to three decimals, which is invisible at any practical size.
+    Safe,
+    /// Rounds numbers to two decimals.
+    Aggressive,
+}
+
+impl OptimizeLevel {
+    fn precision(self) -> Option<usize> {
+        match self {
+            OptimizeLevel::Off => None,
+            OptimizeLevel::Safe => Some(3),
+            OptimizeLevel::Aggressive => Some(2),
+        }
+    }
+}
+
+/// Shrinks an SVG without changing how it renders.
+///
+/// Numbers are rounded to the level's precision, path data is minified,
+/// definitions nothing references (such as the glass presets' blur filter)
+/// are removed, attributes restating their defaults are dropped and colors
+/// are shortened. Ids and the generator's class hooks are kept, so themes
+/// and sprites work on optimized output. Comments and insignificant
+/// whitespace are not copied.
+pub fn optimize(svg: &str, level: OptimizeLevel) -> Result<String, IconEngineError> {
+    let Some(precision) = level.precision() else {
+        return Ok(svg.to_string());
+    };
+    let doc = Document::parse(svg).map_err(|e| IconEngineError::SvgParsingError(e.to_string()))?;
+    let writer = Writer { precision, removed: dead_definitions(&doc) };
+    let mut out = String::with_capacity(svg.len());
+    writer.write_element(doc.root_element(), &mut out);
+    Ok(out)
+}
+
+/// Collects the ids referenced through `url(#id)` or `href="#id"` by the
+/// given node.
+fn collect_references(node: Node, referenced: &mut HashSet<String>) {
+    let values = node.attributes().map(|attr| attr.value()).chain(node.text().filter(|_| node.is_text()));
+    for value in values {
+        for part in value.split("url(#").skip(1) {
+            if let Some(end) = part.find(')') {
+                referenced.insert(part[..end].trim_end_matches(['"', '\'']).to_string());
+            }
+        }
+    }
+    for attr in node.attributes().filter(|attr| attr.name() == "href") {
+        if let Some(id) = attr.value().strip_prefix('#') {
+            referenced.insert(id.to_string());
+        }
+    }
+}
+
+/// Returns the children of `<defs>` that nothing references, repeating until
+/// definitions only referenced by other dead definitions are found too.
+fn dead_definitions(doc: &Document) -> HashSet<NodeId> {
+    let mut removed = HashSet::new();
+    loop {
+        let mut referenced = HashSet::new();
+        for node in doc.descendants() {
+            if !node.ancestors().any(|a| removed.contains(&a.id())) {
+                collect_references(node, &mut referenced);
+            }
+        }
+
+        let dead: Vec<NodeId> = doc
+            .descendants()
+            .filter(|n| n.parent().is_some_and(|p| p.has_tag_name("defs")))
+            .filter(|n| !removed.contains(&n.id()))
+            .filter(|n| n.attribute("id").is_some_and(|id| !referenced.contains(id)))
+            .map(|n| n.id())
+            .collect();
+        if dead.is_empty() {
+            return removed;
+        }
+        removed.extend(dead);
+    }
+}
+
+/// Rounds every number in an attribute value, keeping units and separators.
+fn round_numbers(value: &str, precision: usize) -> String {
+    let bytes = value.as_bytes();
+    let mut out = String::with_capacity(value.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let starts_number = bytes[i].is_ascii_digit()
+            || (matches!(bytes[i], b'-' | b'+' | b'.') && bytes.get(i + 1).is_some_and(|b| b.is_ascii_digit() || *b == b'.'));
+        if !starts_number || (i > 0 && bytes[i - 1].is_ascii_alphabetic()) {
+            out.push(bytes[i] as char);
+            i += 1;
+            continue;
+        }
+        let mut end = i + 1;
+        while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.') {
+            end += 1;
+        }
+        if end < bytes.len() && matches!(bytes[end], b'e' | b'E') {
+            let exponent = end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'-' | b'+')));
+            if bytes.get(exponent).is_some_and(u8::is_ascii_digit) {
+                end = exponent;
+                while end < bytes.len() && bytes[end].is_ascii_digit() {
+                    end += 1;
+                }
+            }
+        }
+        match value[i..end].parse::<f32>() {
+            Ok(number) => out.push_str(&compact_number(number, precision)),
+            Err(_) => out.push_str(&value[i..end]),
+        }
+        i = end;
+    }
+    out
+}
+
+/// Rewrites a transform list with rounded arguments, dropping identity
+/// transforms and redundant arguments. Unparseable lists are kept as-is.
+fn normalize_transform(value: &str, precision: usize) -> String {
+    let Some(list) = parse_transform_list(value) else {
+        return value.to_string();
+    };
+    let mut transforms = Vec::new();
+    for (name, mut args) in list {
+        let factor = 10f32.powi(precision as i32);
+        args.iter_mut().for_each(|v| *v = (*v * factor).round() / factor);
+
+        let identity = match name {
+            "translate" | "rotate" | "skewX" | "skewY" => args.first().is_some_and(|v| *v == 0.0) && args.get(1).is_none_or(|v| *v == 0.0),
+            "scale" => args.iter().all(|v| *v == 1.0),
+            "matrix" => args == [1.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            _ => false,
+        };
+        if identity {
+            continue;
+        }
+        // `translate(x 0)` and `scale(s s)` have one-argument forms.
+        if (name == "translate" && args.get(1) == Some(&0.0)) || (name == "scale" && args.get(1) == args.first()) {
+            args.truncate(1);
+        }
+        let args: Vec<String> = args.iter().map(|v| compact_number(*v, precision)).collect();
+        transforms.push(format!("{}({})", name, args.join(" ")));
+    }
+    transforms.join(" ")
+}
+
+/// Shortens `#aabbcc` to `#abc`.
+fn shorten_color(value: &str) -> String {
+    let lower = value.to_ascii_lowercase();
+    let b = lower.as_bytes();
+    if b.len() == 7 && b[0] == b'#' && b[1] == b[2] && b[3] == b[4] && b[5] == b[6] {
+        format!("#{}{}{}", b[1] as char, b[3] as char, b[5] as char)
+    } else if b.len() == 7 && b[0] == b'#' {
+        lower
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape(value: &str, quote: bool) -> String {
+    let value = value.replace('&', "&amp;").replace('<', "&lt;");
+    if quote {
+        value.replace('"', "&quot;")
+    } else {
+        value
+    }
+}
+
+/// Returns the prefixed name of an element or attribute in `node`'s scope.
+fn qualified_name(node: Node, namespace: Option<&str>, local: &str) -> String {
+    match namespace.and_then(|uri| node.lookup_prefix(uri)) {
+        Some(prefix) => format!("{}:{}", prefix, local),
+        None => local.to_string(),
+    }
+}
+
+struct Writer {
+    precision: usize,
+    removed: HashSet<NodeId>,
+}
+
+impl Writer {
+    /// Returns the optimized value of an attribute, or `None` to drop it.
+    fn attribute(&self, node: Node, name: &str, value: &str) -> Option<String> {
+        let value = match name {
+            "d" => match parse_path(value) {
+                Ok(segments) => minify_path_data(&segments, self.precision),
+                Err(_) => value.to_string(),
+            },
+            "offset" => match value.strip_suffix('%').map(str::parse::<f32>) {
+                Some(Ok(percent)) => compact_number(percent / 100.0, self.precision),
+                _ => round_numbers(value, self.precision),
+            },
+            _ if TRANSFORM_ATTRIBUTES.contains(&name) => normalize_transform(value, self.precision),
+            _ if NUMERIC_ATTRIBUTES.contains(&name) => round_numbers(value, self.precision),
+            _ if COLOR_ATTRIBUTES.contains(&name) => shorten_color(value),
+            _ => value.to_string(),
+        };
+
+        if value.is_empty() && TRANSFORM_ATTRIBUTES.contains(&name) {
+            return None;
+        }
+        if PRESENTATION_DEFAULTS.contains(&(name, value.as_str()))
+            && !node.ancestors().skip(1).any(|a| a.has_attribute(name))
+        {
+            return None;
+        }
+        let is_zero = value == "0" || value == "0%";
+        let redundant = match node.tag_name().name() {
+            "rect" => {
+                (matches!(name, "x" | "y") && is_zero)
+                    || (name == "ry" && node.attribute("rx").map(|rx| round_numbers(rx, self.precision)) == Some(value.clone()))
+            }
+            // Gradients that inherit from a template must keep their own values.
+            "linearGradient" if !node.has_attribute("href") && !node.has_attribute(("http://www.w3.org/1999/xlink", "href")) => {
+                (matches!(name, "x1" | "y1" | "y2") && is_zero) || (name == "x2" && value == "100%")
+            }
+            _ => false,
+        };
+        (!redundant).then_some(value)
+    }
+
+    fn write_element(&self, node: Node, out: &mut String) {
+        let tag = qualified_name(node, node.tag_name().namespace(), node.tag_name().name());
+        out.push('<');
+        out.push_str(&tag);
+
+        // Declare the namespaces that come into scope on this element.
+        let inherited: Vec<_> = node.parent_element().map(|p| p.namespaces().collect()).unwrap_or_default();
+        for ns in node.namespaces().filter(|ns| !inherited.contains(ns)) {
+            match ns.name() {
+                Some(prefix) => out.push_str(&format!(" xmlns:{}=\"{}\"", prefix, escape(ns.uri(), true))),
+                None => out.push_str(&format!(" xmlns=\"{}\"", escape(ns.uri(), true))),
+            }
+        }
+
+        for attr in node.attributes() {
+            if let Some(value) = self.attribute(node, attr.name(), attr.value()) {
+                let name = qualified_name(node, attr.namespace(), attr.name());
+                out.push_str(&format!(" {}=\"{}\"", name, escape(&value, true)));
+            }
+        }
+
+        let children: Vec<Node> = node
+            .children()
+            .filter(|n| !self.removed.contains(&n.id()))
+            .filter(|n| !n.is_element() || !n.has_tag_name("defs") || n.children().any(|c| c.is_element() && !self.removed.contains(&c.id())))
+            .filter(|n| n.is_element() || (n.is_text() && !n.text().unwrap_or_default().trim().is_empty()))
+            .collect();
+        if children.is_empty() {
+            out.push_str("/>");
+            return;
+        }
+        out.push('>');
+        for child in children {
+            if child.is_element() {
+                self.write_element(child, out);
+            } else {
+                out.push_str(&escape(child.text().unwrap_or_default(), false));
+            }
+        }
+        out.push_str(&format!("</{}>", tag));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, CustomStyles, Gradient, StylePreset};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn off_leaves_the_svg_untouched() {
+        let svg = "<svg>  <!-- comment --> </svg>";
+        assert_eq!(optimize(svg, OptimizeLevel::Off).unwrap(), svg);
+    }
+
+    #[test]
+    fn removes_unreferenced_definitions() {
+        let svg = generate_icon(TEST_SVG, StylePreset::Glassmorphism, &CustomStyles::default()).unwrap();
+        assert!(svg.contains("glass-blur"));
+        let optimized = optimize(&svg, OptimizeLevel::Safe).unwrap();
+        assert!(!optimized.contains("glass-blur"));
+        assert!(!optimized.contains("<defs"));
+
+        // A gradient only used by a dead filter goes with it.
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg"><defs><linearGradient id="g"/><filter id="f"><feFlood flood-color="url(#g)"/></filter><clipPath id="c"/></defs><rect clip-path="url(#c)"/></svg>"##;
+        assert_eq!(
+            optimize(svg, OptimizeLevel::Safe).unwrap(),
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><defs><clipPath id="c"/></defs><rect clip-path="url(#c)"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn rounds_numbers_and_collapses_attributes() {
+        let styles = CustomStyles {
+            padding: 10,
+            width: 100,
+            height: 80,
+            gradient: Some(Gradient { angle: 90, start_color: "#FF0000".to_string(), stop_color: "#0000ff".to_string() }),
+            ..CustomStyles::default()
+        };
+        let svg = generate_icon(r#"<svg viewBox="0 0 24 21"><path d="M 12.0000 2 L 2 22 L 22 22 Z"/></svg>"#, StylePreset::Neumorphism, &styles).unwrap();
+        let optimized = optimize(&svg, OptimizeLevel::Safe).unwrap();
+
+        assert!(optimized.len() < svg.len());
+        assert!(optimized.contains(r#"<linearGradient id="base-gradient" y1="50%" y2="50%">"#), "{}", optimized);
+        assert!(optimized.contains(r##"offset="0" stop-color="#f00""##));
+        assert!(optimized.contains(r##"offset="1" stop-color="#00f""##));
+        assert!(optimized.contains(r#"<rect class="icon-base" width="100" height="80" rx="25" fill="url(#base-gradient)""#));
+        assert!(optimized.contains(r#"transform="translate(15.714 10) scale(2.857)""#));
+        assert!(optimized.contains(r#"d="M12 2 2 22H22z""#));
+        assert!(optimized.contains(r#"class="icon-fg""#));
+        let aggressive = optimize(&svg, OptimizeLevel::Aggressive).unwrap();
+        assert!(aggressive.contains(r#"transform="translate(15.71 10) scale(2.86)""#));
+    }
+
+    #[test]
+    fn normalizes_transforms() {
+        assert_eq!(normalize_transform("translate(0, 0) scale(1)", 3), "");
+        assert_eq!(normalize_transform("translate(4.00001, 0) scale(2, 2) rotate(45)", 3), "translate(4) scale(2) rotate(45)");
+        assert_eq!(normalize_transform("bogus", 3), "bogus");
+        assert_eq!(round_numbers("-50.0% 1e-5 .126", 2), "-50% 0 .13");
+    }
+}
@@ -0,0 +1,316 @@
+use crate::path_data::format_number;
+use crate::{svg_processor, Alignment, CustomStyles, IconEngineError};
+
+const CUTOUT_MASK_ID: &str = "overlay-cutout";
+/// The class, numbered from 1 per overlay, of an overlay's filled shape.
+pub(crate) const OVERLAY_FILL_CLASS: &str = "overlay-fill";
+/// The class, numbered from 1 per overlay, of a badge or ribbon label.
+pub(crate) const OVERLAY_LABEL_CLASS: &str = "overlay-label";
+
+/// What an overlay draws.
+///
+/// Badge and ribbon labels are SVG `<text>` in a generic sans-serif font,
+/// which the rasterizer draws with an installed font.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OverlayShape {
+    /// A filled circle, e.g. a notification dot.
+    Dot,
+    /// A pill holding a short label such as an unread count; it grows wider
+    /// for longer labels.
+    Badge { text: String, text_color: String },
+    /// A band with a label across one of the canvas corners, e.g. "NEW".
+    Ribbon { text: String, text_color: String },
+    /// A second source SVG, fitted into the overlay's square like the main
+    /// glyph is fitted into the canvas.
+    Icon(String),
+}
+
+/// A badge or secondary glyph composited over the icon by
+/// [`crate::generate_icon`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    pub shape: OverlayShape,
+    /// The fill of the dot, badge, ribbon or glyph.
+    pub color: String,
+    /// The part of the canvas the overlay is placed in.
+    pub anchor: Alignment,
+    /// The height of the overlay in canvas units; the band width for ribbons.
+    pub size: f32,
+    /// The width of a transparent ring around the overlay, cut out of the
+    /// base and glyph underneath so the overlay stands apart from them.
+    pub cutout: Option<f32>,
+}
+
+/// The overlays of an icon as SVG fragments.
+pub(crate) struct OverlayMarkup {
+    /// A `<mask>` definition for the cutout rings, if any overlay has one.
+    pub mask: Option<String>,
+    /// The overlays themselves, drawn over everything else.
+    pub shapes: String,
+}
+
+impl OverlayMarkup {
+    /// Wraps the base and glyph markup in the cutout mask, if there is one.
+    pub fn masked(&self, content: &str) -> String {
+        match self.mask {
+            Some(_) => format!(r#"<g mask="url(#{})">{}</g>"#, CUTOUT_MASK_ID, content),
+            None => content.to_string(),
+        }
+    }
+}
+
+/// One overlay's outline: the shape drawn in the overlay color and, in
+/// black, in the cutout mask.
+struct Outline {
+    /// The outline element without a fill.
+    element: String,
+    /// Scales the cutout ring width into the element's coordinate system.
+    stroke_scale: f32,
+}
+
+/// Fails for styles with overlays, for outputs that cannot draw them.
+pub(crate) fn reject_overlays(styles: &CustomStyles, output: &str) -> Result<(), IconEngineError> {
+    if styles.overlays.is_empty() {
+        return Ok(());
+    }
+    Err(IconEngineError::InvalidInput(format!("Overlays are not supported in {} output", output)))
+}
+
+pub(crate) fn overlay_markup(styles: &CustomStyles) -> Result<OverlayMarkup, IconEngineError> {
+    let (width, height) = (styles.width as f32, styles.height as f32);
+    let mut shapes = String::new();
+    let mut cutouts = String::new();
+
+    for (index, overlay) in styles.overlays.iter().enumerate() {
+        if overlay.size.is_nan() || overlay.size <= 0.0 {
+            return Err(IconEngineError::InvalidInput(format!(
+                "Overlay size must be positive, got {}",
+                overlay.size
+            )));
+        }
+        let (outline, label) = match &overlay.shape {
+            OverlayShape::Dot => {
+                let (x, y) = anchor_position(overlay.anchor, overlay.size, overlay.size, width, height);
+                let radius = overlay.size / 2.0;
+                (circle(x + radius, y + radius, radius), String::new())
+            }
+            OverlayShape::Badge { text, text_color } => badge(overlay, text, text_color, width, height),
+            OverlayShape::Ribbon { text, text_color } => ribbon(overlay, text, text_color, width, height)?,
+            OverlayShape::Icon(icon_data) => (glyph(overlay, icon_data, width, height)?, String::new()),
+        };
+
+        // Numbered classes let the adaptive theme recolor each overlay.
+        let number = index + 1;
+        shapes.push_str(&format!(
+            r#"<g class="overlay">{}{}</g>"#,
+            outline.element.replacen(
+                "/>",
+                &format!(r#" class="{}-{}" fill="{}"/>"#, OVERLAY_FILL_CLASS, number, overlay.color),
+                1
+            ),
+            label.replacen("<text ", &format!(r#"<text class="{}-{}" "#, OVERLAY_LABEL_CLASS, number), 1)
+        ));
+        if let Some(ring) = overlay.cutout {
+            cutouts.push_str(&outline.element.replacen(
+                "/>",
+                &format!(
+                    r#" fill="black" stroke="black" stroke-width="{}" stroke-linejoin="round"/>"#,
+                    format_number(2.0 * ring / outline.stroke_scale)
+                ),
+                1,
+            ));
+        }
+    }
+
+    let mask = (!cutouts.is_empty()).then(|| {
+        format!(
+            r#"<defs><mask id="{id}" maskUnits="userSpaceOnUse" x="0" y="0" width="{w}" height="{h}"><rect width="{w}" height="{h}" fill="white"/>{cutouts}</mask></defs>"#,
+            id = CUTOUT_MASK_ID,
+            w = styles.width,
+            h = styles.height,
+            cutouts = cutouts
+        )
+    });
+    Ok(OverlayMarkup { mask, shapes })
+}
+
+/// Returns the top-left corner of a `w` x `h` box placed at `anchor` within
+/// the canvas, touching the edges it is aligned to.
+fn anchor_position(anchor: Alignment, w: f32, h: f32, width: f32, height: f32) -> (f32, f32) {
+    use Alignment::*;
+    let x = match anchor {
+        TopLeft | Left | BottomLeft => 0.0,
+        Top | Center | Bottom => (width - w) / 2.0,
+        TopRight | Right | BottomRight => width - w,
+    };
+    let y = match anchor {
+        TopLeft | Top | TopRight => 0.0,
+        Left | Center | Right => (height - h) / 2.0,
+        BottomLeft | Bottom | BottomRight => height - h,
+    };
+    (x, y)
+}
+
+fn circle(cx: f32, cy: f32, r: f32) -> Outline {
+    Outline {
+        element: format!(
+            r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+            format_number(cx),
+            format_number(cy),
+            format_number(r)
+        ),
+        stroke_scale: 1.0,
+    }
+}
+
+/// Returns a centered `<text>` label of the given font size.
+fn label(text: &str, color: &str, (x, y): (f32, f32), font_size: f32, rotation: f32) -> String {
+    let rotate = if rotation == 0.0 {
+        String::new()
+    } else {
+        format!(r#" transform="rotate({} {} {})""#, format_number(rotation), format_number(x), format_number(y))
+    };
+    format!(
+        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" font-weight="bold" text-anchor="middle" dominant-baseline="central" fill="{}"{}>{}</text>"#,
+        format_number(x),
+        format_number(y),
+        format_number(font_size),
+        color,
+        rotate,
+        text.replace('&', "&amp;").replace('<', "&lt;")
+    )
+}
+
+fn badge(overlay: &Overlay, text: &str, text_color: &str, width: f32, height: f32) -> (Outline, String) {
+    let h = overlay.size;
+    // Roughly the advance of a bold sans-serif digit at the label's font size.
+    let w = (h * 0.6 + text.chars().count() as f32 * h * 0.4).max(h);
+    let (x, y) = anchor_position(overlay.anchor, w, h, width, height);
+    let outline = Outline {
+        element: format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}"/>"#,
+            format_number(x),
+            format_number(y),
+            format_number(w),
+            format_number(h),
+            format_number(h / 2.0)
+        ),
+        stroke_scale: 1.0,
+    };
+    (outline, label(text, text_color, (x + w / 2.0, y + h / 2.0), h * 0.7, 0.0))
+}
+
+fn ribbon(
+    overlay: &Overlay,
+    text: &str,
+    text_color: &str,
+    width: f32,
+    height: f32,
+) -> Result<(Outline, String), IconEngineError> {
+    // The corner and the directions pointing into the canvas from it.
+    let (cx, cy, sx, sy) = match overlay.anchor {
+        Alignment::TopLeft => (0.0, 0.0, 1.0, 1.0),
+        Alignment::TopRight => (width, 0.0, -1.0, 1.0),
+        Alignment::BottomLeft => (0.0, height, 1.0, -1.0),
+        Alignment::BottomRight => (width, height, -1.0, -1.0),
+        anchor => {
+            return Err(IconEngineError::InvalidInput(format!(
+                "Ribbons must be anchored to a corner, not {:?}",
+                anchor
+            )))
+        }
+    };
+    // The band's edges cut the canvas edges this far from the corner, so it
+    // is `size` wide measured across.
+    let inner = overlay.size * 1.5;
+    let outer = inner + overlay.size * std::f32::consts::SQRT_2;
+    let points = [(sx * inner, 0.0), (sx * outer, 0.0), (0.0, sy * outer), (0.0, sy * inner)]
+        .map(|(dx, dy)| format!("{},{}", format_number(cx + dx), format_number(cy + dy)));
+    let outline = Outline { element: format!(r#"<polygon points="{}"/>"#, points.join(" ")), stroke_scale: 1.0 };
+
+    let middle = (inner + outer) / 2.0;
+    let mut rotation = sy.atan2(-sx).to_degrees();
+    if rotation > 90.0 {
+        rotation -= 180.0;
+    } else if rotation < -90.0 {
+        rotation += 180.0;
+    }
+    let center = (cx + sx * middle / 2.0, cy + sy * middle / 2.0);
+    Ok((outline, label(text, text_color, center, overlay.size * 0.6, rotation)))
+}
+
+fn glyph(overlay: &Overlay, icon_data: &str, width: f32, height: f32) -> Result<Outline, IconEngineError> {
+    let icon = svg_processor::parse_svg(icon_data)?;
+    let (x, y) = anchor_position(overlay.anchor, overlay.size, overlay.size, width, height);
+    let scale = overlay.size / icon.viewbox.width.max(icon.viewbox.height);
+    let tx = x + (overlay.size - icon.viewbox.width * scale) / 2.0;
+    let ty = y + (overlay.size - icon.viewbox.height * scale) / 2.0;
+    Ok(Outline {
+        element: format!(
+            r#"<path transform="translate({}, {}) scale({})" d="{}"/>"#,
+            format_number(tx),
+            format_number(ty),
+            format_number(scale),
+            icon.path_data
+        ),
+        stroke_scale: scale,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, StylePreset};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"/></svg>"#;
+
+    fn overlay(shape: OverlayShape, anchor: Alignment, cutout: Option<f32>) -> Overlay {
+        Overlay { shape, color: "#e53935".to_string(), anchor, size: 32.0, cutout }
+    }
+
+    #[test]
+    fn places_overlays_with_cutouts() {
+        let styles = CustomStyles {
+            overlays: vec![
+                overlay(OverlayShape::Dot, Alignment::TopRight, Some(4.0)),
+                overlay(
+                    OverlayShape::Badge { text: "12".to_string(), text_color: "white".to_string() },
+                    Alignment::BottomLeft,
+                    None,
+                ),
+                overlay(OverlayShape::Icon(TEST_SVG.to_string()), Alignment::BottomRight, Some(2.0)),
+            ],
+            ..CustomStyles::default()
+        };
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+        roxmltree::Document::parse(&svg).unwrap();
+
+        assert!(svg.contains(r#"<g mask="url(#overlay-cutout)"><rect class="icon-base""#));
+        assert!(svg.contains(r##"<g class="overlay"><circle cx="112" cy="16" r="16" class="overlay-fill-1" fill="#e53935"/></g>"##));
+        assert!(svg.contains(r#"<circle cx="112" cy="16" r="16" fill="black" stroke="black" stroke-width="8""#));
+        // Two characters widen the badge to 1.4 times its height.
+        assert!(svg.contains(r##"<rect x="0" y="96" width="44.8" height="32" rx="16" class="overlay-fill-2" fill="#e53935"/>"##));
+        assert!(svg.contains(">12</text>"));
+        // The ring is scaled into the overlay glyph's viewBox units.
+        assert!(svg.contains(r#"transform="translate(96, 96) scale(1.333)" d="M12 2L2 22h20L12 2z" fill="black" stroke="black" stroke-width="3""#));
+    }
+
+    #[test]
+    fn ribbons_cross_a_corner() {
+        let ribbon = OverlayShape::Ribbon { text: "NEW".to_string(), text_color: "white".to_string() };
+        let styles = CustomStyles {
+            overlays: vec![overlay(ribbon.clone(), Alignment::TopLeft, None)],
+            ..CustomStyles::default()
+        };
+        let markup = overlay_markup(&styles).unwrap();
+        assert!(markup.mask.is_none());
+        assert!(markup.shapes.contains(r#"rotate(-45 "#));
+        assert_eq!(markup.masked("<rect/>"), "<rect/>");
+
+        let centered = CustomStyles {
+            overlays: vec![overlay(ribbon, Alignment::Center, None)],
+            ..CustomStyles::default()
+        };
+        assert!(matches!(overlay_markup(&centered), Err(IconEngineError::InvalidInput(_))));
+    }
+}
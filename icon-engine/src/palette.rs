@@ -0,0 +1,401 @@
+use crate::color::Rgb;
+use crate::glyph_stack::GlyphLayer;
+use crate::overlay::{Overlay, OverlayShape};
+use crate::pattern::BasePattern;
+use crate::{CustomStyles, Gradient, IconEngineError, ShadowColors};
+
+/// The prefix that marks a color value as a reference to a palette entry,
+/// e.g. `@brand-primary`.
+pub const PALETTE_REF_PREFIX: char = '@';
+
+/// The file formats a palette can be loaded from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// A JSON object mapping names to colors, optionally nested under `"colors"`.
+    Json,
+    /// CSS custom properties, e.g. `--brand-primary: #0f6cbd;`.
+    Css,
+    /// A GIMP `.gpl` palette.
+    Gpl,
+    /// An Adobe Swatch Exchange `.ase` file.
+    Ase,
+}
+
+impl PaletteFormat {
+    /// Picks the format from a file extension.
+    pub fn from_extension(ext: &str) -> Option<PaletteFormat> {
+        match ext.to_ascii_lowercase().as_str() {
+            "json" => Some(PaletteFormat::Json),
+            "css" => Some(PaletteFormat::Css),
+            "gpl" => Some(PaletteFormat::Gpl),
+            "ase" => Some(PaletteFormat::Ase),
+            _ => None,
+        }
+    }
+}
+
+/// A named set of colors that styles can reference with `@name`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Palette {
+    entries: Vec<(String, Rgb)>,
+}
+
+impl Palette {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a color, replacing any existing entry with the same name.
+    pub fn insert(&mut self, name: &str, color: Rgb) {
+        let name = name.trim();
+        match self.entries.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(name)) {
+            Some(entry) => entry.1 = color,
+            None => self.entries.push((name.to_string(), color)),
+        }
+    }
+
+    /// Looks up a color by name, ignoring case and surrounding whitespace.
+    pub fn get(&self, name: &str) -> Option<Rgb> {
+        let name = name.trim();
+        self.entries
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, c)| *c)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterates over the entries in file order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Rgb)> {
+        self.entries.iter().map(|(n, c)| (n.as_str(), *c))
+    }
+
+    /// Parses a palette in the given format.
+    pub fn parse(data: &[u8], format: PaletteFormat) -> Result<Palette, IconEngineError> {
+        match format {
+            PaletteFormat::Ase => parse_ase(data),
+            _ => {
+                let text = std::str::from_utf8(data).map_err(|_| {
+                    IconEngineError::InvalidInput("Palette file is not valid UTF-8".to_string())
+                })?;
+                match format {
+                    PaletteFormat::Json => parse_json(text),
+                    PaletteFormat::Css => Ok(parse_css(text)),
+                    PaletteFormat::Gpl => parse_gpl(text),
+                    PaletteFormat::Ase => unreachable!(),
+                }
+            }
+        }
+    }
+
+    /// Resolves a color value, replacing an `@name` reference with the
+    /// palette entry's hex color. Other values are returned unchanged.
+    pub fn resolve(&self, color: &str) -> Result<String, IconEngineError> {
+        match color.trim().strip_prefix(PALETTE_REF_PREFIX) {
+            Some(name) => self.get(name).map(Rgb::to_hex).ok_or_else(|| {
+                IconEngineError::InvalidInput(format!("Unknown palette color: {}", name))
+            }),
+            None => Ok(color.to_string()),
+        }
+    }
+
+    /// Returns a copy of `styles` with every palette reference in the
+    /// foreground, base, gradient stops, shadows, overlays and glyph layers
+    /// resolved.
+    pub fn apply(&self, styles: &CustomStyles) -> Result<CustomStyles, IconEngineError> {
+        let resolve_opt = |color: &Option<String>| color.as_deref().map(|c| self.resolve(c)).transpose();
+
+        Ok(CustomStyles {
+            icon_color: self.resolve(&styles.icon_color)?,
+            base_color: resolve_opt(&styles.base_color)?,
+            gradient: styles
+                .gradient
+                .as_ref()
+                .map(|g| -> Result<Gradient, IconEngineError> {
+                    Ok(Gradient {
+                        angle: g.angle,
+                        start_color: self.resolve(&g.start_color)?,
+                        stop_color: self.resolve(&g.stop_color)?,
+                    })
+                })
+                .transpose()?,
+            shadow_colors: styles
+                .shadow_colors
+                .as_ref()
+                .map(|s| -> Result<ShadowColors, IconEngineError> {
+                    Ok(ShadowColors {
+                        dark: self.resolve(&s.dark)?,
+                        light: self.resolve(&s.light)?,
+                    })
+                })
+                .transpose()?,
+            pattern: styles
+                .pattern
+                .as_ref()
+                .map(|p| -> Result<BasePattern, IconEngineError> {
+                    Ok(BasePattern { color: self.resolve(&p.color)?, ..p.clone() })
+                })
+                .transpose()?,
+            overlays: styles
+                .overlays
+                .iter()
+                .map(|overlay| -> Result<Overlay, IconEngineError> {
+                    let shape = match &overlay.shape {
+                        OverlayShape::Badge { text, text_color } => {
+                            OverlayShape::Badge { text: text.clone(), text_color: self.resolve(text_color)? }
+                        }
+                        OverlayShape::Ribbon { text, text_color } => {
+                            OverlayShape::Ribbon { text: text.clone(), text_color: self.resolve(text_color)? }
+                        }
+                        shape => shape.clone(),
+                    };
+                    Ok(Overlay { shape, color: self.resolve(&overlay.color)?, ..overlay.clone() })
+                })
+                .collect::<Result<_, _>>()?,
+            glyph_layers: styles
+                .glyph_layers
+                .iter()
+                .map(|layer| -> Result<GlyphLayer, IconEngineError> {
+                    Ok(GlyphLayer { color: resolve_opt(&layer.color)?, ..layer.clone() })
+                })
+                .collect::<Result<_, _>>()?,
+            ..styles.clone()
+        })
+    }
+}
+
+fn invalid_color(name: &str, value: &str) -> IconEngineError {
+    IconEngineError::InvalidInput(format!("Invalid color for palette entry '{}': {}", name, value))
+}
+
+fn parse_json(text: &str) -> Result<Palette, IconEngineError> {
+    let value: serde_json::Value = serde_json::from_str(text)
+        .map_err(|e| IconEngineError::InvalidInput(format!("Invalid palette JSON: {}", e)))?;
+
+    let colors = value.get("colors").unwrap_or(&value);
+    let object = colors.as_object().ok_or_else(|| {
+        IconEngineError::InvalidInput("Palette JSON must be an object of name/color pairs".to_string())
+    })?;
+
+    let mut palette = Palette::new();
+    for (name, color) in object {
+        let value = color.as_str().ok_or_else(|| invalid_color(name, &color.to_string()))?;
+        let rgb = Rgb::parse(value).ok_or_else(|| invalid_color(name, value))?;
+        palette.insert(name, rgb);
+    }
+    Ok(palette)
+}
+
+/// Collects every `--name: <color>` declaration, skipping values that are
+/// not plain colors (e.g. `var(...)` or lengths).
+fn parse_css(text: &str) -> Palette {
+    let mut palette = Palette::new();
+    for declaration in text.split([';', '{', '}']) {
+        let Some((property, value)) = declaration.split_once(':') else {
+            continue;
+        };
+        let Some(name) = property.trim().strip_prefix("--") else {
+            continue;
+        };
+        if let Some(rgb) = Rgb::parse(value) {
+            palette.insert(name, rgb);
+        }
+    }
+    palette
+}
+
+fn parse_gpl(text: &str) -> Result<Palette, IconEngineError> {
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some("GIMP Palette") {
+        return Err(IconEngineError::InvalidInput(
+            "GPL palette must start with 'GIMP Palette'".to_string(),
+        ));
+    }
+
+    let mut palette = Palette::new();
+    for line in lines {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.contains(':') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let mut channel = || fields.next().and_then(|v| v.parse::<u8>().ok());
+        let (Some(r), Some(g), Some(b)) = (channel(), channel(), channel()) else {
+            return Err(IconEngineError::InvalidInput(format!("Invalid GPL color line: {}", line)));
+        };
+        let rgb = Rgb::new(r, g, b);
+        let name = fields.collect::<Vec<_>>().join(" ");
+        let name = if name.is_empty() { rgb.to_hex() } else { name };
+        palette.insert(&name, rgb);
+    }
+    Ok(palette)
+}
+
+fn parse_ase(data: &[u8]) -> Result<Palette, IconEngineError> {
+    let truncated = || IconEngineError::InvalidInput("ASE palette is truncated".to_string());
+    let mut reader = ByteReader { data, pos: 0 };
+
+    if reader.take(4).ok_or_else(truncated)? != b"ASEF" {
+        return Err(IconEngineError::InvalidInput("Not an ASE palette".to_string()));
+    }
+    reader.take(4).ok_or_else(truncated)?; // version
+    let block_count = reader.u32().ok_or_else(truncated)?;
+
+    let mut palette = Palette::new();
+    for _ in 0..block_count {
+        let block_type = reader.u16().ok_or_else(truncated)?;
+        let length = reader.u32().ok_or_else(truncated)? as usize;
+        let mut block = ByteReader { data: reader.take(length).ok_or_else(truncated)?, pos: 0 };
+
+        // Only color entries matter; group start/end blocks are skipped.
+        if block_type != 0x0001 {
+            continue;
+        }
+
+        let name_len = block.u16().ok_or_else(truncated)? as usize;
+        let name_units: Vec<u16> = (0..name_len)
+            .map(|_| block.u16())
+            .collect::<Option<_>>()
+            .ok_or_else(truncated)?;
+        let name = String::from_utf16_lossy(&name_units).trim_end_matches('\0').to_string();
+
+        let model = block.take(4).ok_or_else(truncated)?;
+        let channels = match model {
+            b"RGB " => 3,
+            b"CMYK" => 4,
+            b"Gray" => 1,
+            // LAB swatches would need a reference white; skip them.
+            _ => continue,
+        };
+        let values: Vec<f32> = (0..channels)
+            .map(|_| block.f32())
+            .collect::<Option<_>>()
+            .ok_or_else(truncated)?;
+        let to_u8 = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        let rgb = match channels {
+            3 => Rgb::new(to_u8(values[0]), to_u8(values[1]), to_u8(values[2])),
+            4 => {
+                let k = 1.0 - values[3];
+                Rgb::new(
+                    to_u8((1.0 - values[0]) * k),
+                    to_u8((1.0 - values[1]) * k),
+                    to_u8((1.0 - values[2]) * k),
+                )
+            }
+            _ => Rgb::new(to_u8(values[0]), to_u8(values[0]), to_u8(values[0])),
+        };
+        palette.insert(&name, rgb);
+    }
+    Ok(palette)
+}
+
+/// A minimal big-endian cursor over a byte slice.
+struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(n)?)?;
+        self.pos += n;
+        Some(slice)
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn f32(&mut self) -> Option<f32> {
+        self.u32().map(f32::from_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_json_nested_and_flat() {
+        let flat = Palette::parse(br##"{"primary": "#0f6cbd"}"##, PaletteFormat::Json).unwrap();
+        let nested =
+            Palette::parse(br##"{"name": "Brand", "colors": {"primary": "#0f6cbd"}}"##, PaletteFormat::Json)
+                .unwrap();
+        assert_eq!(flat, nested);
+        assert_eq!(flat.get("Primary"), Some(Rgb::new(0x0f, 0x6c, 0xbd)));
+    }
+
+    #[test]
+    fn parse_css_custom_properties() {
+        let css = ":root { --brand: #112233; --spacing: 4px; --accent:#fff }";
+        let palette = Palette::parse(css.as_bytes(), PaletteFormat::Css).unwrap();
+        assert_eq!(palette.len(), 2);
+        assert_eq!(palette.get("accent"), Some(Rgb::new(255, 255, 255)));
+    }
+
+    #[test]
+    fn parse_gimp_palette() {
+        let gpl = "GIMP Palette\nName: Brand\nColumns: 2\n# comment\n255   0   0\tRed Alert\n  0 128 255 Sky\n";
+        let palette = Palette::parse(gpl.as_bytes(), PaletteFormat::Gpl).unwrap();
+        assert_eq!(palette.get("red alert"), Some(Rgb::new(255, 0, 0)));
+        assert_eq!(palette.get("Sky"), Some(Rgb::new(0, 128, 255)));
+    }
+
+    #[test]
+    fn parse_ase_rgb_swatch() {
+        let mut ase = b"ASEF".to_vec();
+        ase.extend_from_slice(&[0, 1, 0, 0]);
+        ase.extend_from_slice(&1u32.to_be_bytes());
+
+        let mut block = Vec::new();
+        let name: Vec<u16> = "Ink\0".encode_utf16().collect();
+        block.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        name.iter().for_each(|u| block.extend_from_slice(&u.to_be_bytes()));
+        block.extend_from_slice(b"RGB ");
+        for v in [1.0f32, 0.5, 0.0] {
+            block.extend_from_slice(&v.to_bits().to_be_bytes());
+        }
+        block.extend_from_slice(&2u16.to_be_bytes());
+
+        ase.extend_from_slice(&1u16.to_be_bytes());
+        ase.extend_from_slice(&(block.len() as u32).to_be_bytes());
+        ase.extend_from_slice(&block);
+
+        let palette = Palette::parse(&ase, PaletteFormat::Ase).unwrap();
+        assert_eq!(palette.get("Ink"), Some(Rgb::new(255, 128, 0)));
+    }
+
+    #[test]
+    fn apply_resolves_references() {
+        let mut palette = Palette::new();
+        palette.insert("fg", Rgb::new(1, 2, 3));
+        palette.insert("start", Rgb::new(255, 0, 0));
+
+        let styles = CustomStyles {
+            icon_color: "@fg".to_string(),
+            gradient: Some(Gradient {
+                angle: 0,
+                start_color: "@start".to_string(),
+                stop_color: "#000000".to_string(),
+            }),
+            ..CustomStyles::default()
+        };
+        let resolved = palette.apply(&styles).unwrap();
+        assert_eq!(resolved.icon_color, "#010203");
+        assert_eq!(resolved.gradient.unwrap().start_color, "#ff0000");
+
+        let missing = CustomStyles { base_color: Some("@nope".to_string()), ..styles };
+        assert!(palette.apply(&missing).is_err());
+    }
+}
@@ -0,0 +1,849 @@
+use std::f32::consts::PI;
+
+use crate::IconEngineError;
+
+/// A point in user space.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// One absolute path command. Relative and shorthand forms (`h`, `v`, `s`,
+/// `t`, ...) are resolved while parsing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    MoveTo(Point),
+    LineTo(Point),
+    CubicTo(Point, Point, Point),
+    QuadTo(Point, Point),
+    ArcTo {
+        rx: f32,
+        ry: f32,
+        x_axis_rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    },
+    ClosePath,
+}
+
+/// A 2D affine transform with the components of SVG's
+/// `matrix(a b c d e f)`: `x' = a*x + c*y + e`, `y' = b*x + d*y + f`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub const fn translate(tx: f32, ty: f32) -> Self {
+        Self { e: tx, f: ty, ..Self::IDENTITY }
+    }
+
+    pub const fn scale(sx: f32, sy: f32) -> Self {
+        Self { a: sx, d: sy, ..Self::IDENTITY }
+    }
+
+    /// Returns the transform that applies `self` first and then `next`.
+    pub fn then(self, next: Transform) -> Self {
+        Self {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            e: next.a * self.e + next.c * self.f + next.e,
+            f: next.b * self.e + next.d * self.f + next.f,
+        }
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// Parses an SVG transform list such as `translate(4 4) rotate(45 12 12)`.
+    /// Returns `None` for malformed lists.
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut transform = Self::IDENTITY;
+        for (name, args) in parse_transform_list(value)? {
+            let (sin, cos) = args.first().copied().unwrap_or(0.0).to_radians().sin_cos();
+            let next = match (name, args.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Self { a, b, c, d, e, f },
+                ("translate", &[tx]) => Self::translate(tx, 0.0),
+                ("translate", &[tx, ty]) => Self::translate(tx, ty),
+                ("scale", &[s]) => Self::scale(s, s),
+                ("scale", &[sx, sy]) => Self::scale(sx, sy),
+                ("rotate", &[_]) => Self { a: cos, b: sin, c: -sin, d: cos, ..Self::IDENTITY },
+                ("rotate", &[_, cx, cy]) => Self::translate(-cx, -cy)
+                    .then(Self { a: cos, b: sin, c: -sin, d: cos, ..Self::IDENTITY })
+                    .then(Self::translate(cx, cy)),
+                ("skewX", &[_]) => Self { c: sin / cos, ..Self::IDENTITY },
+                ("skewY", &[_]) => Self { b: sin / cos, ..Self::IDENTITY },
+                _ => return None,
+            };
+            // Later transforms in the list apply to the coordinates first.
+            transform = next.then(transform);
+        }
+        Some(transform)
+    }
+
+    /// Maps an arc's ellipse through the linear part of the transform,
+    /// returning the new `(rx, ry, x_axis_rotation)` in degrees.
+    fn apply_to_ellipse(&self, rx: f32, ry: f32, rotation: f32) -> (f32, f32, f32) {
+        // The ellipse is the unit circle under M = L * R(rotation) * diag(rx, ry);
+        // its radii and axis are the singular values and left rotation of M.
+        let (sin, cos) = rotation.to_radians().sin_cos();
+        let (p, q) = ((self.a * cos + self.c * sin) * rx, (self.c * cos - self.a * sin) * ry);
+        let (r, s) = ((self.b * cos + self.d * sin) * rx, (self.d * cos - self.b * sin) * ry);
+
+        let (e, f, g, h) = ((p + s) / 2.0, (p - s) / 2.0, (r + q) / 2.0, (r - q) / 2.0);
+        let (big, small) = (e.hypot(h), f.hypot(g));
+        let angle = (g.atan2(f) + h.atan2(e)) / 2.0;
+        (big + small, (big - small).abs(), angle.to_degrees())
+    }
+}
+
+/// Splits an SVG transform list such as `translate(4 4) rotate(45 12 12)`
+/// into its functions' names and arguments, without checking either.
+/// Returns `None` for lists that are not `name(numbers)` sequences.
+pub fn parse_transform_list(value: &str) -> Option<Vec<(&str, Vec<f32>)>> {
+    let mut list = Vec::new();
+    let mut parts: Vec<&str> = value.split(')').collect();
+    // Anything after the last closing parenthesis is an unterminated item.
+    if !parts.pop()?.trim_matches(|c: char| c == ',' || c.is_whitespace()).is_empty() {
+        return None;
+    }
+    for part in parts {
+        let part = part.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let (name, args) = part.split_once('(')?;
+        let args = args
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<f32>().ok())
+            .collect::<Option<Vec<f32>>>()?;
+        list.push((name.trim(), args));
+    }
+    Some(list)
+}
+
+/// Applies an affine transform to every coordinate of a path. Arcs stay
+/// arcs: their radii and rotation are transformed exactly, and the sweep
+/// flips when the transform mirrors.
+pub fn transform_path(segments: &[PathSegment], transform: &Transform) -> Vec<PathSegment> {
+    let mirrors = transform.a * transform.d - transform.b * transform.c < 0.0;
+    segments
+        .iter()
+        .map(|segment| match *segment {
+            PathSegment::MoveTo(p) => PathSegment::MoveTo(transform.apply(p)),
+            PathSegment::LineTo(p) => PathSegment::LineTo(transform.apply(p)),
+            PathSegment::CubicTo(c1, c2, p) => {
+                PathSegment::CubicTo(transform.apply(c1), transform.apply(c2), transform.apply(p))
+            }
+            PathSegment::QuadTo(c, p) => PathSegment::QuadTo(transform.apply(c), transform.apply(p)),
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to } => {
+                let (rx, ry, x_axis_rotation) = transform.apply_to_ellipse(rx, ry, x_axis_rotation);
+                PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep: sweep != mirrors, to: transform.apply(to) }
+            }
+            PathSegment::ClosePath => PathSegment::ClosePath,
+        })
+        .collect()
+}
+
+/// Parses SVG path data into absolute segments.
+pub fn parse_path(d: &str) -> Result<Vec<PathSegment>, IconEngineError> {
+    let mut lexer = Lexer { bytes: d.as_bytes(), pos: 0 };
+    let mut segments = Vec::new();
+
+    let mut current = Point::new(0.0, 0.0);
+    let mut subpath_start = current;
+    // The reflected control point for S/s and T/t.
+    let mut last_cubic_ctrl: Option<Point> = None;
+    let mut last_quad_ctrl: Option<Point> = None;
+    let mut command: Option<u8> = None;
+
+    loop {
+        lexer.skip_separators();
+        if lexer.at_end() {
+            break;
+        }
+
+        if let Some(c) = lexer.command() {
+            command = Some(c);
+        } else if command.is_none() {
+            return Err(lexer.error("Path data must start with a command"));
+        }
+        let cmd = command.unwrap();
+        let relative = cmd.is_ascii_lowercase();
+        let base = if relative { current } else { Point::new(0.0, 0.0) };
+        let abs = |x: f32, y: f32| Point::new(base.x + x, base.y + y);
+
+        let mut cubic_ctrl = None;
+        let mut quad_ctrl = None;
+
+        match cmd.to_ascii_uppercase() {
+            b'M' => {
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::MoveTo(p));
+                current = p;
+                subpath_start = p;
+                // Subsequent coordinate pairs are implicit line-to commands.
+                command = Some(if relative { b'l' } else { b'L' });
+            }
+            b'L' => {
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::LineTo(p));
+                current = p;
+            }
+            b'H' => {
+                let x = lexer.number()? + if relative { current.x } else { 0.0 };
+                current = Point::new(x, current.y);
+                segments.push(PathSegment::LineTo(current));
+            }
+            b'V' => {
+                let y = lexer.number()? + if relative { current.y } else { 0.0 };
+                current = Point::new(current.x, y);
+                segments.push(PathSegment::LineTo(current));
+            }
+            b'C' => {
+                let c1 = abs(lexer.number()?, lexer.number()?);
+                let c2 = abs(lexer.number()?, lexer.number()?);
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::CubicTo(c1, c2, p));
+                cubic_ctrl = Some(c2);
+                current = p;
+            }
+            b'S' => {
+                let c1 = reflect(last_cubic_ctrl, current);
+                let c2 = abs(lexer.number()?, lexer.number()?);
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::CubicTo(c1, c2, p));
+                cubic_ctrl = Some(c2);
+                current = p;
+            }
+            b'Q' => {
+                let c = abs(lexer.number()?, lexer.number()?);
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::QuadTo(c, p));
+                quad_ctrl = Some(c);
+                current = p;
+            }
+            b'T' => {
+                let c = reflect(last_quad_ctrl, current);
+                let p = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::QuadTo(c, p));
+                quad_ctrl = Some(c);
+                current = p;
+            }
+            b'A' => {
+                let rx = lexer.number()?;
+                let ry = lexer.number()?;
+                let x_axis_rotation = lexer.number()?;
+                let large_arc = lexer.flag()?;
+                let sweep = lexer.flag()?;
+                let to = abs(lexer.number()?, lexer.number()?);
+                segments.push(PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to });
+                current = to;
+            }
+            b'Z' => {
+                segments.push(PathSegment::ClosePath);
+                current = subpath_start;
+                // A close path takes no arguments, so it never repeats.
+                command = None;
+            }
+            other => {
+                return Err(lexer.error(&format!("Unknown path command '{}'", other as char)));
+            }
+        }
+
+        last_cubic_ctrl = cubic_ctrl;
+        last_quad_ctrl = quad_ctrl;
+    }
+
+    Ok(segments)
+}
+
+/// Builds the outline of a rectangle at the origin with circular corners,
+/// clamping the radius to half the shorter side.
+pub fn rounded_rect(width: f32, height: f32, radius: f32) -> Vec<PathSegment> {
+    let r = radius.min(width / 2.0).min(height / 2.0).max(0.0);
+    if r == 0.0 {
+        return vec![
+            PathSegment::MoveTo(Point::new(0.0, 0.0)),
+            PathSegment::LineTo(Point::new(width, 0.0)),
+            PathSegment::LineTo(Point::new(width, height)),
+            PathSegment::LineTo(Point::new(0.0, height)),
+            PathSegment::ClosePath,
+        ];
+    }
+    let corner = |x: f32, y: f32| PathSegment::ArcTo {
+        rx: r,
+        ry: r,
+        x_axis_rotation: 0.0,
+        large_arc: false,
+        sweep: true,
+        to: Point::new(x, y),
+    };
+    vec![
+        PathSegment::MoveTo(Point::new(r, 0.0)),
+        PathSegment::LineTo(Point::new(width - r, 0.0)),
+        corner(width, r),
+        PathSegment::LineTo(Point::new(width, height - r)),
+        corner(width - r, height),
+        PathSegment::LineTo(Point::new(r, height)),
+        corner(0.0, height - r),
+        PathSegment::LineTo(Point::new(0.0, r)),
+        corner(r, 0.0),
+        PathSegment::ClosePath,
+    ]
+}
+
+/// Serializes segments back into compact absolute path data.
+pub fn to_path_data(segments: &[PathSegment]) -> String {
+    let mut out = String::new();
+    // Numbers are always separated by a space, which every path syntax
+    // derived from SVG's (VectorDrawable, XAML) accepts.
+    let mut push = |cmd: char, values: &[f32]| {
+        out.push(cmd);
+        for (i, v) in values.iter().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            out.push_str(&format_number(*v));
+        }
+    };
+
+    for segment in segments {
+        match *segment {
+            PathSegment::MoveTo(p) => push('M', &[p.x, p.y]),
+            PathSegment::LineTo(p) => push('L', &[p.x, p.y]),
+            PathSegment::CubicTo(c1, c2, p) => push('C', &[c1.x, c1.y, c2.x, c2.y, p.x, p.y]),
+            PathSegment::QuadTo(c, p) => push('Q', &[c.x, c.y, p.x, p.y]),
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to } => push(
+                'A',
+                &[rx, ry, x_axis_rotation, large_arc as u8 as f32, sweep as u8 as f32, to.x, to.y],
+            ),
+            PathSegment::ClosePath => push('Z', &[]),
+        }
+    }
+    out
+}
+
+/// Formats a coordinate with at most three decimals and no trailing zeros.
+pub fn format_number(value: f32) -> String {
+    let s = format!("{:.3}", value);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" || s.is_empty() {
+        "0".to_string()
+    } else {
+        s.to_string()
+    }
+}
+
+/// Formats a number as briefly as SVG allows, rounded to `precision`
+/// decimals: `0.5` becomes `.5` and `-0.25` becomes `-.25`.
+pub fn compact_number(value: f32, precision: usize) -> String {
+    let s = format!("{:.*}", precision, value);
+    let s = if s.contains('.') { s.trim_end_matches('0').trim_end_matches('.') } else { &s };
+    match s {
+        "-0" | "" => "0".to_string(),
+        _ if s.starts_with("0.") => s[1..].to_string(),
+        _ if s.starts_with("-0.") => format!("-{}", &s[2..]),
+        _ => s.to_string(),
+    }
+}
+
+/// Serializes segments as compactly as possible at the given precision.
+///
+/// Each segment uses whichever of its absolute or relative forms (including
+/// `H`/`V` for axis-aligned lines) is shorter, repeated commands are left
+/// implicit and separators are dropped wherever the next number's sign or
+/// decimal point already delimits it.
+pub fn minify_path_data(segments: &[PathSegment], precision: usize) -> String {
+    let factor = 10f32.powi(precision as i32);
+    let round = |v: f32| (v * factor).round() / factor;
+
+    let mut out = String::new();
+    // The command a bare list of numbers would continue, and the last number written.
+    let mut implicit: Option<char> = None;
+    let mut last_number = String::new();
+    // Positions as a decoder will see them, so rounding errors don't accumulate.
+    let mut current = Point::new(0.0, 0.0);
+    let mut start = current;
+
+    for segment in segments {
+        let rel = |p: Point| Point::new(round(p.x - current.x), round(p.y - current.y));
+        let abs = |p: Point| Point::new(round(p.x), round(p.y));
+        let moved = |d: Point| Point::new(current.x + d.x, current.y + d.y);
+
+        // (command, numbers, decoded end point) for each equivalent form.
+        let candidates: Vec<(char, Vec<f32>, Point)> = match *segment {
+            PathSegment::MoveTo(p) => {
+                let (a, r) = (abs(p), rel(p));
+                vec![('M', vec![a.x, a.y], a), ('m', vec![r.x, r.y], moved(r))]
+            }
+            PathSegment::LineTo(p) => {
+                let (a, r) = (abs(p), rel(p));
+                let mut forms = vec![('L', vec![a.x, a.y], a), ('l', vec![r.x, r.y], moved(r))];
+                if r.y == 0.0 {
+                    forms.push(('H', vec![a.x], Point::new(a.x, current.y)));
+                    forms.push(('h', vec![r.x], moved(Point::new(r.x, 0.0))));
+                }
+                if r.x == 0.0 {
+                    forms.push(('V', vec![a.y], Point::new(current.x, a.y)));
+                    forms.push(('v', vec![r.y], moved(Point::new(0.0, r.y))));
+                }
+                forms
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                let (a1, a2, a) = (abs(c1), abs(c2), abs(p));
+                let (r1, r2, r) = (rel(c1), rel(c2), rel(p));
+                vec![
+                    ('C', vec![a1.x, a1.y, a2.x, a2.y, a.x, a.y], a),
+                    ('c', vec![r1.x, r1.y, r2.x, r2.y, r.x, r.y], moved(r)),
+                ]
+            }
+            PathSegment::QuadTo(c, p) => {
+                let (ac, a, rc, r) = (abs(c), abs(p), rel(c), rel(p));
+                vec![('Q', vec![ac.x, ac.y, a.x, a.y], a), ('q', vec![rc.x, rc.y, r.x, r.y], moved(r))]
+            }
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to } => {
+                let shape = [round(rx), round(ry), round(x_axis_rotation), large_arc as u8 as f32, sweep as u8 as f32];
+                let (a, r) = (abs(to), rel(to));
+                vec![
+                    ('A', [&shape[..], &[a.x, a.y]].concat(), a),
+                    ('a', [&shape[..], &[r.x, r.y]].concat(), moved(r)),
+                ]
+            }
+            PathSegment::ClosePath => vec![('z', Vec::new(), start)],
+        };
+
+        let encoded = candidates
+            .into_iter()
+            .map(|(command, values, end)| {
+                let mut text = String::new();
+                let mut previous = last_number.clone();
+                let numbers: Vec<String> = values.iter().map(|v| compact_number(*v, precision)).collect();
+                if implicit != Some(command) || numbers.is_empty() {
+                    text.push(command);
+                    previous.clear();
+                }
+                for number in &numbers {
+                    let delimited = number.starts_with('-')
+                        || (number.starts_with('.') && (previous.contains('.') || previous.contains('e')));
+                    if !previous.is_empty() && !delimited {
+                        text.push(' ');
+                    }
+                    text.push_str(number);
+                    previous.clone_from(number);
+                }
+                (text, command, previous, end)
+            })
+            .min_by_key(|(text, ..)| text.len());
+
+        if let Some((text, command, previous, end)) = encoded {
+            out.push_str(&text);
+            last_number = previous;
+            current = end;
+            implicit = match command {
+                'M' => Some('L'),
+                'm' => Some('l'),
+                'z' => None,
+                other => Some(other),
+            };
+            match segment {
+                PathSegment::MoveTo(_) => start = end,
+                PathSegment::ClosePath => last_number.clear(),
+                _ => {}
+            }
+        }
+    }
+    out
+}
+
+/// Replaces every elliptical arc with the cubic Béziers that approximate it,
+/// for consumers that only understand lines and curves.
+pub fn arcs_to_cubics(segments: &[PathSegment]) -> Vec<PathSegment> {
+    let mut out = Vec::with_capacity(segments.len());
+    let mut current = Point::new(0.0, 0.0);
+    let mut subpath_start = current;
+
+    for segment in segments {
+        match *segment {
+            PathSegment::ArcTo { rx, ry, x_axis_rotation, large_arc, sweep, to } => {
+                out.extend(arc_to_cubics(current, rx, ry, x_axis_rotation, large_arc, sweep, to));
+            }
+            other => out.push(other),
+        }
+        current = match *segment {
+            PathSegment::MoveTo(p) => {
+                subpath_start = p;
+                p
+            }
+            PathSegment::ClosePath => subpath_start,
+            other => end_point(&other).unwrap_or(current),
+        };
+    }
+    out
+}
+
+/// Returns the point a segment ends at, if it draws to one.
+pub fn end_point(segment: &PathSegment) -> Option<Point> {
+    match *segment {
+        PathSegment::MoveTo(p) | PathSegment::LineTo(p) => Some(p),
+        PathSegment::CubicTo(_, _, p) | PathSegment::QuadTo(_, p) => Some(p),
+        PathSegment::ArcTo { to, .. } => Some(to),
+        PathSegment::ClosePath => None,
+    }
+}
+
+/// An elliptical arc in center parameterization (SVG 1.1 appendix F.6.5).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CenterArc {
+    pub center: Point,
+    pub rx: f32,
+    pub ry: f32,
+    /// The x-axis rotation in radians.
+    pub phi: f32,
+    /// The start angle in radians.
+    pub start: f32,
+    /// The signed sweep in radians.
+    pub delta: f32,
+}
+
+impl CenterArc {
+    /// Converts an endpoint arc, returning `None` for arcs that draw nothing
+    /// or degenerate to a straight line.
+    pub fn new(from: Point, rx: f32, ry: f32, x_axis_rotation: f32, large_arc: bool, sweep: bool, to: Point) -> Option<Self> {
+        let (mut rx, mut ry) = (rx.abs(), ry.abs());
+        if from == to || rx == 0.0 || ry == 0.0 {
+            return None;
+        }
+
+        let phi = x_axis_rotation.to_radians();
+        let (sin_phi, cos_phi) = phi.sin_cos();
+
+        // Step 1: compute (x1', y1').
+        let dx = (from.x - to.x) / 2.0;
+        let dy = (from.y - to.y) / 2.0;
+        let x1p = cos_phi * dx + sin_phi * dy;
+        let y1p = -sin_phi * dx + cos_phi * dy;
+
+        // Scale up radii that are too small to span the endpoints.
+        let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+        if lambda > 1.0 {
+            let s = lambda.sqrt();
+            rx *= s;
+            ry *= s;
+        }
+
+        // Step 2: compute (cx', cy').
+        let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+        let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+        let mut coef = (num / den).max(0.0).sqrt();
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cxp = coef * rx * y1p / ry;
+        let cyp = -coef * ry * x1p / rx;
+
+        // Step 3: compute (cx, cy).
+        let cx = cos_phi * cxp - sin_phi * cyp + (from.x + to.x) / 2.0;
+        let cy = sin_phi * cxp + cos_phi * cyp + (from.y + to.y) / 2.0;
+
+        // Step 4: compute the start angle and sweep.
+        let angle = |ux: f32, uy: f32, vx: f32, vy: f32| {
+            let sign = if ux * vy - uy * vx < 0.0 { -1.0 } else { 1.0 };
+            let dot = (ux * vx + uy * vy) / ((ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt());
+            sign * dot.clamp(-1.0, 1.0).acos()
+        };
+        let ux = (x1p - cxp) / rx;
+        let uy = (y1p - cyp) / ry;
+        let vx = (-x1p - cxp) / rx;
+        let vy = (-y1p - cyp) / ry;
+        let start = angle(1.0, 0.0, ux, uy);
+        let mut delta = angle(ux, uy, vx, vy);
+        if !sweep && delta > 0.0 {
+            delta -= 2.0 * PI;
+        } else if sweep && delta < 0.0 {
+            delta += 2.0 * PI;
+        }
+
+        Some(Self { center: Point::new(cx, cy), rx, ry, phi, start, delta })
+    }
+
+    pub fn point_at(&self, t: f32) -> Point {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (sin_t, cos_t) = t.sin_cos();
+        Point::new(
+            self.center.x + self.rx * cos_phi * cos_t - self.ry * sin_phi * sin_t,
+            self.center.y + self.rx * sin_phi * cos_t + self.ry * cos_phi * sin_t,
+        )
+    }
+
+    fn derivative_at(&self, t: f32) -> Point {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (sin_t, cos_t) = t.sin_cos();
+        Point::new(
+            -self.rx * cos_phi * sin_t - self.ry * sin_phi * cos_t,
+            -self.rx * sin_phi * sin_t + self.ry * cos_phi * cos_t,
+        )
+    }
+
+    /// Returns whether the angle `t` lies on the arc.
+    pub fn contains_angle(&self, t: f32) -> bool {
+        let offset = (t - self.start).rem_euclid(2.0 * PI);
+        if self.delta >= 0.0 {
+            offset <= self.delta
+        } else {
+            offset == 0.0 || offset - 2.0 * PI >= self.delta
+        }
+    }
+}
+
+/// Converts one SVG arc to cubic Béziers using the endpoint-to-center
+/// conversion from the SVG specification (appendix F.6).
+fn arc_to_cubics(
+    from: Point,
+    rx: f32,
+    ry: f32,
+    x_axis_rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+) -> Vec<PathSegment> {
+    let Some(arc) = CenterArc::new(from, rx, ry, x_axis_rotation, large_arc, sweep, to) else {
+        return if from == to { Vec::new() } else { vec![PathSegment::LineTo(to)] };
+    };
+
+    // Split into pieces of at most 90 degrees.
+    let pieces = (arc.delta.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = arc.delta / pieces as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+    (0..pieces)
+        .map(|i| {
+            let t1 = arc.start + step * i as f32;
+            let t2 = t1 + step;
+            let (p1, p2) = (arc.point_at(t1), arc.point_at(t2));
+            let (d1, d2) = (arc.derivative_at(t1), arc.derivative_at(t2));
+            let end = if i + 1 == pieces { to } else { p2 };
+            PathSegment::CubicTo(
+                Point::new(p1.x + k * d1.x, p1.y + k * d1.y),
+                Point::new(p2.x - k * d2.x, p2.y - k * d2.y),
+                end,
+            )
+        })
+        .collect()
+}
+
+fn reflect(control: Option<Point>, around: Point) -> Point {
+    match control {
+        Some(c) => Point::new(2.0 * around.x - c.x, 2.0 * around.y - c.y),
+        None => around,
+    }
+}
+
+struct Lexer<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Lexer<'_> {
+    fn at_end(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len() && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',') {
+            self.pos += 1;
+        }
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        let c = *self.bytes.get(self.pos)?;
+        if c.is_ascii_alphabetic() {
+            self.pos += 1;
+            Some(c)
+        } else {
+            None
+        }
+    }
+
+    fn number(&mut self) -> Result<f32, IconEngineError> {
+        self.skip_separators();
+        let start = self.pos;
+        let mut seen_dot = false;
+        let mut seen_digit = false;
+
+        if matches!(self.bytes.get(self.pos), Some(b'+' | b'-')) {
+            self.pos += 1;
+        }
+        while let Some(&c) = self.bytes.get(self.pos) {
+            match c {
+                b'0'..=b'9' => seen_digit = true,
+                // A second dot starts the next number, as in "0.5.5".
+                b'.' if !seen_dot => seen_dot = true,
+                _ => break,
+            }
+            self.pos += 1;
+        }
+        if seen_digit && matches!(self.bytes.get(self.pos), Some(b'e' | b'E')) {
+            let save = self.pos;
+            self.pos += 1;
+            if matches!(self.bytes.get(self.pos), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            if matches!(self.bytes.get(self.pos), Some(b'0'..=b'9')) {
+                while matches!(self.bytes.get(self.pos), Some(b'0'..=b'9')) {
+                    self.pos += 1;
+                }
+            } else {
+                self.pos = save;
+            }
+        }
+
+        if !seen_digit {
+            self.pos = start;
+            return Err(self.error("Expected a number"));
+        }
+        std::str::from_utf8(&self.bytes[start..self.pos])
+            .ok()
+            .and_then(|s| s.parse::<f32>().ok())
+            .ok_or_else(|| self.error("Invalid number"))
+    }
+
+    fn flag(&mut self) -> Result<bool, IconEngineError> {
+        self.skip_separators();
+        match self.bytes.get(self.pos) {
+            Some(b'0') => {
+                self.pos += 1;
+                Ok(false)
+            }
+            Some(b'1') => {
+                self.pos += 1;
+                Ok(true)
+            }
+            _ => Err(self.error("Expected an arc flag (0 or 1)")),
+        }
+    }
+
+    fn error(&self, message: &str) -> IconEngineError {
+        IconEngineError::SvgParsingError(format!("{} at offset {} of path data", message, self.pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_relative_and_shorthand_commands() {
+        let segments = parse_path("m10 10h5v5l-5 0zM0 0c1 1 2 2 3 3s4 4 5 5").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                PathSegment::MoveTo(Point::new(10.0, 10.0)),
+                PathSegment::LineTo(Point::new(15.0, 10.0)),
+                PathSegment::LineTo(Point::new(15.0, 15.0)),
+                PathSegment::LineTo(Point::new(10.0, 15.0)),
+                PathSegment::ClosePath,
+                PathSegment::MoveTo(Point::new(0.0, 0.0)),
+                PathSegment::CubicTo(Point::new(1.0, 1.0), Point::new(2.0, 2.0), Point::new(3.0, 3.0)),
+                PathSegment::CubicTo(Point::new(4.0, 4.0), Point::new(7.0, 7.0), Point::new(8.0, 8.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_compact_numbers_and_arc_flags() {
+        let segments = parse_path("M.5.5-1e1-2A5 5 0 01 10 10").unwrap();
+        assert_eq!(segments[0], PathSegment::MoveTo(Point::new(0.5, 0.5)));
+        assert_eq!(segments[1], PathSegment::LineTo(Point::new(-10.0, -2.0)));
+        assert!(matches!(
+            segments[2],
+            PathSegment::ArcTo { large_arc: false, sweep: true, to: Point { x: 10.0, y: 10.0 }, .. }
+        ));
+    }
+
+    #[test]
+    fn rejects_malformed_data() {
+        assert!(parse_path("10 10").is_err());
+        assert!(parse_path("M10").is_err());
+        assert!(parse_path("M0 0X1").is_err());
+    }
+
+    #[test]
+    fn round_trips_through_path_data() {
+        let d = "M12 2L2 22H22Z";
+        let serialized = to_path_data(&parse_path(d).unwrap());
+        assert_eq!(serialized, "M12 2L2 22L22 22Z");
+        assert_eq!(to_path_data(&[PathSegment::MoveTo(Point::new(-0.0001, 1.25))]), "M0 1.25");
+    }
+
+    #[test]
+    fn minifies_path_data() {
+        let segments = parse_path("M12 2L2 22L22 22L12 2Z M 0.5 0.5 L 0.25 -0.75 C 1.12345 1 2 2 3 3").unwrap();
+        assert_eq!(minify_path_data(&segments, 3), "M12 2 2 22H22L12 2zM.5.5.25-.75C1.123 1 2 2 3 3");
+        assert_eq!(minify_path_data(&segments[..2], 0), "M12 2 2 22");
+
+        // Relative forms stay anchored to the rounded positions.
+        let segments = parse_path("M100.44 100.44l0.44 0.44 0.44 0.44").unwrap();
+        assert_eq!(minify_path_data(&segments, 0), "M100 100l1 1h0");
+        assert_eq!(compact_number(-0.0004, 3), "0");
+        assert_eq!(compact_number(1.5, 0), "2");
+    }
+
+    #[test]
+    fn transforms_coordinates_and_arcs() {
+        let transform = Transform::scale(2.0, 2.0).then(Transform::translate(10.0, 5.0));
+        let segments = parse_path("M0 0L1 1A1 1 0 0 1 3 1Z").unwrap();
+        assert_eq!(to_path_data(&transform_path(&segments, &transform)), "M10 5L12 7A2 2 0 0 1 16 7Z");
+
+        // Stretching turns a circle into an axis-aligned ellipse; mirroring flips the sweep.
+        let stretched = transform_path(&segments, &Transform::scale(3.0, -1.0));
+        let PathSegment::ArcTo { rx, ry, x_axis_rotation, sweep, to, .. } = stretched[2] else { panic!("expected an arc") };
+        assert!((rx - 3.0).abs() < 1e-4 && (ry - 1.0).abs() < 1e-4, "{} {}", rx, ry);
+        assert!(x_axis_rotation.abs() < 1e-3 || (x_axis_rotation.abs() - 180.0).abs() < 1e-3);
+        assert!(!sweep);
+        assert_eq!(to, Point::new(9.0, -1.0));
+
+        // A rotated ellipse under a rotation keeps its radii.
+        let rotation = Transform { a: 0.0, b: 1.0, c: -1.0, d: 0.0, e: 0.0, f: 0.0 };
+        let arc = [PathSegment::ArcTo { rx: 4.0, ry: 2.0, x_axis_rotation: 30.0, large_arc: true, sweep: true, to: Point::new(1.0, 0.0) }];
+        let PathSegment::ArcTo { rx, ry, x_axis_rotation, .. } = transform_path(&arc, &rotation)[0] else { panic!("expected an arc") };
+        assert!((rx - 4.0).abs() < 1e-4 && (ry - 2.0).abs() < 1e-4);
+        assert!((x_axis_rotation - 120.0).abs() < 1e-3, "{}", x_axis_rotation);
+    }
+
+    #[test]
+    fn parses_transform_lists() {
+        let transform = Transform::parse("translate(10, 0) scale(2)").unwrap();
+        assert_eq!(transform.apply(Point::new(1.0, 1.0)), Point::new(12.0, 2.0));
+        let rotated = Transform::parse("rotate(90 12 12)").unwrap().apply(Point::new(12.0, 2.0));
+        assert!((rotated.x - 22.0).abs() < 1e-4 && (rotated.y - 12.0).abs() < 1e-4, "{:?}", rotated);
+        assert!(Transform::parse("scale(1, 2, 3)").is_none());
+        assert_eq!(Transform::parse(""), Some(Transform::IDENTITY));
+    }
+
+    #[test]
+    fn arcs_become_cubics_ending_at_the_arc_end() {
+        let segments = parse_path("M0 10A10 10 0 0 1 20 10").unwrap();
+        let cubics = arcs_to_cubics(&segments);
+        assert_eq!(cubics.len(), 3);
+        let PathSegment::CubicTo(_, _, mid) = cubics[1] else { panic!("expected a cubic") };
+        assert!((mid.x - 10.0).abs() < 1e-3 && mid.y.abs() < 1e-3, "{:?}", mid);
+        assert_eq!(end_point(&cubics[2]), Some(Point::new(20.0, 10.0)));
+    }
+}
@@ -0,0 +1,233 @@
+use crate::color::parse_with_alpha;
+use crate::path_data::format_number;
+use crate::{CustomStyles, IconEngineError};
+
+const PATTERN_ID: &str = "base-pattern";
+const NOISE_FILTER_ID: &str = "base-noise";
+const CLIP_ID: &str = "base-clip";
+/// The class on whatever paints the pattern color, which the adaptive theme
+/// restyles through `fill` and `flood-color`.
+pub(crate) const PATTERN_FILL_CLASS: &str = "base-pattern-fill";
+
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// What a [`BasePattern`] draws.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternKind {
+    /// Film-like grain from `feTurbulence`.
+    Noise,
+    /// A grid of dots.
+    Dots,
+    /// Diagonal stripes.
+    Stripes,
+    Checkerboard,
+    /// A PNG tiled over the base, embedded as a data URI.
+    Image(Vec<u8>),
+}
+
+/// A texture drawn over the base's fill or gradient, clipped to its shape.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BasePattern {
+    pub kind: PatternKind,
+    /// The color of the grain, dots, stripes or checks; unused by images.
+    pub color: String,
+    /// The size of one tile in canvas units: the dot spacing, the stripe
+    /// period, a check's side or an image tile's side. For noise, the
+    /// typical grain size.
+    pub scale: f32,
+    pub opacity: f32,
+}
+
+impl BasePattern {
+    /// A pattern with the kind's default scale and opacity, in black.
+    pub fn new(kind: PatternKind) -> Self {
+        let (scale, opacity) = match kind {
+            PatternKind::Noise => (2.0, 0.25),
+            PatternKind::Dots | PatternKind::Stripes => (12.0, 0.12),
+            PatternKind::Checkerboard => (16.0, 0.08),
+            PatternKind::Image(_) => (128.0, 1.0),
+        };
+        Self { kind, color: "#000000".to_string(), scale, opacity }
+    }
+
+    /// An image pattern from PNG data.
+    pub fn image(png: Vec<u8>) -> Result<Self, IconEngineError> {
+        if !png.starts_with(PNG_SIGNATURE) {
+            return Err(IconEngineError::InvalidInput("Pattern images must be PNG files".to_string()));
+        }
+        Ok(Self::new(PatternKind::Image(png)))
+    }
+}
+
+/// Fails for styles with a base pattern, for outputs that cannot draw it.
+pub(crate) fn reject_pattern(styles: &CustomStyles, output: &str) -> Result<(), IconEngineError> {
+    if styles.pattern.is_none() {
+        return Ok(());
+    }
+    Err(IconEngineError::InvalidInput(format!("Base patterns are not supported in {} output", output)))
+}
+
+/// Returns the `(definitions, shape)` drawing `CustomStyles::pattern` over
+/// the base, if one is set.
+pub(crate) fn pattern_markup(styles: &CustomStyles) -> Result<Option<(String, String)>, IconEngineError> {
+    let Some(pattern) = &styles.pattern else {
+        return Ok(None);
+    };
+    let scale = pattern.scale;
+    if scale.is_nan() || scale <= 0.0 {
+        return Err(IconEngineError::InvalidInput(format!("Pattern scale must be positive, got {}", scale)));
+    }
+    let (width, height, radius) = (styles.width, styles.height, format_number(styles.corner_radius));
+    let opacity = if pattern.opacity < 1.0 {
+        format!(r#" opacity="{}""#, format_number(pattern.opacity.max(0.0)))
+    } else {
+        String::new()
+    };
+
+    // Noise is a filter rather than a paint server, so its rectangle is
+    // clipped to the rounded base instead of having corners of its own.
+    if pattern.kind == PatternKind::Noise {
+        if parse_with_alpha(&pattern.color).is_none() {
+            return Err(IconEngineError::InvalidInput(format!("Unsupported pattern color: {}", pattern.color)));
+        }
+        // The noise's red channel, stretched for contrast, becomes the
+        // alpha of a flood of the pattern color.
+        let defs = format!(
+            r#"<filter id="{filter}" x="0" y="0" width="100%" height="100%"><feTurbulence type="fractalNoise" baseFrequency="{frequency}" numOctaves="3" stitchTiles="stitch"/><feColorMatrix type="matrix" values="0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 3 0 0 0 -1" result="grain"/><feFlood class="{class}" flood-color="{color}"/><feComposite in2="grain" operator="in"/></filter><clipPath id="{clip}"><rect width="{w}" height="{h}" rx="{radius}" ry="{radius}"/></clipPath>"#,
+            filter = NOISE_FILTER_ID,
+            frequency = format_number(1.0 / scale),
+            class = PATTERN_FILL_CLASS,
+            color = pattern.color,
+            clip = CLIP_ID,
+            w = width,
+            h = height,
+            radius = radius
+        );
+        let shape = format!(
+            r#"<rect class="base-pattern" width="{}" height="{}" filter="url(#{})" clip-path="url(#{})"{}/>"#,
+            width, height, NOISE_FILTER_ID, CLIP_ID, opacity
+        );
+        return Ok(Some((defs, shape)));
+    }
+
+    let s = format_number(scale);
+    let half = format_number(scale / 2.0);
+    let (tile, attributes, content) = match &pattern.kind {
+        PatternKind::Dots => (
+            s.clone(),
+            String::new(),
+            format!(
+                r#"<circle class="{class}" cx="{half}" cy="{half}" r="{}" fill="{}"/>"#,
+                format_number(scale / 5.0),
+                pattern.color,
+                class = PATTERN_FILL_CLASS,
+                half = half
+            ),
+        ),
+        PatternKind::Stripes => (
+            s.clone(),
+            r#" patternTransform="rotate(45)""#.to_string(),
+            format!(
+                r#"<rect class="{}" width="{}" height="{}" fill="{}"/>"#,
+                PATTERN_FILL_CLASS, half, s, pattern.color
+            ),
+        ),
+        PatternKind::Checkerboard => (
+            format_number(scale * 2.0),
+            String::new(),
+            format!(
+                r#"<rect class="{class}" width="{s}" height="{s}" fill="{c}"/><rect class="{class}" x="{s}" y="{s}" width="{s}" height="{s}" fill="{c}"/>"#,
+                class = PATTERN_FILL_CLASS,
+                s = s,
+                c = pattern.color
+            ),
+        ),
+        PatternKind::Image(png) => (
+            s.clone(),
+            String::new(),
+            format!(
+                r#"<image href="data:image/png;base64,{}" width="{s}" height="{s}" preserveAspectRatio="xMidYMid slice"/>"#,
+                base64(png),
+                s = s
+            ),
+        ),
+        PatternKind::Noise => unreachable!("noise is drawn with a filter"),
+    };
+    let defs = format!(
+        r#"<pattern id="{}" patternUnits="userSpaceOnUse" width="{t}" height="{t}"{}>{}</pattern>"#,
+        PATTERN_ID,
+        attributes,
+        content,
+        t = tile
+    );
+    let shape = format!(
+        r#"<rect class="base-pattern" width="{}" height="{}" rx="{r}" ry="{r}" fill="url(#{})"{}/>"#,
+        width,
+        height,
+        PATTERN_ID,
+        opacity,
+        r = radius
+    );
+    Ok(Some((defs, shape)))
+}
+
+/// Encodes bytes as standard, padded base64.
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, StylePreset};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn draws_patterns_over_the_base() {
+        let dots = BasePattern { color: "#ffffff".to_string(), ..BasePattern::new(PatternKind::Dots) };
+        let styles = CustomStyles { pattern: Some(dots), ..CustomStyles::default() };
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+        roxmltree::Document::parse(&svg).unwrap();
+        assert!(svg.contains(r##"<pattern id="base-pattern" patternUnits="userSpaceOnUse" width="12" height="12"><circle class="base-pattern-fill" cx="6" cy="6" r="2.4" fill="#ffffff"/></pattern>"##));
+        // The pattern sits between the base and the glyph.
+        let base = svg.find(r#"class="icon-base""#).unwrap();
+        let pattern = svg.find(r#"<rect class="base-pattern" width="128" height="128" rx="25" ry="25" fill="url(#base-pattern)" opacity="0.12"/>"#).unwrap();
+        assert!(base < pattern && pattern < svg.find("icon-fg").unwrap());
+
+        let noise = CustomStyles { pattern: Some(BasePattern::new(PatternKind::Noise)), ..CustomStyles::default() };
+        let svg = generate_icon(TEST_SVG, StylePreset::Glassmorphism, &noise).unwrap();
+        assert!(svg.contains(r#"<feTurbulence type="fractalNoise" baseFrequency="0.5""#));
+        assert!(svg.contains(r#"filter="url(#base-noise)" clip-path="url(#base-clip)" opacity="0.25"/>"#));
+    }
+
+    #[test]
+    fn embeds_png_images() {
+        assert!(BasePattern::image(b"GIF89a".to_vec()).is_err());
+        let png = [PNG_SIGNATURE, b"rest"].concat();
+        let styles = CustomStyles { pattern: Some(BasePattern::image(png).unwrap()), ..CustomStyles::default() };
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+        assert!(svg.contains(r#"<image href="data:image/png;base64,iVBORw0KGgpyZXN0" width="128" height="128""#));
+        assert!(!svg.contains("opacity=\"1\""));
+
+        assert_eq!(base64(b"Ma"), "TWE=");
+        assert_eq!(base64(b"M"), "TQ==");
+        let zero = CustomStyles {
+            pattern: Some(BasePattern { scale: 0.0, ..BasePattern::new(PatternKind::Stripes) }),
+            ..CustomStyles::default()
+        };
+        assert!(generate_icon(TEST_SVG, StylePreset::Neumorphism, &zero).is_err());
+    }
+}
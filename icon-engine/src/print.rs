@@ -0,0 +1,578 @@
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::color::parse_with_alpha;
+use crate::path_data::{self, format_number, to_path_data, PathSegment, Point};
+use crate::raster::render;
+use crate::style_generator::{
+    base_fill, base_fill_opacity, gradient_points, shadow_colors, shadow_geometry, GLASS_STROKE, GLASS_STROKE_WIDTH,
+};
+use crate::{contrast, glyph_stack, overlay, pattern, states, svg_processor, CustomStyles, IconEngineError, StylePreset};
+
+/// Pixels per canvas unit of the rasterized shadow masks.
+const SHADOW_RESOLUTION: f32 = 2.0;
+
+/// An RGB color with alpha, all in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Color {
+    r: f32,
+    g: f32,
+    b: f32,
+    a: f32,
+}
+
+impl Color {
+    fn parse(color: &str) -> Result<Self, IconEngineError> {
+        let (rgb, a) = parse_with_alpha(color)
+            .ok_or_else(|| IconEngineError::InvalidInput(format!("Unsupported color for print output: {}", color)))?;
+        Ok(Self { r: rgb.r as f32 / 255.0, g: rgb.g as f32 / 255.0, b: rgb.b as f32 / 255.0, a })
+    }
+
+    /// Composites the color over white paper, for formats without transparency.
+    fn over_white(self, opacity: f32) -> Self {
+        let a = self.a * opacity;
+        let blend = |c: f32| c * a + (1.0 - a);
+        Self { r: blend(self.r), g: blend(self.g), b: blend(self.b), a: 1.0 }
+    }
+
+    fn components(self) -> String {
+        format!("{} {} {}", format_number(self.r), format_number(self.g), format_number(self.b))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Paint {
+    Solid(Color),
+    /// An axial gradient between two points in canvas units.
+    Linear { start: Point, end: Point, from: Color, to: Color },
+}
+
+#[derive(Debug, Clone)]
+enum Layer {
+    /// A blurred, offset copy of the base shape in a single color.
+    Shadow { dx: f32, dy: f32, blur: f32, color: Color },
+    Fill { path: Vec<PathSegment>, paint: Paint, opacity: f32 },
+    Stroke { path: Vec<PathSegment>, color: Color, width: f32 },
+}
+
+/// A styled icon as the flat list of layers `generate_icon` would draw, in
+/// canvas units with the y axis pointing down. Paths only contain moves,
+/// lines, cubics and close commands.
+#[derive(Debug, Clone)]
+struct Scene {
+    width: f32,
+    height: f32,
+    base: Vec<PathSegment>,
+    layers: Vec<Layer>,
+}
+
+/// Rewrites a path with arcs and quadratics as cubics, mapping every point
+/// through `map`.
+fn cubic_outline(segments: &[PathSegment], map: impl Fn(Point) -> Point) -> Vec<PathSegment> {
+    let mut out = Vec::with_capacity(segments.len());
+    let mut current = Point::new(0.0, 0.0);
+    let mut start = current;
+    for segment in path_data::arcs_to_cubics(segments) {
+        out.push(match segment {
+            PathSegment::MoveTo(p) => {
+                start = map(p);
+                current = start;
+                PathSegment::MoveTo(current)
+            }
+            PathSegment::LineTo(p) => {
+                current = map(p);
+                PathSegment::LineTo(current)
+            }
+            PathSegment::CubicTo(c1, c2, p) => {
+                current = map(p);
+                PathSegment::CubicTo(map(c1), map(c2), current)
+            }
+            PathSegment::QuadTo(c, p) => {
+                let (c, p) = (map(c), map(p));
+                let lerp = |a: Point, b: Point| Point::new(a.x + (b.x - a.x) * 2.0 / 3.0, a.y + (b.y - a.y) * 2.0 / 3.0);
+                let segment = PathSegment::CubicTo(lerp(current, c), lerp(p, c), p);
+                current = p;
+                segment
+            }
+            PathSegment::ClosePath => {
+                current = start;
+                PathSegment::ClosePath
+            }
+            // Arcs were converted to cubics above.
+            PathSegment::ArcTo { .. } => continue,
+        });
+    }
+    out
+}
+
+fn build_scene(icon_data: &str, preset: StylePreset, styles: &CustomStyles) -> Result<Scene, IconEngineError> {
+    overlay::reject_overlays(styles, "print")?;
+    glyph_stack::reject_glyph_layers(styles, "print")?;
+    pattern::reject_pattern(styles, "print")?;
+    states::reject_states(styles, "print")?;
+    let icon = svg_processor::parse_svg(icon_data)?;
+    let fit = svg_processor::placement_matrix(&icon, styles)?;
+    let (width, height) = (styles.width as f32, styles.height as f32);
+
+    let base = cubic_outline(&path_data::rounded_rect(width, height, styles.corner_radius), |p| p);
+    let glyph = cubic_outline(&path_data::parse_path(&icon.path_data)?, |p| fit.apply(p));
+
+    let mut layers = Vec::new();
+    if preset == StylePreset::Neumorphism && styles.shadows {
+        let (offset, blur) = shadow_geometry(styles);
+        let shadows = shadow_colors(styles);
+        layers.push(Layer::Shadow { dx: -offset, dy: -offset, blur, color: Color::parse(&shadows.light)? });
+        layers.push(Layer::Shadow { dx: offset, dy: offset, blur, color: Color::parse(&shadows.dark)? });
+    }
+
+    let paint = match &styles.gradient {
+        Some(gradient) => {
+            let ((x1, y1), (x2, y2)) = gradient_points(gradient);
+            Paint::Linear {
+                start: Point::new(x1 * width, y1 * height),
+                end: Point::new(x2 * width, y2 * height),
+                from: Color::parse(&gradient.start_color)?,
+                to: Color::parse(&gradient.stop_color)?,
+            }
+        }
+        None => Paint::Solid(Color::parse(&base_fill(styles, preset))?),
+    };
+    layers.push(Layer::Fill { path: base.clone(), paint, opacity: base_fill_opacity(preset) });
+    if preset != StylePreset::Neumorphism {
+        layers.push(Layer::Stroke { path: base.clone(), color: Color::parse(GLASS_STROKE)?, width: GLASS_STROKE_WIDTH });
+    }
+
+    let icon_color = Color::parse(&contrast::resolve_icon_color(styles, preset))?;
+    layers.push(Layer::Fill { path: glyph, paint: Paint::Solid(icon_color), opacity: 1.0 });
+
+    Ok(Scene { width, height, base, layers })
+}
+
+/// Writes path construction operators shared by PDF and PostScript, with
+/// the operator names of the target language.
+fn path_operators(path: &[PathSegment], ops: [&str; 4]) -> String {
+    let [move_to, line_to, curve_to, close] = ops;
+    let point = |p: Point| format!("{} {}", format_number(p.x), format_number(p.y));
+    path.iter()
+        .map(|segment| match *segment {
+            PathSegment::MoveTo(p) => format!("{} {}\n", point(p), move_to),
+            PathSegment::LineTo(p) => format!("{} {}\n", point(p), line_to),
+            PathSegment::CubicTo(c1, c2, p) => format!("{} {} {} {}\n", point(c1), point(c2), point(p), curve_to),
+            PathSegment::ClosePath => format!("{}\n", close),
+            _ => String::new(),
+        })
+        .collect()
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>, IconEngineError> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(data).map_err(|e| IconEngineError::RenderError(e.to_string()))?;
+    encoder.finish().map_err(|e| IconEngineError::RenderError(e.to_string()))
+}
+
+/// A rasterized shadow alpha channel covering the canvas plus `margin` units
+/// on every side.
+struct ShadowMask {
+    alpha: Vec<u8>,
+    width: u32,
+    height: u32,
+    margin: f32,
+}
+
+/// Renders the blurred base shape and returns its alpha channel, scaled by
+/// `alpha`.
+fn shadow_mask(scene: &Scene, blur: f32, alpha: f32) -> Result<ShadowMask, IconEngineError> {
+    // A Gaussian blur fades out within three standard deviations.
+    let margin = (blur * 3.0).ceil();
+    let (w, h) = (scene.width + 2.0 * margin, scene.height + 2.0 * margin);
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="{m} {m} {w} {h}"><filter id="blur" filterUnits="userSpaceOnUse" x="{m}" y="{m}" width="{w}" height="{h}"><feGaussianBlur stdDeviation="{blur}"/></filter><path d="{d}" filter="url(#blur)"/></svg>"#,
+        w = w,
+        h = h,
+        m = -margin,
+        blur = blur,
+        d = to_path_data(&scene.base)
+    );
+    let image = render(&svg, None, SHADOW_RESOLUTION)?;
+    Ok(ShadowMask {
+        alpha: image.data.chunks_exact(4).map(|p| (p[3] as f32 * alpha).round() as u8).collect(),
+        width: image.width,
+        height: image.height,
+        margin,
+    })
+}
+
+/// A PDF file under construction; objects are numbered from 1 in the order
+/// they are added.
+#[derive(Default)]
+struct PdfWriter {
+    objects: Vec<Vec<u8>>,
+}
+
+impl PdfWriter {
+    fn add(&mut self, object: Vec<u8>) -> usize {
+        self.objects.push(object);
+        self.objects.len()
+    }
+
+    fn add_stream(&mut self, dictionary: &str, data: &[u8]) -> Result<usize, IconEngineError> {
+        let compressed = deflate(data)?;
+        let mut object =
+            format!("<< {} /Filter /FlateDecode /Length {} >>\nstream\n", dictionary, compressed.len()).into_bytes();
+        object.extend_from_slice(&compressed);
+        object.extend_from_slice(b"\nendstream");
+        Ok(self.add(object))
+    }
+
+    /// Reserves an object number to be filled in with [`PdfWriter::set`].
+    fn reserve(&mut self) -> usize {
+        self.add(Vec::new())
+    }
+
+    fn set(&mut self, id: usize, object: Vec<u8>) {
+        self.objects[id - 1] = object;
+    }
+
+    fn finish(self, root: usize) -> Vec<u8> {
+        // The binary comment marks the file as binary for transfer tools.
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, object) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(object);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                self.objects.len() + 1,
+                root,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+/// Writes a single-page PDF of the styled icon with a page the size of the
+/// canvas in points.
+///
+/// The base, gradient and glyph are vector paths. The neumorphism shadows
+/// are SVG filters, so each is approximated by a rasterized, blurred copy of
+/// the base used as the soft mask of a solid-color image. Overlays, glyph
+/// layers, base patterns and the focused, pressed and disabled states are
+/// not supported.
+pub fn to_pdf(icon_data: &str, preset: StylePreset, styles: &CustomStyles) -> Result<Vec<u8>, IconEngineError> {
+    let scene = build_scene(icon_data, preset, styles)?;
+    let mut pdf = PdfWriter::default();
+    let catalog = pdf.reserve();
+    let pages = pdf.reserve();
+    let page = pdf.reserve();
+
+    let mut ext_states: BTreeMap<String, String> = BTreeMap::new();
+    let mut shadings = Vec::new();
+    let mut images = Vec::new();
+    let mut alpha_state = |key: &str, alpha: f32| {
+        let value = format_number(alpha);
+        let name = format!("GS{}", ext_states.len());
+        ext_states.entry(format!("<< /{} {} >>", key, value)).or_insert(name).clone()
+    };
+
+    // Flip the y axis so canvas coordinates can be used as-is.
+    let mut content = format!("1 0 0 -1 0 {} cm\n", format_number(scene.height));
+    let ops = ["m", "l", "c", "h"];
+    for layer in &scene.layers {
+        match layer {
+            Layer::Shadow { dx, dy, blur, color } => {
+                let mask = shadow_mask(&scene, *blur, color.a)?;
+                let smask = pdf.add_stream(
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /DeviceGray /BitsPerComponent 8",
+                        mask.width, mask.height
+                    ),
+                    &mask.alpha,
+                )?;
+                let rgb = [color.r, color.g, color.b].map(|c| (c * 255.0).round() as u8);
+                let image = pdf.add_stream(
+                    &format!(
+                        "/Type /XObject /Subtype /Image /Width 1 /Height 1 /ColorSpace /DeviceRGB /BitsPerComponent 8 /SMask {} 0 R",
+                        smask
+                    ),
+                    &rgb,
+                )?;
+                let name = format!("Im{}", images.len());
+                images.push((name.clone(), image));
+                let (width, height) = (scene.width + 2.0 * mask.margin, scene.height + 2.0 * mask.margin);
+                // Images fill the unit square bottom-up, so flip them back.
+                content.push_str(&format!(
+                    "q {} 0 0 {} {} {} cm /{} Do Q\n",
+                    format_number(width),
+                    format_number(-height),
+                    format_number(dx - mask.margin),
+                    format_number(dy - mask.margin + height),
+                    name
+                ));
+            }
+            Layer::Fill { path, paint, opacity } => {
+                content.push_str("q\n");
+                match paint {
+                    Paint::Solid(color) => {
+                        let alpha = color.a * opacity;
+                        if alpha < 1.0 {
+                            content.push_str(&format!("/{} gs\n", alpha_state("ca", alpha)));
+                        }
+                        content.push_str(&format!("{} rg\n", color.components()));
+                        content.push_str(&path_operators(path, ops));
+                        content.push_str("f\n");
+                    }
+                    Paint::Linear { start, end, from, to } => {
+                        if *opacity < 1.0 {
+                            content.push_str(&format!("/{} gs\n", alpha_state("ca", *opacity)));
+                        }
+                        let shading = pdf.add(
+                            format!(
+                                "<< /ShadingType 2 /ColorSpace /DeviceRGB /Coords [{} {} {} {}] /Function << /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >> /Extend [true true] >>",
+                                format_number(start.x),
+                                format_number(start.y),
+                                format_number(end.x),
+                                format_number(end.y),
+                                from.components(),
+                                to.components()
+                            )
+                            .into_bytes(),
+                        );
+                        let name = format!("Sh{}", shadings.len());
+                        shadings.push((name.clone(), shading));
+                        content.push_str(&path_operators(path, ops));
+                        content.push_str(&format!("W n\n/{} sh\n", name));
+                    }
+                }
+                content.push_str("Q\n");
+            }
+            Layer::Stroke { path, color, width } => {
+                content.push_str("q\n");
+                if color.a < 1.0 {
+                    content.push_str(&format!("/{} gs\n", alpha_state("CA", color.a)));
+                }
+                content.push_str(&format!("{} RG\n{} w\n", color.components(), format_number(*width)));
+                content.push_str(&path_operators(path, ops));
+                content.push_str("S\nQ\n");
+            }
+        }
+    }
+    let contents = pdf.add_stream("", content.as_bytes())?;
+
+    let dictionary = |entries: Vec<(String, String)>| {
+        entries.iter().map(|(name, value)| format!("/{} {}", name, value)).collect::<Vec<_>>().join(" ")
+    };
+    let resources = format!(
+        "<< /ExtGState << {} >> /Shading << {} >> /XObject << {} >> >>",
+        dictionary(ext_states.into_iter().map(|(value, name)| (name, value)).collect()),
+        dictionary(shadings.into_iter().map(|(name, id)| (name, format!("{} 0 R", id))).collect()),
+        dictionary(images.into_iter().map(|(name, id)| (name, format!("{} 0 R", id))).collect())
+    );
+
+    pdf.set(catalog, format!("<< /Type /Catalog /Pages {} 0 R >>", pages).into_bytes());
+    pdf.set(pages, format!("<< /Type /Pages /Kids [{} 0 R] /Count 1 >>", page).into_bytes());
+    pdf.set(
+        page,
+        format!(
+            "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources {} /Contents {} 0 R >>",
+            pages,
+            format_number(scene.width),
+            format_number(scene.height),
+            resources,
+            contents
+        )
+        .into_bytes(),
+    );
+    Ok(pdf.finish(catalog))
+}
+
+/// Writes an Encapsulated PostScript (level 3) file of the styled icon.
+///
+/// PostScript has no transparency, so translucent fills are composited over
+/// white and the neumorphism shadows are left out. Overlays, glyph layers,
+/// base patterns and the focused, pressed and disabled states are not
+/// supported.
+pub fn to_eps(icon_data: &str, preset: StylePreset, styles: &CustomStyles) -> Result<Vec<u8>, IconEngineError> {
+    let scene = build_scene(icon_data, preset, styles)?;
+    let (width, height) = (format_number(scene.width), format_number(scene.height));
+    let mut eps = format!(
+        "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 {w} {h}\n%%HiResBoundingBox: 0 0 {w} {h}\n%%Creator: icon-engine\n%%LanguageLevel: 3\n%%Pages: 1\n%%EndComments\n%%Page: 1 1\ngsave\n[1 0 0 -1 0 {h}] concat\n",
+        w = width,
+        h = height
+    );
+    let ops = ["moveto", "lineto", "curveto", "closepath"];
+
+    for layer in &scene.layers {
+        match layer {
+            Layer::Shadow { .. } => {}
+            Layer::Fill { path, paint, opacity } => {
+                eps.push_str("newpath\n");
+                eps.push_str(&path_operators(path, ops));
+                match paint {
+                    Paint::Solid(color) => {
+                        eps.push_str(&format!("{} setrgbcolor fill\n", color.over_white(*opacity).components()));
+                    }
+                    Paint::Linear { start, end, from, to } => {
+                        eps.push_str(&format!(
+                            "gsave clip\n<< /ShadingType 2 /ColorSpace /DeviceRGB /Coords [{} {} {} {}] /Function << /FunctionType 2 /Domain [0 1] /C0 [{}] /C1 [{}] /N 1 >> /Extend [true true] >> shfill\ngrestore\n",
+                            format_number(start.x),
+                            format_number(start.y),
+                            format_number(end.x),
+                            format_number(end.y),
+                            from.over_white(*opacity).components(),
+                            to.over_white(*opacity).components()
+                        ));
+                    }
+                }
+            }
+            Layer::Stroke { path, color, width } => {
+                eps.push_str("newpath\n");
+                eps.push_str(&path_operators(path, ops));
+                eps.push_str(&format!(
+                    "{} setrgbcolor {} setlinewidth stroke\n",
+                    color.over_white(1.0).components(),
+                    format_number(*width)
+                ));
+            }
+        }
+    }
+
+    eps.push_str("grestore\nshowpage\n%%EOF\n");
+    Ok(eps.into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::IconState;
+    use crate::Gradient;
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    fn inflate(data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        std::io::Read::read_to_end(&mut flate2::read::ZlibDecoder::new(data), &mut out).unwrap();
+        out
+    }
+
+    /// Returns the decompressed content stream of the single page.
+    fn page_content(pdf: &[u8]) -> String {
+        let text = String::from_utf8_lossy(pdf);
+        let contents = text.split("/Contents ").nth(1).unwrap().split(' ').next().unwrap();
+        let header = format!("\n{} 0 obj\n", contents);
+        let start = pdf.windows(header.len()).position(|w| w == header.as_bytes()).unwrap();
+        let body = &pdf[start..];
+        let data_start = body.windows(7).position(|w| w == b"stream\n").unwrap() + 7;
+        let data_end = body.windows(10).position(|w| w == b"\nendstream").unwrap();
+        String::from_utf8(inflate(&body[data_start..data_end])).unwrap()
+    }
+
+    #[test]
+    fn overlays_are_rejected() {
+        let styles = CustomStyles {
+            overlays: vec![crate::overlay::Overlay {
+                shape: crate::overlay::OverlayShape::Dot,
+                color: "#d32f2f".to_string(),
+                anchor: crate::Alignment::TopRight,
+                size: 24.0,
+                cutout: None,
+            }],
+            ..CustomStyles::default()
+        };
+        assert!(to_pdf(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+        assert!(to_eps(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+    }
+
+    #[test]
+    fn patterns_are_rejected() {
+        let styles = CustomStyles {
+            pattern: Some(crate::pattern::BasePattern::new(crate::pattern::PatternKind::Dots)),
+            ..CustomStyles::default()
+        };
+        assert!(to_pdf(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+        assert!(to_eps(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+    }
+
+    #[test]
+    fn states_without_a_print_counterpart_are_rejected() {
+        for state in [IconState::Focused, IconState::Pressed, IconState::Disabled] {
+            let styles = CustomStyles { state, ..CustomStyles::default() };
+            assert!(to_pdf(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+            assert!(to_eps(TEST_SVG, StylePreset::Neumorphism, &styles).is_err());
+        }
+        let hover = CustomStyles { state: IconState::Hover, ..CustomStyles::default() };
+        assert!(to_pdf(TEST_SVG, StylePreset::Neumorphism, &hover).is_ok());
+    }
+
+    #[test]
+    fn pdf_has_valid_structure() {
+        let pdf = to_pdf(TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        // Every cross-reference offset points at its object header.
+        let text = String::from_utf8_lossy(&pdf);
+        let xref = text.find("xref\n").unwrap();
+        for (i, line) in text[xref..].lines().skip(3).take_while(|l| l.ends_with(" n ")).enumerate() {
+            let offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{} 0 obj", i + 1).as_bytes()));
+        }
+        assert!(text.contains("/MediaBox [0 0 128 128]"));
+    }
+
+    #[test]
+    fn pdf_draws_shadows_base_and_glyph() {
+        let pdf = to_pdf(TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert_eq!(text.matches("/SMask").count(), 2);
+
+        let content = page_content(&pdf);
+        assert!(content.starts_with("1 0 0 -1 0 128 cm\n"));
+        assert_eq!(content.matches(" Do Q").count(), 2);
+        // The base fill, then the glyph placed by the fit transform.
+        assert!(content.contains("0.878 0.878 0.878 rg"));
+        assert!(content.contains("0.2 0.2 0.2 rg\n64 24 m\n24 104 l\n104 104 l\n64 24 l\nh\nf"));
+    }
+
+    #[test]
+    fn pdf_uses_shadings_and_transparency() {
+        let styles = CustomStyles {
+            gradient: Some(Gradient {
+                angle: 90,
+                start_color: "#ff0000".to_string(),
+                stop_color: "#0000ff".to_string(),
+            }),
+            ..CustomStyles::default()
+        };
+        let pdf = to_pdf(TEST_SVG, StylePreset::Glassmorphism, &styles).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/ShadingType 2"));
+        assert!(text.contains("/Coords [0 64 128 64]"));
+        assert!(text.contains("<< /ca 0.2 >>"));
+        assert!(text.contains("<< /CA 0.3 >>"));
+        assert!(!text.contains("/SMask"));
+    }
+
+    #[test]
+    fn eps_composites_over_white() {
+        let eps = String::from_utf8(to_eps(TEST_SVG, StylePreset::Glassmorphism, &CustomStyles::default()).unwrap())
+            .unwrap();
+        assert!(eps.starts_with("%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 128 128\n"));
+        assert!(eps.contains("1 1 1 setrgbcolor fill"));
+        assert!(eps.contains("0.2 0.2 0.2 setrgbcolor fill"));
+        assert!(eps.ends_with("showpage\n%%EOF\n"));
+    }
+}
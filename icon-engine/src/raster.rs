@@ -0,0 +1,144 @@
+use std::sync::OnceLock;
+
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{self, fontdb, TreeParsing, TreePostProc};
+
+use crate::IconEngineError;
+
+/// A rendered icon as straight (non-premultiplied) RGBA8 pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterImage {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major RGBA bytes, `width * height * 4` long.
+    pub data: Vec<u8>,
+}
+
+/// Rasterizes an SVG string on the CPU.
+///
+/// With `size` set, the SVG is scaled so its longer side is `size` pixels;
+/// otherwise its own `width`/`height` are used. `scale` multiplies the result,
+/// e.g. `2.0` for an `@2x` asset. Filters emitted by the style generator, such
+/// as the neumorphism drop shadows, are rendered as well.
+///
+/// Text, such as badge and ribbon labels, is drawn with the installed
+/// fonts; SVGs with text fail to render when no sans-serif font is found.
+pub fn render(svg: &str, size: Option<u32>, scale: f32) -> Result<RasterImage, IconEngineError> {
+    let mut tree = usvg::Tree::from_str(svg, &usvg::Options::default())
+        .map_err(|e| IconEngineError::RenderError(e.to_string()))?;
+    let fonts = font_database();
+    if tree.has_text_nodes() && fonts.query(&sans_serif()).is_none() {
+        return Err(IconEngineError::RenderError(
+            "No sans-serif font is installed to draw the SVG's text".to_string(),
+        ));
+    }
+    tree.postprocess(usvg::PostProcessingSteps::default(), fonts);
+
+    let (svg_w, svg_h) = (tree.size.width(), tree.size.height());
+    let fit = size.map_or(1.0, |s| s as f32 / svg_w.max(svg_h));
+    let factor = fit * scale;
+
+    let width = (svg_w * factor).round() as u32;
+    let height = (svg_h * factor).round() as u32;
+    let mut pixmap = Pixmap::new(width, height).ok_or_else(|| {
+        IconEngineError::RenderError(format!("Invalid raster size {}x{}", width, height))
+    })?;
+
+    resvg::render(&tree, Transform::from_scale(factor, factor), &mut pixmap.as_mut());
+
+    let data = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+
+    Ok(RasterImage { width, height, data })
+}
+
+/// Returns the system fonts, loaded on first use.
+fn font_database() -> &'static fontdb::Database {
+    static FONTS: OnceLock<fontdb::Database> = OnceLock::new();
+    FONTS.get_or_init(|| {
+        let mut fonts = fontdb::Database::new();
+        fonts.load_system_fonts();
+        // The generic family defaults to a face such as Arial that may not be
+        // installed; any proportional sans-serif face will do instead.
+        if fonts.query(&sans_serif()).is_none() {
+            let fallback = fonts
+                .faces()
+                .filter(|face| !face.monospaced)
+                .flat_map(|face| face.families.iter().map(|(family, _)| family))
+                .find(|family| family.contains("Sans"))
+                .cloned();
+            if let Some(family) = fallback {
+                fonts.set_sans_serif_family(family);
+            }
+        }
+        fonts
+    })
+}
+
+fn sans_serif() -> fontdb::Query<'static> {
+    fontdb::Query { families: &[fontdb::Family::SansSerif], ..fontdb::Query::default() }
+}
+
+/// Encodes a raster image as PNG.
+pub fn encode_png(image: &RasterImage) -> Result<Vec<u8>, IconEngineError> {
+    let mut pixmap = Pixmap::new(image.width, image.height).ok_or_else(|| {
+        IconEngineError::RenderError(format!("Invalid raster size {}x{}", image.width, image.height))
+    })?;
+    for (dst, src) in pixmap.pixels_mut().iter_mut().zip(image.data.chunks_exact(4)) {
+        *dst = resvg::tiny_skia::ColorU8::from_rgba(src[0], src[1], src[2], src[3]).premultiply();
+    }
+    pixmap
+        .encode_png()
+        .map_err(|e| IconEngineError::RenderError(e.to_string()))
+}
+
+/// Rasterizes an SVG string and encodes it as PNG.
+pub fn render_png(svg: &str, size: Option<u32>, scale: f32) -> Result<Vec<u8>, IconEngineError> {
+    encode_png(&render(svg, size, scale)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, CustomStyles, StylePreset};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn render_scales_to_requested_size() {
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        let image = render(&svg, Some(64), 2.0).unwrap();
+        assert_eq!((image.width, image.height), (128, 128));
+
+        // The center of the icon is covered by the opaque foreground.
+        let center = ((64 * image.width + 64) * 4) as usize;
+        assert_eq!(&image.data[center..center + 4], &[0x33, 0x33, 0x33, 0xff]);
+    }
+
+    #[test]
+    fn render_png_produces_png_signature() {
+        let svg = generate_icon(TEST_SVG, StylePreset::Glassmorphism, &CustomStyles::default()).unwrap();
+        let png = render_png(&svg, None, 1.0).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn render_draws_text_or_fails() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="32"><text x="16" y="24" font-family="sans-serif" font-size="24" text-anchor="middle" fill="red">8</text></svg>"#;
+        match render(svg, None, 1.0) {
+            Ok(image) => assert!(image.data.chunks_exact(4).any(|pixel| pixel[0] > 0)),
+            Err(_) => assert!(font_database().query(&sans_serif()).is_none()),
+        }
+    }
+
+    #[test]
+    fn render_rejects_invalid_svg() {
+        assert!(render("<svg", Some(16), 1.0).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeSet, HashSet};
+
+use roxmltree::Document;
+
+use crate::IconEngineError;
+
+/// Turns an arbitrary name (e.g. a file stem like `video player`) into a
+/// valid, readable XML id.
+pub fn sanitize_id(name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    if id.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        id
+    } else {
+        format!("icon-{}", id)
+    }
+}
+
+/// Sanitizes each name with [`sanitize_id`], appending `-2`, `-3`, ... to
+/// names whose id is already taken, so `a b` and `a-b` become `a-b` and
+/// `a-b-2`. The first of the colliding names keeps the plain id, so the
+/// result only depends on the order of `names`.
+pub fn unique_ids<S: AsRef<str>>(names: &[S]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    names
+        .iter()
+        .map(|name| {
+            let id = sanitize_id(name.as_ref());
+            let unique = (1..)
+                .map(|n| if n == 1 { id.clone() } else { format!("{}-{}", id, n) })
+                .find(|candidate| !taken.contains(candidate))
+                .unwrap_or_default();
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// Prefixes every `id` in a generated SVG, along with the `url(#...)` and
+/// `href="#..."` references to it, so several icons can share one document
+/// without their `neumorphism-shadow` or `base-gradient` definitions clashing.
+///
+/// Stylesheet `#id` selectors and SMIL `id.event` timing references to the
+/// SVG's own ids are prefixed as well.
+pub fn namespace_ids(svg: &str, prefix: &str) -> String {
+    let ids: BTreeSet<&str> = svg
+        .match_indices(" id=\"")
+        .filter_map(|(start, attr)| {
+            let value = &svg[start + attr.len()..];
+            value.find('"').map(|end| &value[..end])
+        })
+        .collect();
+    let mut out = svg
+        .replace(" id=\"", &format!(" id=\"{}-", prefix))
+        .replace("url(#", &format!("url(#{}-", prefix))
+        .replace("href=\"#", &format!("href=\"#{}-", prefix));
+    for id in ids {
+        out = out.replace(&format!("=\"{}.", id), &format!("=\"{}-{}.", prefix, id));
+        out = prefix_selectors(&out, id, prefix);
+    }
+    out
+}
+
+/// Prefixes `#id` selectors that name exactly `id`.
+fn prefix_selectors(markup: &str, id: &str, prefix: &str) -> String {
+    let selector = format!("#{}", id);
+    let mut out = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(start) = rest.find(&selector) {
+        let end = start + selector.len();
+        out.push_str(&rest[..start]);
+        if rest[end..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            out.push_str(&selector);
+        } else {
+            out.push_str(&format!("#{}-{}", prefix, id));
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Converts a standalone SVG into a `<symbol>` with the given id, keeping its
+/// `viewBox` and namespacing its internal ids under the symbol id.
+///
+/// A `<style>` inside the icon would apply to every symbol of the sprite, so
+/// icons that carry one also have their class names namespaced, in both the
+/// `class` attributes and the stylesheet.
+pub fn svg_to_symbol(id: &str, svg: &str) -> Result<String, IconEngineError> {
+    let doc = Document::parse(svg).map_err(|e| IconEngineError::SvgParsingError(e.to_string()))?;
+    let root = doc.root_element();
+
+    let view_box = match root.attribute("viewBox") {
+        Some(view_box) => view_box.to_string(),
+        None => format!(
+            "0 0 {} {}",
+            root.attribute("width").unwrap_or("0"),
+            root.attribute("height").unwrap_or("0")
+        ),
+    };
+
+    let content = match (root.first_child(), root.last_child()) {
+        (Some(first), Some(last)) => &svg[first.range().start..last.range().end],
+        _ => "",
+    };
+    let mut content = namespace_ids(content, id);
+    if root.descendants().any(|node| node.has_tag_name("style")) {
+        let classes: BTreeSet<&str> = root
+            .descendants()
+            .filter_map(|node| node.attribute("class"))
+            .flat_map(str::split_whitespace)
+            .collect();
+        content = namespace_classes(&content, &classes, id);
+    }
+
+    Ok(format!(r#"<symbol id="{}" viewBox="{}">{}</symbol>"#, id, view_box, content))
+}
+
+/// Prefixes the given class names in `class` attributes and in the selectors
+/// of `<style>` elements.
+fn namespace_classes(markup: &str, classes: &BTreeSet<&str>, prefix: &str) -> String {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut out = String::with_capacity(markup.len());
+    let mut rest = markup;
+    loop {
+        let class_attr = rest.find(" class=\"");
+        let style = rest.find("<style");
+        match (class_attr, style) {
+            (Some(start), _) if style.is_none_or(|style| start < style) => {
+                let value_start = start + " class=\"".len();
+                let value_end = value_start + rest[value_start..].find('"').unwrap_or(0);
+                out.push_str(&rest[..value_start]);
+                let names: Vec<String> = rest[value_start..value_end]
+                    .split_whitespace()
+                    .map(|name| format!("{}-{}", prefix, name))
+                    .collect();
+                out.push_str(&names.join(" "));
+                rest = &rest[value_end..];
+            }
+            (_, Some(start)) => {
+                let end = rest[start..].find("</style>").map_or(rest.len(), |end| start + end);
+                out.push_str(&rest[..start]);
+                let mut css = &rest[start..end];
+                while let Some(dot) = css.find('.') {
+                    out.push_str(&css[..=dot]);
+                    css = &css[dot + 1..];
+                    let name_end = css.find(|c: char| !is_name_char(c)).unwrap_or(css.len());
+                    if classes.contains(&css[..name_end]) {
+                        out.push_str(prefix);
+                        out.push('-');
+                    }
+                }
+                out.push_str(css);
+                rest = &rest[end..];
+            }
+            _ => {
+                out.push_str(rest);
+                return out;
+            }
+        }
+    }
+}
+
+/// Combines `(id, svg)` pairs into one sprite sheet of `<symbol>`s,
+/// referenced from pages with `<svg><use href="sprite.svg#id"/></svg>`.
+///
+/// The sheet takes no space but is not `display:none`, which would stop
+/// browsers from rendering the gradients and filters its symbols use when
+/// it is inlined. Ids must be unique; see [`unique_ids`].
+pub fn build_sprite(icons: &[(String, String)]) -> Result<String, IconEngineError> {
+    let mut seen = HashSet::new();
+    if let Some((id, _)) = icons.iter().find(|(id, _)| !seen.insert(id)) {
+        return Err(IconEngineError::InvalidInput(format!("Duplicate sprite symbol id: {}", id)));
+    }
+    let symbols = icons
+        .iter()
+        .map(|(id, svg)| svg_to_symbol(id, svg))
+        .collect::<Result<String, _>>()?;
+
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0" style="position:absolute">{}</svg>"#,
+        symbols
+    ))
+}
+
+/// Builds an HTML page that inlines the sprite and shows every symbol with
+/// its id, for checking a sprite sheet in a browser.
+pub fn sprite_preview_html(sprite: &str, ids: &[String]) -> String {
+    let cells: String = ids
+        .iter()
+        .map(|id| {
+            format!(
+                r##"<figure><svg width="64" height="64"><use href="#{id}"/></svg><figcaption>{id}</figcaption></figure>"##,
+                id = id
+            )
+        })
+        .collect();
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Icon sprite preview</title>
+<style>body{{font-family:sans-serif;display:flex;flex-wrap:wrap;gap:16px}}figure{{margin:0;text-align:center}}figcaption{{font-size:12px}}</style>
+</head>
+<body>
+{}
+{}
+</body>
+</html>
+"#,
+        sprite, cells
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_icon, CustomStyles, Gradient, StylePreset};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn sanitize_id_replaces_invalid_characters() {
+        assert_eq!(sanitize_id("video player"), "video-player");
+        assert_eq!(sanitize_id("3d.box"), "icon-3d-box");
+    }
+
+    #[test]
+    fn unique_ids_suffix_collisions() {
+        assert_eq!(unique_ids(&["a b", "a-b", "a-b-2", "a.b"]), ["a-b", "a-b-2", "a-b-2-2", "a-b-3"]);
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &CustomStyles::default()).unwrap();
+        let icons = [("a".to_string(), svg.clone()), ("a".to_string(), svg)];
+        assert!(build_sprite(&icons).is_err());
+    }
+
+    #[test]
+    fn sprite_namespaces_stylesheet_classes() {
+        let svg = r#"<svg viewBox="0 0 8 8"><style>.icon-fg{opacity:.5}.icon-fg.other{opacity:0.5}</style><path class="icon-fg" d="M0 0h8v8z"/></svg>"#;
+        let sprite = build_sprite(&[("home".to_string(), svg.to_string())]).unwrap();
+        assert!(sprite.contains(r#"<style>.home-icon-fg{opacity:.5}.home-icon-fg.other{opacity:0.5}</style><path class="home-icon-fg""#));
+        assert!(sprite.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="0" height="0" style="position:absolute">"#));
+        // Icons without a stylesheet keep their classes for page CSS.
+        let plain = build_sprite(&[("lock".to_string(), svg.replace("<style>.icon-fg{opacity:.5}.icon-fg.other{opacity:0.5}</style>", ""))]).unwrap();
+        assert!(plain.contains(r#"class="icon-fg""#));
+    }
+
+    #[test]
+    fn sprite_namespaces_shared_definitions() {
+        let styles = CustomStyles {
+            gradient: Some(Gradient {
+                angle: 45,
+                start_color: "#ff0000".to_string(),
+                stop_color: "#0000ff".to_string(),
+            }),
+            ..CustomStyles::default()
+        };
+        let svg = generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap();
+        let sprite = build_sprite(&[("home".to_string(), svg.clone()), ("lock".to_string(), svg)]).unwrap();
+
+        assert!(sprite.contains(r#"<symbol id="home" viewBox="0 0 128 128">"#));
+        assert!(sprite.contains(r#"id="home-neumorphism-shadow""#));
+        assert!(sprite.contains(r#"filter="url(#lock-neumorphism-shadow)""#));
+        assert!(sprite.contains(r#"fill="url(#lock-base-gradient)""#));
+        assert!(!sprite.contains(r#"id="base-gradient""#));
+        assert!(Document::parse(&sprite).is_ok());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::color::desaturate_color_str;
+use crate::overlay::{Overlay, OverlayShape};
+use crate::path_data::format_number;
+use crate::sprite::namespace_ids;
+use crate::{generate_icon, CustomStyles, Gradient, IconEngineError, StylePreset};
+
+/// How much further hovered icons cast their neumorphism shadows.
+pub const HOVER_ELEVATION: f32 = 1.5;
+/// The opacity of disabled icons.
+pub const DISABLED_OPACITY: f32 = 0.4;
+/// The default stroke of the focus ring, restyled via its `focus-ring` class.
+pub const FOCUS_RING_COLOR: &str = "#005fcc";
+
+/// The interaction state of a button an icon is drawn for.
+///
+/// Variants are ordered by precedence: a pressed, hovered button shows the
+/// pressed state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum IconState {
+    #[default]
+    Normal,
+    /// Lifts the base with longer neumorphism shadows.
+    Hover,
+    /// Draws a ring just inside the canvas edge.
+    Focused,
+    /// Presses the base in, with its neumorphism shadows drawn inside.
+    Pressed,
+    /// Greys the icon out and fades it.
+    Disabled,
+}
+
+impl IconState {
+    pub const ALL: [IconState; 5] =
+        [IconState::Normal, IconState::Hover, IconState::Focused, IconState::Pressed, IconState::Disabled];
+
+    /// The lowercase name used in file suffixes, class names and `data-state`.
+    pub fn name(self) -> &'static str {
+        match self {
+            IconState::Normal => "normal",
+            IconState::Hover => "hover",
+            IconState::Focused => "focused",
+            IconState::Pressed => "pressed",
+            IconState::Disabled => "disabled",
+        }
+    }
+
+    /// The selectors of elements whose descendants show this state: the
+    /// inlined SVG itself, a link or button around it, or any element with
+    /// a matching `data-state` attribute.
+    fn holders(self) -> &'static str {
+        match self {
+            IconState::Normal => "",
+            IconState::Hover => r#"svg:hover,a:hover,button:hover,[data-state="hover"]"#,
+            IconState::Focused => {
+                r#"svg:focus-visible,a:focus-visible,button:focus-visible,[data-state="focused"]"#
+            }
+            IconState::Pressed => r#"svg:active,a:active,button:active,[data-state="pressed"]"#,
+            IconState::Disabled => r#"button:disabled,[aria-disabled="true"],[data-state="disabled"]"#,
+        }
+    }
+}
+
+/// Derives the styles of one state from the normal ones.
+///
+/// Disabled icons have every color greyed out (colors that cannot be parsed,
+/// such as `rgba(...)`, are kept) and lose their animation; the other states
+/// only set `CustomStyles::state`, which `generate_icon` draws.
+pub fn state_styles(styles: &CustomStyles, state: IconState) -> CustomStyles {
+    let mut derived = styles.clone();
+    derived.state = state;
+    if state != IconState::Disabled {
+        return derived;
+    }
+
+    derived.icon_color = desaturate_color_str(&styles.icon_color);
+    derived.base_color = styles.base_color.as_deref().map(desaturate_color_str);
+    derived.gradient = styles.gradient.as_ref().map(|g| Gradient {
+        angle: g.angle,
+        start_color: desaturate_color_str(&g.start_color),
+        stop_color: desaturate_color_str(&g.stop_color),
+    });
+    derived.overlays = styles
+        .overlays
+        .iter()
+        .map(|overlay| {
+            let shape = match &overlay.shape {
+                OverlayShape::Badge { text, text_color } => {
+                    OverlayShape::Badge { text: text.clone(), text_color: desaturate_color_str(text_color) }
+                }
+                OverlayShape::Ribbon { text, text_color } => {
+                    OverlayShape::Ribbon { text: text.clone(), text_color: desaturate_color_str(text_color) }
+                }
+                shape => shape.clone(),
+            };
+            Overlay { shape, color: desaturate_color_str(&overlay.color), ..overlay.clone() }
+        })
+        .collect();
+    if let Some(pattern) = &mut derived.pattern {
+        pattern.color = desaturate_color_str(&pattern.color);
+    }
+    for layer in &mut derived.glyph_layers {
+        layer.color = layer.color.as_deref().map(desaturate_color_str);
+    }
+    derived.animation = None;
+    derived
+}
+
+/// Applies the parts of `CustomStyles::state` drawn over the whole icon:
+/// fading disabled icons and ringing focused ones.
+pub(crate) fn state_markup(styles: &CustomStyles, content: String) -> String {
+    match styles.state {
+        IconState::Disabled => format!(r#"<g opacity="{}">{}</g>"#, format_number(DISABLED_OPACITY), content),
+        IconState::Focused => {
+            let (width, height) = (styles.width as f32, styles.height as f32);
+            let stroke = (width.min(height) / 32.0).max(2.0);
+            let inset = stroke / 2.0;
+            format!(
+                r#"{}<rect class="focus-ring" x="{i}" y="{i}" width="{}" height="{}" rx="{r}" ry="{r}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+                content,
+                format_number(width - stroke),
+                format_number(height - stroke),
+                FOCUS_RING_COLOR,
+                format_number(stroke),
+                i = format_number(inset),
+                r = format_number((styles.corner_radius - inset).max(0.0))
+            )
+        }
+        _ => content,
+    }
+}
+
+/// Fails for styles in a state whose focus ring, fade or inset shadows
+/// `output` cannot draw. Hovered icons only cast longer shadows and pass.
+pub(crate) fn reject_states(styles: &CustomStyles, output: &str) -> Result<(), IconEngineError> {
+    match styles.state {
+        IconState::Normal | IconState::Hover => Ok(()),
+        state => Err(IconEngineError::InvalidInput(format!(
+            "The {} state is not supported in {} output",
+            state.name(),
+            output
+        ))),
+    }
+}
+
+/// Generates a separate icon for each state, in the order given.
+pub fn generate_state_variants(
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+    states: &[IconState],
+) -> Result<Vec<(IconState, String)>, IconEngineError> {
+    states
+        .iter()
+        .map(|&state| Ok((state, generate_icon(icon_data, preset, &state_styles(styles, state))?)))
+        .collect()
+}
+
+/// Generates a single SVG holding every given state (and the normal one),
+/// of which a stylesheet shows the one matching the inlined SVG's or its
+/// button's `:hover`, `:focus-visible`, `:active` or `:disabled` state, or
+/// a `data-state` attribute on any ancestor. Each state's definitions are
+/// namespaced under `state-<name>`.
+pub fn generate_stateful_icon(
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+    states: &[IconState],
+) -> Result<String, IconEngineError> {
+    let mut states = states.to_vec();
+    states.push(IconState::Normal);
+    states.sort();
+    states.dedup();
+
+    // `:where` keeps every rule at the specificity of one class, so the
+    // state with the highest precedence wins by coming last.
+    let mut stylesheet = String::from(".icon-state{display:none}.state-normal{display:inline}");
+    let mut groups = String::new();
+    for (state, svg) in generate_state_variants(icon_data, preset, styles, &states)? {
+        if state != IconState::Normal {
+            stylesheet.push_str(&format!(
+                ":where({h}) .icon-state{{display:none}}:where({h}) .state-{n}{{display:inline}}",
+                h = state.holders(),
+                n = state.name()
+            ));
+        }
+        let content_start = svg.find('>').map(|i| i + 1).ok_or(IconEngineError::Unknown)?;
+        let content = svg[content_start..].strip_suffix("</svg>").ok_or(IconEngineError::Unknown)?;
+        let prefix = format!("state-{}", state.name());
+        groups.push_str(&format!(
+            r#"<g class="icon-state {}">{}</g>"#,
+            prefix,
+            namespace_ids(content, &prefix)
+        ));
+    }
+
+    Ok(format!(
+        r#"<svg width="{width}" height="{height}" viewBox="0 0 {width} {height}" xmlns="http://www.w3.org/2000/svg"><style>{style}</style>{groups}</svg>"#,
+        width = styles.width,
+        height = styles.height,
+        style = stylesheet,
+        groups = groups
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn derives_each_state() {
+        let styles = CustomStyles { icon_color: "#1565c0".to_string(), ..CustomStyles::default() };
+        let variants =
+            generate_state_variants(TEST_SVG, StylePreset::Neumorphism, &styles, &IconState::ALL).unwrap();
+        let svg = |state: IconState| &variants.iter().find(|(s, _)| *s == state).unwrap().1;
+
+        assert_eq!(svg(IconState::Normal), &generate_icon(TEST_SVG, StylePreset::Neumorphism, &styles).unwrap());
+        // Hover lengthens the 5.12px shadows.
+        assert!(svg(IconState::Hover).contains(r#"dx="7.68""#));
+        assert!(svg(IconState::Pressed).contains(r#"<feComposite in2="SourceAlpha" operator="out"/>"#));
+        assert!(!svg(IconState::Pressed).contains("feDropShadow"));
+        assert!(svg(IconState::Focused).contains(r##"<rect class="focus-ring" x="2" y="2" width="124" height="124" rx="23" ry="23" fill="none" stroke="#005fcc" stroke-width="4"/>"##));
+
+        let disabled = svg(IconState::Disabled);
+        assert!(disabled.contains(r#"<g opacity="0.4">"#));
+        assert!(disabled.contains(r##"fill="#6b6b6b""##), "{}", disabled);
+        for (_, svg) in &variants {
+            roxmltree::Document::parse(svg).unwrap();
+        }
+    }
+
+    #[test]
+    fn stateful_icon_switches_states_with_css() {
+        let svg = generate_stateful_icon(
+            TEST_SVG,
+            StylePreset::Neumorphism,
+            &CustomStyles::default(),
+            &[IconState::Disabled, IconState::Hover],
+        )
+        .unwrap();
+        roxmltree::Document::parse(&svg).unwrap();
+
+        assert_eq!(svg.matches(r#"<g class="icon-state "#).count(), 3);
+        assert!(svg.contains(r#"id="state-hover-neumorphism-shadow""#));
+        assert!(svg.contains(r#"filter="url(#state-disabled-neumorphism-shadow)""#));
+        // Disabled comes after hover so it wins when both apply.
+        let hover = svg.find(".state-hover{display:inline}").unwrap();
+        let disabled = svg.find(".state-disabled{display:inline}").unwrap();
+        assert!(hover < disabled);
+        assert!(svg.contains(r#":where(button:disabled,[aria-disabled="true"],[data-state="disabled"]) .icon-state{display:none}"#));
+    }
+}
@@ -1,17 +1,90 @@
-use crate::{CustomStyles, Gradient, StylePreset};
+use crate::animation::{Animation, AnimationKind};
+use crate::pattern::pattern_markup;
+use crate::states::{IconState, HOVER_ELEVATION};
+use crate::{CustomStyles, Gradient, IconEngineError, ShadowColors, StylePreset};
 
 const NEUMORPHISM_FILTER_ID: &str = "neumorphism-shadow";
 const GLASS_BLUR_FILTER_ID: &str = "glass-blur";
 const GRADIENT_ID: &str = "base-gradient";
 
+const DEFAULT_SHADOW_DARK: &str = "rgba(0,0,0,0.12)";
+const DEFAULT_SHADOW_LIGHT: &str = "rgba(255,255,255,0.7)";
+
+/// The outline drawn around glass bases.
+pub const GLASS_STROKE: &str = "rgba(255,255,255,0.3)";
+pub const GLASS_STROKE_WIDTH: f32 = 1.0;
+
+/// Returns the fill of the base shape when no gradient is set, honouring
+/// `CustomStyles::base_color` before falling back to the preset default.
+pub fn base_fill(styles: &CustomStyles, preset: StylePreset) -> String {
+    if let Some(color) = &styles.base_color {
+        return color.clone();
+    }
+    let default_fill = if preset == StylePreset::Neumorphism {
+        "#e0e0e0"
+    } else {
+        "white"
+    };
+    default_fill.to_string()
+}
+
+/// Returns the neumorphism shadow colors, falling back to the defaults.
+pub fn shadow_colors(styles: &CustomStyles) -> ShadowColors {
+    styles.shadow_colors.clone().unwrap_or_else(|| ShadowColors {
+        dark: DEFAULT_SHADOW_DARK.to_string(),
+        light: DEFAULT_SHADOW_LIGHT.to_string(),
+    })
+}
+
+/// Returns the opacity of the base fill: glass presets are translucent.
+pub fn base_fill_opacity(preset: StylePreset) -> f32 {
+    match preset {
+        StylePreset::Neumorphism => 1.0,
+        StylePreset::Glassmorphism => 0.2,
+        StylePreset::FrostedGlass => 0.1,
+    }
+}
+
+/// Returns the neumorphism shadow `(offset, blur radius)`; the dark shadow
+/// is offset towards the bottom-right and the light one towards the top-left.
+pub fn shadow_geometry(styles: &CustomStyles) -> (f32, f32) {
+    let elevation = if styles.state == IconState::Hover { HOVER_ELEVATION } else { 1.0 };
+    let shadow_offset = styles.width as f32 / 25.0 * elevation;
+    (shadow_offset, shadow_offset * 1.2)
+}
+
+/// Returns the start and end points of a gradient's axis as fractions of
+/// the base's bounding box, following the CSS angle convention.
+pub fn gradient_points(gradient: &Gradient) -> ((f32, f32), (f32, f32)) {
+    let angle_rad = (gradient.angle as f32 - 90.0).to_radians();
+    let (sin, cos) = angle_rad.sin_cos();
+    ((0.5 - cos * 0.5, 0.5 - sin * 0.5), (0.5 + cos * 0.5, 0.5 + sin * 0.5))
+}
+
 /// Creates the styled base as a tuple of strings: (definitions, base_shape).
+/// A pattern adds a second shape over the base, so it stays clear of the
+/// base's shadow filter. Patterns that cannot be drawn are left out; see
+/// [`try_create_styled_base_str`].
 pub fn create_styled_base_str(
     styles: &CustomStyles,
     preset: StylePreset,
 ) -> (String, String) {
+    styled_base(styles, preset, pattern_markup(styles).ok().flatten())
+}
+
+/// Like [`create_styled_base_str`], but fails on an invalid
+/// `CustomStyles::pattern` instead of leaving it out.
+pub fn try_create_styled_base_str(
+    styles: &CustomStyles,
+    preset: StylePreset,
+) -> Result<(String, String), IconEngineError> {
+    Ok(styled_base(styles, preset, pattern_markup(styles)?))
+}
+
+fn styled_base(styles: &CustomStyles, preset: StylePreset, pattern: Option<(String, String)>) -> (String, String) {
     let mut defs = String::new();
     let mut rect_attrs = format!(
-        r#"width="{}" height="{}" rx="{}" ry="{}""#,
+        r#"class="icon-base" width="{}" height="{}" rx="{}" ry="{}""#,
         styles.width, styles.height, styles.corner_radius, styles.corner_radius
     );
 
@@ -20,15 +93,12 @@ pub fn create_styled_base_str(
         defs.push_str(&create_gradient_def_str(gradient));
         rect_attrs.push_str(&format!(r#" fill="url(#{})""#, GRADIENT_ID));
     } else {
-        let default_fill = if preset == StylePreset::Neumorphism {
-            "#e0e0e0"
-        } else {
-            "white"
-        };
-        rect_attrs.push_str(&format!(r#" fill="{}""#, default_fill));
+        rect_attrs.push_str(&format!(r#" fill="{}""#, base_fill(styles, preset)));
     }
 
     let (style_attrs, style_defs) = match preset {
+        StylePreset::Neumorphism if !styles.shadows => (String::new(), None),
+        StylePreset::Neumorphism if styles.state == IconState::Pressed => create_inset_neumorphism_style_str(styles),
         StylePreset::Neumorphism => create_neumorphism_style_str(styles),
         StylePreset::Glassmorphism | StylePreset::FrostedGlass => {
             create_glassmorphism_style_str(preset)
@@ -40,17 +110,22 @@ pub fn create_styled_base_str(
         defs.push_str(&style_def_str);
     }
 
+    let mut rect = format!("<rect {}/>", rect_attrs);
+    if let Some((pattern_defs, pattern_shape)) = pattern {
+        defs.push_str(&pattern_defs);
+        rect.push_str(&pattern_shape);
+    }
+
     let final_defs = if defs.is_empty() {
         String::new()
     } else {
         format!("<defs>{}</defs>", defs)
     };
 
-    let rect = format!("<rect {}/>", rect_attrs);
-
     (final_defs, rect)
 }
 
+#[allow(unused_parens)]
 fn create_gradient_def_str(gradient: &Gradient) -> String {
     let angle_rad = (gradient.angle as f32 - 90.0).to_radians();
     let x1 = (50.0 - f32::cos(angle_rad) * 50.0);
@@ -59,20 +134,23 @@ fn create_gradient_def_str(gradient: &Gradient) -> String {
     let y2 = (50.0 + f32::sin(angle_rad) * 50.0);
 
     format!(
-        r#"<linearGradient id="{}" x1="{:.1}%" y1="{:.1}%" x2="{:.1}%" y2="{:.1}%"><stop offset="0%" stop-color="{}"/><stop offset="100%" stop-color="{}"/></linearGradient>"#,
+        r#"<linearGradient id="{}" x1="{:.1}%" y1="{:.1}%" x2="{:.1}%" y2="{:.1}%"><stop class="gradient-start" offset="0%" stop-color="{}"/><stop class="gradient-stop" offset="100%" stop-color="{}"/></linearGradient>"#,
         GRADIENT_ID, x1, y1, x2, y2, gradient.start_color, gradient.stop_color
     )
 }
 
 fn create_neumorphism_style_str(styles: &CustomStyles) -> (String, Option<String>) {
-    let shadow_offset = styles.width as f32 / 25.0;
-    let blur_radius = shadow_offset * 1.2;
+    let (shadow_offset, blur_radius) = shadow_geometry(styles);
+
+    let shadows = shadow_colors(styles);
 
     let filter_def = format!(
-        r#"<filter id="{}"><feDropShadow dx="{}" dy="{}" stdDeviation="{}" flood-color="rgba(0,0,0,0.12)"/><feDropShadow dx="{}" dy="{}" stdDeviation="{}" flood-color="rgba(255,255,255,0.7)"/></filter>"#,
+        r#"<filter id="{}"><feDropShadow class="shadow-dark" dx="{}" dy="{}" stdDeviation="{}" flood-color="{}"{}<feDropShadow class="shadow-light" dx="{}" dy="{}" stdDeviation="{}" flood-color="{}"{}</filter>"#,
         NEUMORPHISM_FILTER_ID,
-        shadow_offset, shadow_offset, blur_radius,
-        -shadow_offset, -shadow_offset, blur_radius
+        shadow_offset, shadow_offset, blur_radius, shadows.dark,
+        shadow_end(styles, shadow_offset, blur_radius),
+        -shadow_offset, -shadow_offset, blur_radius, shadows.light,
+        shadow_end(styles, -shadow_offset, blur_radius)
     );
 
     let rect_attrs = format!(r#" filter="url(#{})""#, NEUMORPHISM_FILTER_ID);
@@ -80,18 +158,60 @@ fn create_neumorphism_style_str(styles: &CustomStyles) -> (String, Option<String
     (rect_attrs, Some(filter_def))
 }
 
+/// The pressed-in counterpart of the neumorphism shadows: each color floods
+/// the area outside the base, is offset and blurred like the raised shadow
+/// and then clipped to the base, darkening the top-left inner edge and
+/// lighting the bottom-right one.
+fn create_inset_neumorphism_style_str(styles: &CustomStyles) -> (String, Option<String>) {
+    let (shadow_offset, blur_radius) = shadow_geometry(styles);
+    let shadows = shadow_colors(styles);
+
+    let inner_shadow = |class: &str, color: &str, offset: f32| {
+        format!(
+            r#"<feFlood class="{class}" flood-color="{color}"/><feComposite in2="SourceAlpha" operator="out"/><feOffset dx="{o}" dy="{o}"/><feGaussianBlur stdDeviation="{b}"/><feComposite in2="SourceAlpha" operator="in" result="{class}"/>"#,
+            class = class,
+            color = color,
+            o = offset,
+            b = blur_radius
+        )
+    };
+    let filter_def = format!(
+        r#"<filter id="{}">{}{}<feMerge><feMergeNode in="SourceGraphic"/><feMergeNode in="shadow-dark"/><feMergeNode in="shadow-light"/></feMerge></filter>"#,
+        NEUMORPHISM_FILTER_ID,
+        inner_shadow("shadow-dark", &shadows.dark, shadow_offset),
+        inner_shadow("shadow-light", &shadows.light, -shadow_offset)
+    );
+
+    (format!(r#" filter="url(#{})""#, NEUMORPHISM_FILTER_ID), Some(filter_def))
+}
+
+/// Closes a `<feDropShadow>`, with SMIL animations of its offset and blur
+/// when the icon breathes.
+fn shadow_end(styles: &CustomStyles, offset: f32, blur_radius: f32) -> String {
+    let Some(animation) = styles.animation.filter(|a| a.kind == AnimationKind::Breathe) else {
+        return "/>".to_string();
+    };
+    let timing = animation.smil_timing(true);
+    let offset_values = Animation::breathe_values(offset);
+    format!(
+        r#"><animate attributeName="dx" values="{o}"{t}/><animate attributeName="dy" values="{o}"{t}/><animate attributeName="stdDeviation" values="{b}"{t}/></feDropShadow>"#,
+        o = offset_values,
+        b = Animation::breathe_values(blur_radius),
+        t = timing
+    )
+}
+
 fn create_glassmorphism_style_str(preset: StylePreset) -> (String, Option<String>) {
-    let (blur_std_deviation, fill_opacity) = match preset {
-        StylePreset::Glassmorphism => (5.0, 0.2),
-        StylePreset::FrostedGlass => (12.0, 0.1),
-        _ => (5.0, 0.2),
+    let blur_std_deviation = match preset {
+        StylePreset::FrostedGlass => 12.0,
+        _ => 5.0,
     };
 
     // Note: backdrop-filter is not a standard SVG attribute and might not work.
     // A simple opacity and stroke is more reliable.
     let rect_attrs = format!(
-        r#" fill-opacity="{}" stroke="rgba(255,255,255,0.3)" stroke-width="1""#,
-        fill_opacity
+        r#" fill-opacity="{}" stroke="{}" stroke-width="{}""#,
+        base_fill_opacity(preset), GLASS_STROKE, GLASS_STROKE_WIDTH
     );
 
     // We can still define the blur filter in defs, even if not widely supported.
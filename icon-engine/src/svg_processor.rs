@@ -1,11 +1,65 @@
-use roxmltree::Document;
-use crate::{CustomStyles, IconEngineError};
+use roxmltree::{Document, Node};
+use crate::geometry::{self, Rect};
+use crate::path_data::{parse_path, to_path_data, transform_path, PathSegment, Point, Transform};
+use crate::{glyph_stack, Alignment, CustomStyles, FitMode, IconEngineError, Placement};
+
+/// Elements whose content is only drawn when referenced.
+const NON_RENDERED: &[&str] = &["defs", "clipPath", "mask", "marker", "pattern", "symbol"];
 
 /// Represents the essential data extracted from a source SVG file.
 #[derive(Debug)]
 pub struct SvgIcon {
     pub path_data: String,
     pub viewbox: ViewBox,
+    /// The viewBox's min-x and min-y, the source coordinates of its top-left corner.
+    pub viewbox_origin: Point,
+    /// Every drawn shape of the source, in document order.
+    pub elements: Vec<IconElement>,
+}
+
+impl SvgIcon {
+    /// Returns the exact bounds of each element in `elements`, including
+    /// strokes; `None` for elements with no geometry.
+    pub fn element_bounds(&self) -> Vec<Option<Rect>> {
+        self.elements.iter().map(IconElement::bounds).collect()
+    }
+
+    /// Returns the outline drawn as the glyph: `path_data` as written, without
+    /// transforms. Empty when it cannot be parsed.
+    pub fn glyph_segments(&self) -> Vec<PathSegment> {
+        parse_path(&self.path_data).unwrap_or_default()
+    }
+
+    /// Returns the exact bounds of everything the source draws, in viewBox
+    /// coordinates and including strokes.
+    pub fn bounds(&self) -> Option<Rect> {
+        Rect::from_points(self.element_bounds().into_iter().flatten().flat_map(|r| {
+            [Point::new(r.min_x, r.min_y), Point::new(r.max_x, r.max_y)]
+        }))
+    }
+}
+
+/// A shape element (`path`, `rect`, `circle`, ...) of a source SVG.
+#[derive(Debug, Clone)]
+pub struct IconElement {
+    /// The element's tag name.
+    pub tag: String,
+    /// The outline as absolute path segments in viewBox coordinates, with
+    /// the element's and its ancestors' transforms applied.
+    pub segments: Vec<PathSegment>,
+    /// The stroke width in viewBox coordinates, or 0 for unstroked shapes.
+    pub stroke_width: f32,
+}
+
+impl IconElement {
+    /// Returns the exact bounds of the outline, grown by half the stroke
+    /// width. That is exact for round joins and caps; sharp miter joins can
+    /// reach further.
+    pub fn bounds(&self) -> Option<Rect> {
+        let bounds = geometry::bounds(&self.segments)?;
+        let half = self.stroke_width / 2.0;
+        Some(Rect::new(bounds.min_x - half, bounds.min_y - half, bounds.max_x + half, bounds.max_y + half))
+    }
 }
 
 /// Represents the dimensions of an SVG's viewBox.
@@ -57,20 +111,315 @@ pub fn parse_svg(svg_data: &str) -> Result<SvgIcon, IconEngineError> {
 
     let path_data = path_node.attribute("d").unwrap().to_string();
 
-    Ok(SvgIcon { path_data, viewbox })
+    let mut elements = Vec::new();
+    for node in root_element
+        .descendants()
+        .filter(|n| n.is_element() && !n.ancestors().any(|a| NON_RENDERED.contains(&a.tag_name().name())))
+    {
+        elements.extend(shape_element(node)?);
+    }
+
+    let viewbox_origin = Point::new(viewbox_parts[0], viewbox_parts[1]);
+    Ok(SvgIcon { path_data, viewbox, viewbox_origin, elements })
 }
 
-/// Calculates the `transform` attribute value to scale and center the icon.
+/// Returns a presentation property of `node` or its nearest ancestor that
+/// sets it, from a `style` declaration or, below that, the attribute.
+fn inherited_property<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.ancestors().filter(|n| n.is_element()).find_map(|n| {
+        let declared = n.attribute("style").and_then(|style| {
+            style.split(';').rev().find_map(|declaration| {
+                let (property, value) = declaration.split_once(':')?;
+                (property.trim() == name).then(|| value.trim().trim_end_matches("!important").trim())
+            })
+        });
+        declared.or_else(|| n.attribute(name))
+    })
+}
+
+/// Converts a shape element to path segments with its transforms and
+/// inherited stroke. Elements that are not shapes, or whose geometry is
+/// malformed, yield `None`; a malformed `transform` or `stroke-width` would
+/// misplace or mis-measure the shape, so it is an error.
+fn shape_element(node: Node) -> Result<Option<IconElement>, IconEngineError> {
+    let Some(segments) = shape_segments(node) else {
+        return Ok(None);
+    };
+    let tag = node.tag_name().name();
+    let invalid = |name: &str, value: &str| {
+        IconEngineError::SvgParsingError(format!("Invalid {} on <{}>: {}", name, tag, value))
+    };
+
+    // Transforms closest to the element apply first.
+    let mut transform = Transform::IDENTITY;
+    for ancestor in node.ancestors().filter(|n| n.is_element()) {
+        if let Some(value) = ancestor.attribute("transform") {
+            transform = transform.then(Transform::parse(value).ok_or_else(|| invalid("transform", value))?);
+        }
+    }
+
+    let stroked = inherited_property(node, "stroke").is_some_and(|stroke| stroke != "none");
+    let stroke_width = match inherited_property(node, "stroke-width") {
+        _ if !stroked => 0.0,
+        None => 1.0,
+        Some(value) => {
+            let width = value.trim_end_matches("px").trim().parse::<f32>().map_err(|_| invalid("stroke-width", value))?;
+            // Strokes scale with the transform's average scale factor.
+            width * (transform.a * transform.d - transform.b * transform.c).abs().sqrt()
+        }
+    };
+
+    Ok(Some(IconElement { tag: tag.to_string(), segments: transform_path(&segments, &transform), stroke_width }))
+}
+
+/// Returns a shape element's outline in its own coordinates, or `None` for
+/// elements that are not shapes or have malformed geometry.
+fn shape_segments(node: Node) -> Option<Vec<PathSegment>> {
+    let number = |name: &str| node.attribute(name).map_or(Some(0.0), |v| v.trim().parse::<f32>().ok());
+    let points = || -> Option<Vec<Point>> {
+        let values = node
+            .attribute("points")?
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<f32>().ok())
+            .collect::<Option<Vec<f32>>>()?;
+        Some(values.chunks_exact(2).map(|p| Point::new(p[0], p[1])).collect())
+    };
+    let ellipse = |cx: f32, cy: f32, rx: f32, ry: f32| {
+        let arc = |x: f32| PathSegment::ArcTo {
+            rx,
+            ry,
+            x_axis_rotation: 0.0,
+            large_arc: false,
+            sweep: true,
+            to: Point::new(x, cy),
+        };
+        vec![PathSegment::MoveTo(Point::new(cx - rx, cy)), arc(cx + rx), arc(cx - rx), PathSegment::ClosePath]
+    };
+
+    let tag = node.tag_name().name();
+    let segments = match tag {
+        "path" => parse_path(node.attribute("d")?).ok()?,
+        "rect" => {
+            let (x, y, width, height) = (number("x")?, number("y")?, number("width")?, number("height")?);
+            // A missing radius takes the other one's value.
+            let rx = node.attribute("rx").or(node.attribute("ry")).map_or(Some(0.0), |v| v.parse::<f32>().ok())?;
+            let ry = node.attribute("ry").or(node.attribute("rx")).map_or(Some(0.0), |v| v.parse::<f32>().ok())?;
+            let (rx, ry) = (rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0));
+            let corner = |x: f32, y: f32| PathSegment::ArcTo {
+                rx,
+                ry,
+                x_axis_rotation: 0.0,
+                large_arc: false,
+                sweep: true,
+                to: Point::new(x, y),
+            };
+            vec![
+                PathSegment::MoveTo(Point::new(x + rx, y)),
+                PathSegment::LineTo(Point::new(x + width - rx, y)),
+                corner(x + width, y + ry),
+                PathSegment::LineTo(Point::new(x + width, y + height - ry)),
+                corner(x + width - rx, y + height),
+                PathSegment::LineTo(Point::new(x + rx, y + height)),
+                corner(x, y + height - ry),
+                PathSegment::LineTo(Point::new(x, y + ry)),
+                corner(x + rx, y),
+                PathSegment::ClosePath,
+            ]
+        }
+        "circle" => {
+            let r = number("r")?;
+            ellipse(number("cx")?, number("cy")?, r, r)
+        }
+        "ellipse" => ellipse(number("cx")?, number("cy")?, number("rx")?, number("ry")?),
+        "line" => vec![
+            PathSegment::MoveTo(Point::new(number("x1")?, number("y1")?)),
+            PathSegment::LineTo(Point::new(number("x2")?, number("y2")?)),
+        ],
+        "polyline" | "polygon" => {
+            let points = points()?;
+            let (first, rest) = points.split_first()?;
+            let mut segments = vec![PathSegment::MoveTo(*first)];
+            segments.extend(rest.iter().map(|p| PathSegment::LineTo(*p)));
+            if tag == "polygon" {
+                segments.push(PathSegment::ClosePath);
+            }
+            segments
+        }
+        _ => return None,
+    };
+    Some(segments)
+}
+
+/// Where the glyph is drawn on the canvas: scaled about the origin (negative
+/// factors mirror), rotated clockwise by `rotation` degrees, then translated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlyphPlacement {
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub rotation: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl GlyphPlacement {
+    /// A placement that hides the glyph, used when the padding leaves no room.
+    const HIDDEN: Self = Self { scale_x: 0.0, scale_y: 0.0, rotation: 0.0, tx: 0.0, ty: 0.0 };
+
+    /// Returns the scale, rotation and mirroring without the translation.
+    fn linear(&self) -> Transform {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        Transform::scale(self.scale_x, self.scale_y).then(Transform { a: cos, b: sin, c: -sin, d: cos, e: 0.0, f: 0.0 })
+    }
+
+    pub fn matrix(&self) -> Transform {
+        self.linear().then(Transform::translate(self.tx, self.ty))
+    }
+
+    /// Returns whether the placement is a plain uniform scale and translation.
+    pub fn is_uniform(&self) -> bool {
+        self.rotation == 0.0 && self.scale_x == self.scale_y && self.scale_x >= 0.0
+    }
+}
+
+/// Calculates where to draw the icon within `CustomStyles::content_box`:
+/// fitted according to `CustomStyles::fit` with its aspect ratio preserved,
+/// then adjusted by `CustomStyles::placement`.
 ///
-/// It preserves the icon's aspect ratio and fits it within the padded area
-/// of the base defined by `CustomStyles`.
-pub fn calculate_transform(viewbox: ViewBox, styles: &CustomStyles) -> String {
-    let target_w = styles.width as f32 - (2.0 * styles.padding as f32);
-    let target_h = styles.height as f32 - (2.0 * styles.padding as f32);
+/// Geometry-based modes fit the bounds of the drawn glyph, stacked glyph
+/// layers included, and fall back to the viewBox for paths that cannot be
+/// parsed or have no extent. Alignment applies to the bounds of the scaled, rotated and
+/// mirrored glyph; optical modes only move the glyph's visual centroid
+/// towards the middle on centered axes.
+pub fn glyph_placement(icon: &SvgIcon, styles: &CustomStyles) -> GlyphPlacement {
+    let content = styles.content_box();
+    let (target_w, target_h) = (content.width(), content.height());
+
+    if target_w <= 0.0 || target_h <= 0.0 {
+        // Avoid division by zero or negative dimensions if padding is too large
+        return GlyphPlacement::HIDDEN;
+    }
+
+    let segments = fit_segments(icon, styles);
+    let bounds = match styles.fit {
+        FitMode::ViewBox => None,
+        _ => geometry::bounds(&segments),
+    };
+    let bounds = bounds
+        .filter(|b| b.width() > 0.0 || b.height() > 0.0)
+        .unwrap_or(Rect::new(0.0, 0.0, icon.viewbox.width, icon.viewbox.height));
+
+    // The fit applies to the rotated glyph's box.
+    let placement = &styles.placement;
+    let (sin, cos) = placement.rotation.to_radians().sin_cos();
+    let (sin, cos) = (sin.abs(), cos.abs());
+    let rotated_w = bounds.width() * cos + bounds.height() * sin;
+    let rotated_h = bounds.width() * sin + bounds.height() * cos;
+    let mut scale = (target_w / rotated_w).min(target_h / rotated_h);
+    let coverage = match styles.fit {
+        FitMode::Optical | FitMode::VisualArea => geometry::coverage(&segments),
+        _ => None,
+    };
+    if let (FitMode::VisualArea, Some(coverage)) = (styles.fit, coverage) {
+        let area_scale = (styles.target_coverage * target_w * target_h / coverage.area).sqrt();
+        scale = scale.min(area_scale);
+    }
+
+    if let Some(size) = placement.size {
+        scale = size / bounds.width().max(bounds.height());
+    }
+    scale *= placement.scale;
+    let mut result = GlyphPlacement {
+        scale_x: if placement.flip_horizontal { -scale } else { scale },
+        scale_y: if placement.flip_vertical { -scale } else { scale },
+        rotation: placement.rotation,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    let linear = result.linear();
+    let corners = [
+        Point::new(bounds.min_x, bounds.min_y),
+        Point::new(bounds.max_x, bounds.min_y),
+        Point::new(bounds.min_x, bounds.max_y),
+        Point::new(bounds.max_x, bounds.max_y),
+    ];
+    let Some(placed) = Rect::from_points(corners.map(|p| linear.apply(p))) else {
+        return GlyphPlacement::HIDDEN;
+    };
+    let anchor = linear.apply(coverage.map_or(bounds.center(), |c| c.centroid));
+
+    use {Alignment::*, Anchor::*};
+    let (horizontal, vertical) = match placement.align {
+        TopLeft => (Start, Start),
+        Top => (Middle, Start),
+        TopRight => (End, Start),
+        Left => (Start, Middle),
+        Center => (Middle, Middle),
+        Right => (End, Middle),
+        BottomLeft => (Start, End),
+        Bottom => (Middle, End),
+        BottomRight => (End, End),
+    };
+    // Moves the glyph along one axis; centered glyphs with a known centroid
+    // are centered on it, as far as the padded area allows.
+    let place = |align: Anchor, anchor: f32, (min, max): (f32, f32), (start, end): (f32, f32)| {
+        let lowest = start - min;
+        let highest = end - max;
+        let middle = (start + end) / 2.0;
+        match align {
+            Start => lowest,
+            End => highest,
+            Middle if coverage.is_some() => (middle - anchor).clamp(lowest.min(highest), highest.max(lowest)),
+            Middle => middle - (min + max) / 2.0,
+        }
+    };
+    let horizontal_span = (content.min_x, content.max_x);
+    let vertical_span = (content.min_y, content.max_y);
+    result.tx = place(horizontal, anchor.x, (placed.min_x, placed.max_x), horizontal_span) + placement.offset.0;
+    result.ty = place(vertical, anchor.y, (placed.min_y, placed.max_y), vertical_span) + placement.offset.1;
+    result
+}
+
+/// A position along one axis of the padded area.
+#[derive(Clone, Copy)]
+enum Anchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Returns the outlines the geometry-based fits measure, in viewBox
+/// coordinates: the drawn glyph followed by any stacked glyph layers.
+pub(crate) fn fit_segments(icon: &SvgIcon, styles: &CustomStyles) -> Vec<PathSegment> {
+    let mut segments = icon.glyph_segments();
+    segments.extend(glyph_stack::layer_outlines(icon, styles).into_iter().flatten());
+    segments
+}
+
+/// Returns the share of the padded area the glyph's filled region covers
+/// when fitted tightly, for picking a `CustomStyles::target_coverage` that
+/// matches a set of icons.
+pub fn tight_coverage(icon: &SvgIcon, styles: &CustomStyles) -> Option<f32> {
+    let segments = fit_segments(icon, styles);
+    let tight = CustomStyles { fit: FitMode::Tight, placement: Placement::default(), ..styles.clone() };
+    let scale = glyph_placement(icon, &tight).scale_x;
+    let coverage = geometry::coverage(&segments)?;
+    let content = styles.content_box();
+    let target_area = content.width() * content.height();
+    (scale > 0.0).then(|| coverage.area * scale * scale / target_area)
+}
+
+/// Calculates the `(translate_x, translate_y, scale)` that fits the viewBox
+/// within the padded area of the base, preserving its aspect ratio and
+/// centering it.
+pub fn fit_transform(viewbox: ViewBox, styles: &CustomStyles) -> (f32, f32, f32) {
+    let content = styles.content_box();
+    let (target_w, target_h) = (content.width(), content.height());
 
     if target_w <= 0.0 || target_h <= 0.0 {
         // Avoid division by zero or negative dimensions if padding is too large
-        return "translate(0, 0) scale(0)".to_string();
+        return (0.0, 0.0, 0.0);
     }
 
     let scale_x = target_w / viewbox.width;
@@ -80,8 +429,263 @@ pub fn calculate_transform(viewbox: ViewBox, styles: &CustomStyles) -> String {
     let scaled_w = viewbox.width * scale;
     let scaled_h = viewbox.height * scale;
 
-    let tx = styles.padding as f32 + (target_w - scaled_w) / 2.0;
-    let ty = styles.padding as f32 + (target_h - scaled_h) / 2.0;
+    let tx = content.min_x + (target_w - scaled_w) / 2.0;
+    let ty = content.min_y + (target_h - scaled_h) / 2.0;
+
+    (tx, ty, scale)
+}
 
+/// Calculates the `transform` attribute value to scale and center the icon.
+///
+/// It preserves the icon's aspect ratio and fits it within the padded area
+/// of the base defined by `CustomStyles`. See [`placement_transform`] for
+/// the fit modes and placement options.
+pub fn calculate_transform(viewbox: ViewBox, styles: &CustomStyles) -> String {
+    let (tx, ty, scale) = fit_transform(viewbox, styles);
     format!("translate({}, {}) scale({})", tx, ty, scale)
 }
+
+/// Returns the centered viewBox fit of [`fit_transform`] as an affine transform.
+pub fn fit_matrix(viewbox: ViewBox, styles: &CustomStyles) -> Transform {
+    let (tx, ty, scale) = fit_transform(viewbox, styles);
+    Transform::scale(scale, scale).then(Transform::translate(tx, ty))
+}
+
+/// Checks the fit and placement options that [`glyph_placement`] cannot
+/// make sense of.
+pub fn validate_placement(styles: &CustomStyles) -> Result<(), IconEngineError> {
+    let coverage = styles.target_coverage;
+    if !(coverage > 0.0 && coverage <= 1.0) {
+        return Err(IconEngineError::InvalidInput(format!(
+            "Target coverage must be above 0 and at most 1, got {}",
+            coverage
+        )));
+    }
+    let placement = &styles.placement;
+    if !(placement.scale > 0.0 && placement.scale.is_finite()) {
+        return Err(IconEngineError::InvalidInput(format!("Glyph scale must be above 0, got {}", placement.scale)));
+    }
+    if let Some(size) = placement.size.filter(|size| !(*size > 0.0 && size.is_finite())) {
+        return Err(IconEngineError::InvalidInput(format!("Glyph size must be above 0, got {}", size)));
+    }
+    Ok(())
+}
+
+/// Calculates the `transform` attribute value that draws the icon where
+/// [`glyph_placement`] puts it, honoring `CustomStyles::fit` and
+/// `CustomStyles::placement`.
+pub fn placement_transform(icon: &SvgIcon, styles: &CustomStyles) -> Result<String, IconEngineError> {
+    validate_placement(styles)?;
+    let placement = glyph_placement(icon, styles);
+    if placement.is_uniform() {
+        return Ok(format!("translate({}, {}) scale({})", placement.tx, placement.ty, placement.scale_x));
+    }
+    Ok(format!(
+        "translate({}, {}) rotate({}) scale({}, {})",
+        placement.tx, placement.ty, placement.rotation, placement.scale_x, placement.scale_y
+    ))
+}
+
+/// Returns the placement of [`glyph_placement`] as an affine transform.
+pub fn placement_matrix(icon: &SvgIcon, styles: &CustomStyles) -> Result<Transform, IconEngineError> {
+    validate_placement(styles)?;
+    Ok(glyph_placement(icon, styles).matrix())
+}
+
+/// Applies an affine transform to the coordinates of SVG path data. All
+/// commands are accepted; the result uses absolute commands only.
+pub fn transform_path_data(path_data: &str, transform: &Transform) -> Result<String, IconEngineError> {
+    Ok(to_path_data(&transform_path(&parse_path(path_data)?, transform)))
+}
+
+/// Returns the icon's path data in canvas coordinates, with the fit baked in
+/// so it can be drawn without a transformed group.
+pub fn fitted_path_data(icon: &SvgIcon, styles: &CustomStyles) -> Result<String, IconEngineError> {
+    transform_path_data(&icon.path_data, &placement_matrix(icon, styles)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Insets;
+
+    fn icon(path_data: &str) -> SvgIcon {
+        let elements = parse_path(path_data)
+            .map(|segments| IconElement { tag: "path".to_string(), segments, stroke_width: 0.0 })
+            .into_iter()
+            .collect();
+        SvgIcon {
+            path_data: path_data.to_string(),
+            viewbox: ViewBox { width: 24.0, height: 24.0 },
+            viewbox_origin: Point::new(0.0, 0.0),
+            elements,
+        }
+    }
+
+    fn styles(fit: FitMode) -> CustomStyles {
+        CustomStyles { fit, target_coverage: 0.25, ..CustomStyles::default() }
+    }
+
+    fn placed(icon: &SvgIcon, styles: &CustomStyles) -> (f32, f32, f32) {
+        let placement = glyph_placement(icon, styles);
+        (placement.tx, placement.ty, placement.scale_x)
+    }
+
+    fn assert_close(actual: (f32, f32, f32), expected: (f32, f32, f32)) {
+        let close = |a: f32, b: f32| (a - b).abs() < 0.05;
+        assert!(close(actual.0, expected.0) && close(actual.1, expected.1) && close(actual.2, expected.2), "{:?}", actual);
+    }
+
+    #[test]
+    fn fits_viewbox_or_geometry() {
+        let square = icon("M8 8H16V16H8Z");
+        assert_eq!(placed(&square, &styles(FitMode::ViewBox)), (16.0, 16.0, 4.0));
+        assert_close(placed(&square, &styles(FitMode::Tight)), (-80.0, -80.0, 12.0));
+        // A square's filled area is 64, so a quarter of the 96x96 area takes a scale of 6.
+        assert_close(placed(&square, &styles(FitMode::VisualArea)), (-8.0, -8.0, 6.0));
+
+        // Unparseable geometry falls back to the viewBox.
+        assert_eq!(placed(&icon("M"), &styles(FitMode::Tight)), (16.0, 16.0, 4.0));
+        assert!((tight_coverage(&square, &styles(FitMode::ViewBox)).unwrap() - 1.0).abs() < 0.01);
+
+        // The viewBox-only functions keep fitting and centering the viewBox.
+        assert_eq!(fit_transform(square.viewbox, &styles(FitMode::Tight)), (16.0, 16.0, 4.0));
+        assert_eq!(calculate_transform(square.viewbox, &CustomStyles::default()), "translate(16, 16) scale(4)");
+        assert_eq!(placement_transform(&square, &styles(FitMode::ViewBox)).unwrap(), "translate(16, 16) scale(4)");
+    }
+
+    #[test]
+    fn rejects_target_coverage_outside_the_unit_interval() {
+        let square = icon("M8 8H16V16H8Z");
+        for target_coverage in [0.0, -0.5, 1.5, f32::NAN] {
+            let styles = CustomStyles { target_coverage, ..styles(FitMode::VisualArea) };
+            assert!(placement_matrix(&square, &styles).is_err());
+        }
+        let full = CustomStyles { target_coverage: 1.0, ..styles(FitMode::VisualArea) };
+        assert!(placement_matrix(&square, &full).is_ok());
+
+        for placement in [
+            Placement { scale: 0.0, ..Placement::default() },
+            Placement { scale: -1.0, ..Placement::default() },
+            Placement { size: Some(0.0), ..Placement::default() },
+            Placement { size: Some(f32::INFINITY), ..Placement::default() },
+        ] {
+            assert!(validate_placement(&CustomStyles { placement, ..CustomStyles::default() }).is_err());
+        }
+    }
+
+    #[test]
+    fn bounds_cover_every_shape_and_stroke() {
+        let icon = parse_svg(
+            r#"<svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+                <defs><path d="M-100 -100H100"/></defs>
+                <path d="M4 12C4 4 20 4 20 12"/>
+                <circle cx="12" cy="16" r="3" stroke="none" fill="black"/>
+                <g transform="translate(2 2)"><rect x="1" y="18" width="4" height="2" rx="1"/></g>
+                <line x1="20" y1="14" x2="20" y2="21"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        let tags: Vec<&str> = icon.elements.iter().map(|e| e.tag.as_str()).collect();
+        assert_eq!(tags, ["path", "circle", "rect", "line"]);
+        let bounds = icon.element_bounds();
+        // The curve peaks at y = 6, a stroke half-width above its extremum.
+        assert_eq!(bounds[0], Some(Rect::new(3.0, 5.0, 21.0, 13.0)));
+        assert_eq!(bounds[1], Some(Rect::new(9.0, 13.0, 15.0, 19.0)));
+        assert_eq!(bounds[2], Some(Rect::new(2.0, 19.0, 8.0, 23.0)));
+        assert_eq!(bounds[3], Some(Rect::new(19.0, 13.0, 21.0, 22.0)));
+        assert_eq!(icon.bounds(), Some(Rect::new(2.0, 5.0, 21.0, 23.0)));
+    }
+
+    #[test]
+    fn reads_style_declarations_and_rejects_malformed_attributes() {
+        let icon = parse_svg(
+            r#"<svg viewBox="0 0 24 24" style="stroke: black">
+                <path d="M4 12H20" stroke-width="8" style="fill:none; stroke-width: 2px !important"/>
+            </svg>"#,
+        )
+        .unwrap();
+        assert_eq!(icon.bounds(), Some(Rect::new(3.0, 11.0, 21.0, 13.0)));
+
+        for shape in [
+            r#"<path d="M4 12H20" transform="skewZ(3)"/>"#,
+            r#"<path d="M4 12H20" stroke="black" stroke-width="thick"/>"#,
+            r#"<g transform="rotate(90"><path d="M4 12H20"/></g>"#,
+        ] {
+            let svg = format!(r#"<svg viewBox="0 0 24 24">{}</svg>"#, shape);
+            assert!(matches!(parse_svg(&svg), Err(IconEngineError::SvgParsingError(_))), "{}", shape);
+        }
+    }
+
+    #[test]
+    fn fits_measure_the_drawn_glyph() {
+        // Only the first path is drawn, as written, so the fit ignores its
+        // group's transform and the circle beside it.
+        let icon = parse_svg(
+            r#"<svg viewBox="0 0 24 24"><g transform="translate(10 10)"><path d="M0 0H4V4H0Z"/></g><circle cx="20" cy="20" r="2"/></svg>"#,
+        )
+        .unwrap();
+        let styles = styles(FitMode::Tight);
+        assert_eq!(placement_transform(&icon, &styles).unwrap(), "translate(16, 16) scale(24)");
+        let fitted = fitted_path_data(&icon, &styles).unwrap();
+        let drawn = geometry::bounds(&parse_path(&fitted).unwrap()).unwrap();
+        assert_eq!(drawn.center(), styles.content_box().center());
+    }
+
+    #[test]
+    fn optical_fit_moves_visual_weight_to_the_center() {
+        let play = icon("M8 5V19L19 12Z");
+        let (tight_x, _, scale) = placed(&play, &styles(FitMode::Tight));
+        let (optical_x, optical_y, optical_scale) = placed(&play, &styles(FitMode::Optical));
+        assert_eq!(scale, optical_scale);
+        // The centroid is left of the bounds' center, so the glyph shifts right
+        // until its tip reaches the padding.
+        assert!(optical_x > tight_x);
+        assert!((optical_x + 19.0 * scale - 112.0).abs() < 0.01);
+        assert!((optical_y + 12.0 * scale - 64.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn placement_aligns_offsets_and_mirrors_the_glyph() {
+        let square = icon("M0 0H24V24H0Z");
+        let placed = |placement: Placement| {
+            glyph_placement(&square, &CustomStyles { placement, ..CustomStyles::default() })
+        };
+        let sized = Placement { size: Some(48.0), ..Placement::default() };
+
+        let top_left = placed(Placement { align: Alignment::TopLeft, ..sized });
+        assert_eq!((top_left.tx, top_left.ty, top_left.scale_x), (16.0, 16.0, 2.0));
+        let bottom_right = placed(Placement { align: Alignment::BottomRight, offset: (5.0, -3.0), ..sized });
+        assert_eq!((bottom_right.tx, bottom_right.ty), (69.0, 61.0));
+        let half = placed(Placement { scale: 0.5, ..Placement::default() });
+        assert_eq!((half.tx, half.ty, half.scale_x), (40.0, 40.0, 2.0));
+
+        let mirrored = placed(Placement { flip_horizontal: true, ..Placement::default() });
+        assert_eq!((mirrored.scale_x, mirrored.scale_y, mirrored.tx), (-4.0, 4.0, 112.0));
+        assert_eq!(mirrored.matrix().apply(Point::new(24.0, 0.0)), Point::new(16.0, 16.0));
+        assert_eq!(
+            placement_transform(&square, &CustomStyles { placement: Placement { flip_horizontal: true, ..Placement::default() }, ..CustomStyles::default() }).unwrap(),
+            "translate(112, 16) rotate(0) scale(-4, 4)"
+        );
+
+        // Other angles shrink the glyph until its rotated box fits.
+        let tilted = placed(Placement { rotation: 45.0, ..Placement::default() });
+        assert!((tilted.scale_x - 4.0 / 2f32.sqrt()).abs() < 1e-3, "{:?}", tilted);
+        let top = tilted.matrix().apply(Point::new(0.0, 0.0));
+        assert!((top.x - 64.0).abs() < 1e-3 && (top.y - 16.0).abs() < 1e-3, "{:?}", top);
+
+        // A quarter turn keeps the rotated glyph within the padded area.
+        let rotated = placed(Placement { rotation: 90.0, ..Placement::default() }).matrix();
+        let corner = rotated.apply(Point::new(0.0, 24.0));
+        assert!((corner.x - 16.0).abs() < 1e-3 && (corner.y - 16.0).abs() < 1e-3, "{:?}", corner);
+    }
+
+    #[test]
+    fn centers_in_an_asymmetric_content_box() {
+        let styles = CustomStyles { insets: Insets::parse("16 40 40 16").ok(), ..CustomStyles::default() };
+        assert_eq!(placed(&icon("M0 0H24V24H0Z"), &styles), (16.0, 16.0, 3.0));
+        let wide = CustomStyles { insets: Insets::parse("16 40 16 16").ok(), ..CustomStyles::default() };
+        assert_eq!(placed(&icon("M0 0H24V24H0Z"), &wide), (16.0, 28.0, 3.0));
+    }
+}
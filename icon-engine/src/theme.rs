@@ -0,0 +1,296 @@
+use crate::color::invert_color_str;
+use crate::contrast::resolve_icon_color;
+use crate::overlay::{OverlayShape, OVERLAY_FILL_CLASS, OVERLAY_LABEL_CLASS};
+use crate::pattern::PATTERN_FILL_CLASS;
+use crate::style_generator::{base_fill, shadow_colors};
+use crate::{generate_icon, CustomStyles, Gradient, IconEngineError, ShadowColors, StylePreset};
+
+const DARK_SHADOW_DARK: &str = "rgba(0,0,0,0.5)";
+const DARK_SHADOW_LIGHT: &str = "rgba(255,255,255,0.08)";
+/// The id of the group wrapping an adaptive icon. The dark rules are scoped
+/// under it, so they leave other icons in the same document alone once
+/// [`crate::sprite::namespace_ids`] has made it unique.
+pub const THEME_ROOT_ID: &str = "adaptive-icon";
+
+/// The color scheme an icon variant is generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// Explicit per-theme colors that take precedence over the derived ones.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeOverrides {
+    pub base_color: Option<String>,
+    pub icon_color: Option<String>,
+    pub shadow_colors: Option<ShadowColors>,
+}
+
+/// Light and dark overrides for a themed generation run.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeConfig {
+    pub light: ThemeOverrides,
+    pub dark: ThemeOverrides,
+}
+
+impl ThemeConfig {
+    /// Resolves the styles for `theme`, deriving the dark theme from the
+    /// overridden light theme.
+    pub fn styles_for(&self, styles: &CustomStyles, preset: StylePreset, theme: Theme) -> CustomStyles {
+        let light_styles = themed_styles(styles, preset, Theme::Light, &self.light);
+        match theme {
+            Theme::Light => light_styles,
+            Theme::Dark => themed_styles(&light_styles, preset, Theme::Dark, &self.dark),
+        }
+    }
+}
+
+/// Produces the styles for one theme.
+///
+/// The light theme is the input styles with the light overrides applied. The
+/// dark theme is derived from it by mirroring the lightness of the base,
+/// gradient stops, pattern, foreground, glyph layers and overlays, and
+/// switching to deeper shadows, before the dark overrides are applied. Glass presets keep their translucent base.
+pub fn themed_styles(
+    styles: &CustomStyles,
+    preset: StylePreset,
+    theme: Theme,
+    overrides: &ThemeOverrides,
+) -> CustomStyles {
+    let mut themed = styles.clone();
+
+    if theme == Theme::Dark {
+        if preset == StylePreset::Neumorphism {
+            themed.base_color = Some(invert_color_str(&base_fill(styles, preset)));
+        }
+        themed.icon_color = invert_color_str(&styles.icon_color);
+        themed.gradient = styles.gradient.as_ref().map(|g| Gradient {
+            angle: g.angle,
+            start_color: invert_color_str(&g.start_color),
+            stop_color: invert_color_str(&g.stop_color),
+        });
+        if let Some(pattern) = &mut themed.pattern {
+            pattern.color = invert_color_str(&pattern.color);
+        }
+        for layer in &mut themed.glyph_layers {
+            if let Some(color) = &mut layer.color {
+                *color = invert_color_str(color);
+            }
+        }
+        for overlay in &mut themed.overlays {
+            overlay.color = invert_color_str(&overlay.color);
+            if let OverlayShape::Badge { text_color, .. } | OverlayShape::Ribbon { text_color, .. } =
+                &mut overlay.shape
+            {
+                *text_color = invert_color_str(text_color);
+            }
+        }
+        themed.shadow_colors = Some(ShadowColors {
+            dark: DARK_SHADOW_DARK.to_string(),
+            light: DARK_SHADOW_LIGHT.to_string(),
+        });
+    }
+
+    if let Some(color) = &overrides.base_color {
+        themed.base_color = Some(color.clone());
+    }
+    if let Some(color) = &overrides.icon_color {
+        themed.icon_color = color.clone();
+    }
+    if let Some(shadows) = &overrides.shadow_colors {
+        themed.shadow_colors = Some(shadows.clone());
+    }
+
+    themed
+}
+
+/// Generates the light and dark variants of an icon in one pass.
+pub fn generate_theme_variants(
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+    config: &ThemeConfig,
+) -> Result<(String, String), IconEngineError> {
+    Ok((
+        generate_icon(icon_data, preset, &config.styles_for(styles, preset, Theme::Light))?,
+        generate_icon(icon_data, preset, &config.styles_for(styles, preset, Theme::Dark))?,
+    ))
+}
+
+/// Generates a single SVG that renders the light variant by default and
+/// switches to the dark variant via `@media (prefers-color-scheme: dark)`.
+pub fn generate_adaptive_icon(
+    icon_data: &str,
+    preset: StylePreset,
+    styles: &CustomStyles,
+    config: &ThemeConfig,
+) -> Result<String, IconEngineError> {
+    let light_styles = config.styles_for(styles, preset, Theme::Light);
+    let dark_styles = config.styles_for(styles, preset, Theme::Dark);
+
+    let svg = generate_icon(icon_data, preset, &light_styles)?;
+    let stylesheet = format!(
+        "<style>@media (prefers-color-scheme: dark){{{}}}</style>",
+        dark_rules(&dark_styles, preset)
+    );
+
+    // The stylesheet goes right after the opening <svg> tag and the content
+    // moves into the root group the rules are scoped under.
+    let open_end = svg.find('>').map(|i| i + 1).ok_or(IconEngineError::Unknown)?;
+    let close_start = svg.rfind("</svg>").ok_or(IconEngineError::Unknown)?;
+    Ok(format!(
+        r#"{}{}<g id="{}">{}</g></svg>"#,
+        &svg[..open_end],
+        stylesheet,
+        THEME_ROOT_ID,
+        &svg[open_end..close_start]
+    ))
+}
+
+/// Builds the CSS rules that restyle the class hooks emitted by the generator,
+/// each scoped under [`THEME_ROOT_ID`].
+fn dark_rules(dark: &CustomStyles, preset: StylePreset) -> String {
+    let mut rules = String::new();
+    let mut rule = |selector: &str, declarations: String| {
+        rules.push_str(&format!("#{} {}{{{}}}", THEME_ROOT_ID, selector, declarations));
+    };
+
+    match &dark.gradient {
+        Some(gradient) => {
+            rule(".gradient-start", format!("stop-color:{}", gradient.start_color));
+            rule(".gradient-stop", format!("stop-color:{}", gradient.stop_color));
+        }
+        None => rule(".icon-base", format!("fill:{}", base_fill(dark, preset))),
+    }
+
+    let shadows = shadow_colors(dark);
+    rule(".shadow-dark", format!("flood-color:{}", shadows.dark));
+    rule(".shadow-light", format!("flood-color:{}", shadows.light));
+    let icon_color = resolve_icon_color(dark, preset);
+    rule(".icon-fg", format!("fill:{}", icon_color));
+    if let Some(pattern) = &dark.pattern {
+        rule(&format!(".{}", PATTERN_FILL_CLASS), format!("fill:{0};flood-color:{0}", pattern.color));
+    }
+    for (index, layer) in dark.glyph_layers.iter().enumerate() {
+        let color = layer.color.as_deref().unwrap_or(&icon_color);
+        rule(&format!(".icon-layer-{}", index + 1), format!("fill:{}", color));
+    }
+    for (index, overlay) in dark.overlays.iter().enumerate() {
+        rule(&format!(".{}-{}", OVERLAY_FILL_CLASS, index + 1), format!("fill:{}", overlay.color));
+        if let OverlayShape::Badge { text_color, .. } | OverlayShape::Ribbon { text_color, .. } = &overlay.shape {
+            rule(&format!(".{}-{}", OVERLAY_LABEL_CLASS, index + 1), format!("fill:{}", text_color));
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::glyph_stack::GlyphLayer;
+    use crate::overlay::Overlay;
+    use crate::Alignment;
+    use crate::pattern::{BasePattern, PatternKind};
+
+    const TEST_SVG: &str = r#"<svg viewBox="0 0 24 24"><path d="M12 2L2 22h20L12 2z"></path></svg>"#;
+
+    #[test]
+    fn dark_variant_inverts_colors() {
+        let styles = CustomStyles::default();
+        let (light, dark) = generate_theme_variants(
+            TEST_SVG,
+            StylePreset::Neumorphism,
+            &styles,
+            &ThemeConfig::default(),
+        )
+        .unwrap();
+
+        assert!(light.contains("fill=\"#e0e0e0\""));
+        assert!(light.contains("fill=\"#333333\""));
+        assert!(dark.contains("fill=\"#1f1f1f\""));
+        assert!(dark.contains("fill=\"#cccccc\""));
+        assert!(dark.contains(&format!("flood-color=\"{}\"", DARK_SHADOW_DARK)));
+    }
+
+    #[test]
+    fn overrides_take_precedence() {
+        let styles = CustomStyles::default();
+        let dark = ThemeOverrides {
+            icon_color: Some("#ff00ff".to_string()),
+            ..Default::default()
+        };
+        let styles = themed_styles(&styles, StylePreset::Neumorphism, Theme::Dark, &dark);
+        assert_eq!(styles.icon_color, "#ff00ff");
+        assert_eq!(styles.base_color.as_deref(), Some("#1f1f1f"));
+    }
+
+    #[test]
+    fn adaptive_icon_embeds_media_query() {
+        let styles = CustomStyles {
+            gradient: Some(Gradient {
+                angle: 90,
+                start_color: "#ffffff".to_string(),
+                stop_color: "#000000".to_string(),
+            }),
+            ..CustomStyles::default()
+        };
+        let svg = generate_adaptive_icon(
+            TEST_SVG,
+            StylePreset::Neumorphism,
+            &styles,
+            &ThemeConfig::default(),
+        )
+        .unwrap();
+
+        assert!(svg.contains("<style>@media (prefers-color-scheme: dark){"));
+        assert!(svg.contains("#adaptive-icon .gradient-start{stop-color:#000000}"));
+        assert!(svg.contains("#adaptive-icon .icon-fg{fill:#cccccc}"));
+        assert!(!svg.contains(".icon-base{"));
+        let doc = roxmltree::Document::parse(&svg).unwrap();
+        let root = doc.descendants().find(|n| n.attribute("id") == Some(THEME_ROOT_ID)).unwrap();
+        assert!(root.descendants().any(|n| n.attribute("class") == Some("icon-fg")));
+    }
+
+    #[test]
+    fn adaptive_icon_restyles_the_pattern() {
+        for kind in [PatternKind::Dots, PatternKind::Noise] {
+            let styles = CustomStyles { pattern: Some(BasePattern::new(kind)), ..CustomStyles::default() };
+            let svg = generate_adaptive_icon(TEST_SVG, StylePreset::Neumorphism, &styles, &ThemeConfig::default())
+                .unwrap();
+            roxmltree::Document::parse(&svg).unwrap();
+            assert!(svg.contains("#adaptive-icon .base-pattern-fill{fill:#ffffff;flood-color:#ffffff}"), "{}", svg);
+            assert!(svg.contains(r#"class="base-pattern-fill""#));
+        }
+    }
+
+    #[test]
+    fn adaptive_icon_restyles_layers_and_overlays() {
+        let styles = CustomStyles {
+            glyph_layers: vec![
+                GlyphLayer { color: Some("#000000".to_string()), ..GlyphLayer::new(TEST_SVG) },
+                GlyphLayer::new(TEST_SVG),
+            ],
+            overlays: vec![Overlay {
+                shape: OverlayShape::Badge { text: "3".to_string(), text_color: "#ffffff".to_string() },
+                color: "#e53935".to_string(),
+                anchor: Alignment::TopRight,
+                size: 32.0,
+                cutout: None,
+            }],
+            ..CustomStyles::default()
+        };
+        let svg = generate_adaptive_icon(TEST_SVG, StylePreset::Neumorphism, &styles, &ThemeConfig::default()).unwrap();
+        roxmltree::Document::parse(&svg).unwrap();
+
+        assert!(svg.contains("#adaptive-icon .icon-layer-1{fill:#ffffff}"), "{}", svg);
+        assert!(svg.contains("#adaptive-icon .icon-layer-2{fill:#cccccc}"));
+        assert!(svg.contains(r#"class="icon-layer icon-layer-2""#));
+        assert!(svg.contains("#adaptive-icon .overlay-label-1{fill:#000000}"));
+        assert!(svg.contains(r#"<text class="overlay-label-1" "#));
+        assert!(svg.contains("#adaptive-icon .overlay-fill-1{fill:"));
+        assert!(svg.contains(r#"class="overlay-fill-1""#));
+    }
+}